@@ -0,0 +1,57 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+// 与 src/transition/material.rs 中的 TransitionParams 一致
+struct TransitionParams {
+    progress: f32,
+    mode: u32,
+    outgoing: u32,
+    block: f32,
+    direction: vec2<f32>,
+    size: vec2<f32>,
+    softness: f32,
+    opacity: f32,
+}
+
+const MODE_WIPE: u32 = 0u;
+const MODE_PIXELLATE: u32 = 1u;
+const MODE_IMAGE_DISSOLVE: u32 = 2u;
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> params: TransitionParams;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var image_texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(2) var image_sampler: sampler;
+@group(#{MATERIAL_BIND_GROUP}) @binding(3) var mask_texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(4) var mask_sampler: sampler;
+
+// 进度为 progress 时，阈值为 threshold 的像素的不透明度（阈值小的先出现）
+fn reveal(threshold: f32, progress: f32, softness: f32) -> f32 {
+    let edge = progress * (1.0 + softness);
+    return 1.0 - smoothstep(edge - softness, edge, threshold);
+}
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // 马赛克：按块取块中心的颜色
+    var uv = mesh.uv;
+    if params.mode == MODE_PIXELLATE && params.block > 1.0 {
+        let cells = max(params.size / params.block, vec2<f32>(1.0));
+        uv = (floor(uv * cells) + 0.5) / cells;
+    }
+    let color = textureSample(image_texture, image_sampler, uv);
+    let mask = textureSample(mask_texture, mask_sampler, mesh.uv);
+
+    var visible = params.opacity;
+    if params.mode == MODE_WIPE {
+        // 沿擦除方向，越靠前的像素阈值越小
+        let threshold = 0.5 + dot(mesh.uv - vec2<f32>(0.5), params.direction);
+        visible = reveal(threshold, params.progress, params.softness);
+    } else if params.mode == MODE_IMAGE_DISSOLVE {
+        // 遮罩越亮越先出现
+        let luminance = dot(mask.rgb, vec3<f32>(0.299, 0.587, 0.114));
+        visible = reveal(1.0 - luminance, params.progress, params.softness);
+    }
+    // 旧画面与新画面互补：新画面出现的部分旧画面消失
+    if params.outgoing == 1u && params.mode != MODE_PIXELLATE {
+        visible = 1.0 - visible;
+    }
+    return vec4<f32>(color.rgb, color.a * visible);
+}
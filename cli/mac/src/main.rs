@@ -57,6 +57,13 @@ enum Commands {
         #[arg(index = 1)]
         project_name: String,
     },
+
+    /// 检查项目的配置与对话脚本
+    Check {
+        /// 项目名称（直接跟在命令后）
+        #[arg(index = 1)]
+        project_name: String,
+    },
 }
 
 fn main() {
@@ -65,6 +72,7 @@ fn main() {
     match cli.command {
         Commands::New { name } => create_project(&name),
         Commands::Run { project_name } => run_project(&project_name),
+        Commands::Check { project_name } => check_project(&project_name),
     }
 }
 
@@ -227,6 +235,24 @@ fn run_project(project_name: &str) {
     }
 }
 
+fn check_project(project_name: &str) {
+    println!("🔍 正在检查项目：{}", project_name);
+    let report = Raven::check::check_project(Path::new(project_name));
+
+    for diagnostic in &report.diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    println!(
+        "检查完成：{} 个错误，{} 个警告",
+        report.error_count(),
+        report.warning_count()
+    );
+
+    if report.has_errors() {
+        std::process::exit(1);
+    }
+}
+
 fn set_executable_permissions(path: &Path) {
     #[cfg(unix)]
     {
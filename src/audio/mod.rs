@@ -1,12 +1,22 @@
 // src/audio/mod.rs
 use bevy::audio::Volume;
 use bevy::prelude::*;
+use bevy::transform::TransformSystems;
 use std::collections::HashMap;
+
+use crate::config::SettingsConfig;
 pub struct AudioPlugin;
 
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<AudioManager>();
+        app.init_resource::<AudioManager>()
+            .init_resource::<AudioMixer>()
+            // 在 bevy 创建 AudioSink 之前写入初始音量，避免第一帧以原音量播放
+            .add_systems(
+                PostUpdate,
+                apply_initial_volume.before(TransformSystems::Propagate),
+            )
+            .add_systems(Update, (update_audio_fades, apply_mixer_volume).chain());
     }
 }
 #[derive(Resource, Default)]
@@ -14,7 +24,255 @@ pub struct AudioManager {
     pub playing_audio: HashMap<String, Entity>,
 }
 
-/// 播放音频文件的简单函数
+/// 混音器通道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioChannel {
+    Bgm,
+    Sfx,
+    Voice,
+    Ui,
+}
+
+impl AudioChannel {
+    pub const ALL: [AudioChannel; 4] = [
+        AudioChannel::Bgm,
+        AudioChannel::Sfx,
+        AudioChannel::Voice,
+        AudioChannel::Ui,
+    ];
+
+    /// 在 settings.volumes / 玩家偏好中使用的键名
+    pub fn key(self) -> &'static str {
+        match self {
+            AudioChannel::Bgm => "bgm",
+            AudioChannel::Sfx => "sfx",
+            AudioChannel::Voice => "voice",
+            AudioChannel::Ui => "ui",
+        }
+    }
+
+    /// 设置界面上显示的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            AudioChannel::Bgm => "背景音乐音量",
+            AudioChannel::Sfx => "音效音量",
+            AudioChannel::Voice => "语音音量",
+            AudioChannel::Ui => "界面音效音量",
+        }
+    }
+
+    /// 设置界面中音量标题的界面文字 id
+    pub fn label_id(self) -> &'static str {
+        match self {
+            AudioChannel::Bgm => "volume.bgm",
+            AudioChannel::Sfx => "volume.sfx",
+            AudioChannel::Voice => "volume.voice",
+            AudioChannel::Ui => "volume.ui",
+        }
+    }
+}
+
+/// 挂在混音器通道上的声音
+///
+/// `volume` 是声音自身的音量（角色语音音量、BGM 被语音压低等），`fade` 是淡入淡出的进度，
+/// 实际音量为两者与通道音量的乘积
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MixerSound {
+    pub channel: AudioChannel,
+    pub volume: f32,
+    pub fade: f32,
+}
+
+impl MixerSound {
+    pub fn new(channel: AudioChannel) -> Self {
+        Self {
+            channel,
+            volume: 1.0,
+            fade: 1.0,
+        }
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+}
+
+/// 把 MixerSound 的 fade 逐渐变到 target，完成后移除（despawn 为 true 时移除整个声音）
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AudioFade {
+    pub target: f32,
+    /// 从 0 到 1 需要的秒数
+    pub seconds: f32,
+    pub despawn: bool,
+}
+
+impl AudioFade {
+    pub fn to(target: f32, seconds: f32) -> Self {
+        Self {
+            target,
+            seconds,
+            despawn: false,
+        }
+    }
+
+    /// 淡出后移除声音
+    pub fn out(seconds: f32) -> Self {
+        Self {
+            target: 0.0,
+            seconds,
+            despawn: true,
+        }
+    }
+}
+
+/// 单个通道的音量与静音
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelMix {
+    /// 0.0 ~ 1.0
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl Default for ChannelMix {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+        }
+    }
+}
+
+/// 混音器：各通道的音量与静音，修改后会立即作用于正在播放的声音
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AudioMixer {
+    channels: HashMap<AudioChannel, ChannelMix>,
+}
+
+impl AudioMixer {
+    /// 按 settings 中的 volumes / muted_channels 设置各通道
+    pub fn from_settings(settings: &SettingsConfig) -> Self {
+        let mut mixer = Self::default();
+        for channel in AudioChannel::ALL {
+            if let Some(volume) = settings.volumes.get(channel.key()) {
+                mixer.set_volume(channel, *volume);
+            }
+            let muted = settings.muted_channels.iter().any(|key| key == channel.key());
+            mixer.set_muted(channel, muted);
+        }
+        mixer
+    }
+
+    pub fn channel(&self, channel: AudioChannel) -> ChannelMix {
+        self.channels.get(&channel).copied().unwrap_or_default()
+    }
+
+    pub fn set_volume(&mut self, channel: AudioChannel, volume: f32) {
+        self.channels.entry(channel).or_default().volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn set_muted(&mut self, channel: AudioChannel, muted: bool) {
+        self.channels.entry(channel).or_default().muted = muted;
+    }
+
+    pub fn toggle_mute(&mut self, channel: AudioChannel) {
+        let muted = self.channel(channel).muted;
+        self.set_muted(channel, !muted);
+    }
+
+    /// 通道的实际音量，静音时为 0
+    pub fn effective_volume(&self, channel: AudioChannel) -> f32 {
+        let mix = self.channel(channel);
+        if mix.muted { 0.0 } else { mix.volume }
+    }
+
+    /// 某个声音的实际音量
+    pub fn sound_volume(&self, sound: &MixerSound) -> f32 {
+        sound.volume.clamp(0.0, 1.0) * sound.fade.clamp(0.0, 1.0) * self.effective_volume(sound.channel)
+    }
+}
+
+/// 淡出并移除一个声音，seconds 为 0 时立即移除
+pub fn fade_out_audio(commands: &mut Commands, entity: Entity, seconds: f32) {
+    if seconds > 0.0 {
+        commands.entity(entity).try_insert(AudioFade::out(seconds));
+    } else {
+        commands.entity(entity).try_despawn();
+    }
+}
+
+/// 循环播放一段音乐并淡入；previous 为正在播放的音乐时同时淡出（交叉淡化）
+pub fn crossfade_music(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    previous: Option<Entity>,
+    audio_path: &str,
+    seconds: f32,
+) -> Entity {
+    if let Some(previous) = previous {
+        fade_out_audio(commands, previous, seconds);
+    }
+    let mut sound = MixerSound::new(AudioChannel::Bgm);
+    let mut entity = commands.spawn((
+        AudioPlayer::new(asset_server.load::<AudioSource>(audio_path.to_string())),
+        PlaybackSettings::LOOP,
+    ));
+    // 从静音开始淡入
+    if seconds > 0.0 {
+        sound.fade = 0.0;
+        entity.insert(AudioFade::to(1.0, seconds));
+    }
+    entity.insert(sound).id()
+}
+
+// 推进淡入淡出
+fn update_audio_fades(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sounds: Query<(Entity, &AudioFade, &mut MixerSound)>,
+) {
+    for (entity, fade, mut sound) in sounds.iter_mut() {
+        let step = if fade.seconds > 0.0 {
+            time.delta_secs() / fade.seconds
+        } else {
+            1.0
+        };
+        sound.fade = if sound.fade < fade.target {
+            (sound.fade + step).min(fade.target)
+        } else {
+            (sound.fade - step).max(fade.target)
+        };
+        if (sound.fade - fade.target).abs() <= f32::EPSILON {
+            if fade.despawn {
+                commands.entity(entity).despawn();
+            } else {
+                commands.entity(entity).remove::<AudioFade>();
+            }
+        }
+    }
+}
+
+// 新生成的声音按混音器设置初始音量
+fn apply_initial_volume(
+    mixer: Res<AudioMixer>,
+    mut sounds: Query<(&MixerSound, &mut PlaybackSettings), Added<MixerSound>>,
+) {
+    for (sound, mut settings) in sounds.iter_mut() {
+        settings.volume = Volume::Linear(mixer.sound_volume(sound));
+    }
+}
+
+// 把混音器的音量同步到正在播放的声音上
+fn apply_mixer_volume(mixer: Res<AudioMixer>, mut sinks: Query<(&MixerSound, &mut AudioSink)>) {
+    for (sound, mut sink) in sinks.iter_mut() {
+        let target = mixer.sound_volume(sound);
+        if (sink.volume().to_linear() - target).abs() > f32::EPSILON {
+            sink.set_volume(Volume::Linear(target));
+        }
+    }
+}
+
+/// 播放音频文件的简单函数（音效通道）
 ///
 /// # 参数
 /// * `commands` - 用于生成音频实体的Commands
@@ -37,12 +295,13 @@ pub fn play_audio(
     commands
         .spawn((
             AudioPlayer::new(asset_server.load(audio_path)),
-            PlaybackSettings::ONCE.with_volume(Volume::Linear(1.0)),
+            PlaybackSettings::ONCE,
+            MixerSound::new(AudioChannel::Sfx),
         ))
         .id() // 添加.id()来返回Entity
 }
 
-/// 播放音频文件并设置音量
+/// 播放音频文件并设置音量（音效通道）
 ///
 /// # 参数
 /// * `commands` - 用于生成音频实体的Commands
@@ -57,11 +316,12 @@ pub fn play_audio_with_volume(
 ) {
     commands.spawn((
         AudioPlayer::new(asset_server.load(audio_path)),
-        PlaybackSettings::ONCE.with_volume(Volume::Linear(volume)),
+        PlaybackSettings::ONCE,
+        MixerSound::new(AudioChannel::Sfx).with_volume(volume),
     ));
 }
 
-/// 循环播放音频文件
+/// 循环播放音频文件（背景音乐通道）
 ///
 /// # 参数
 /// * `commands` - 用于生成音频实体的Commands
@@ -76,7 +336,8 @@ pub fn play_audio_loop(
 ) {
     commands.spawn((
         AudioPlayer::new(asset_server.load(audio_path)),
-        PlaybackSettings::LOOP.with_volume(Volume::Linear(volume)),
+        PlaybackSettings::LOOP,
+        MixerSound::new(AudioChannel::Bgm).with_volume(volume),
     ));
 }
 
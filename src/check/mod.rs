@@ -0,0 +1,510 @@
+// src/check/mod.rs
+// 项目检查（raven check）：运行前校验 main.yaml 与对话脚本，报告文件和行号
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::{MainConfig, VariableValue},
+    easing::Easing,
+    effect::CameraEffect,
+    motion::Motion,
+    script::{
+        Script,
+        dialogue::{BgmChange, StagePosition},
+        load_script,
+        loader::LoadError,
+        markup::parse_color,
+        parse_expression, voice_path,
+    },
+    transition::{Transition, TransitionKind},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 一条检查结果
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "错误",
+            Severity::Warning => "警告",
+        };
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}: {}", self.file.display(), line, level, self.message),
+            None => write!(f, "{}: {}: {}", self.file.display(), level, self.message),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl CheckReport {
+    pub fn error_count(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+
+    fn push(&mut self, severity: Severity, file: &Path, line: Option<usize>, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            file: file.to_path_buf(),
+            line,
+            message,
+        });
+    }
+}
+
+/// 检查项目目录（包含 assets/main.yaml 与对话脚本）
+pub fn check_project(project_dir: &Path) -> CheckReport {
+    let assets_dir = project_dir.join("assets");
+    let config_path = assets_dir.join("main.yaml");
+    let mut report = CheckReport::default();
+
+    let config_str = match fs::read_to_string(&config_path) {
+        Ok(content) => content,
+        Err(e) => {
+            report.push(Severity::Error, &config_path, None, format!("无法读取配置文件: {}", e));
+            return report;
+        }
+    };
+    let config: MainConfig = match serde_yaml::from_str(&config_str) {
+        Ok(config) => config,
+        Err(e) => {
+            let line = e.location().map(|location| location.line());
+            report.push(Severity::Error, &config_path, line, format!("配置文件格式错误: {}", e));
+            return report;
+        }
+    };
+
+    check_asset_files(&config, &config_str, &config_path, &assets_dir, &mut report);
+    check_characters(&config, &config_str, &config_path, &assets_dir, &mut report);
+    check_transforms(&config, &config_str, &config_path, &mut report);
+
+    match load_script(&config, &assets_dir) {
+        Ok(script) => {
+            let expressions = config.character_expressions(&assets_dir);
+            check_script(&script, &config, &assets_dir, &expressions, &mut report)
+        }
+        Err(LoadError::Parse { path, line, message }) => {
+            report.push(Severity::Error, &path, Some(line), format!("脚本格式错误: {}", message));
+        }
+        Err(LoadError::Io { path, source }) => {
+            report.push(Severity::Error, &path, None, format!("无法读取脚本文件: {}", source));
+        }
+        Err(LoadError::IncludeCycle(path)) => {
+            report.push(Severity::Error, &path, None, "脚本文件被循环 include".to_string());
+        }
+    }
+
+    report
+}
+
+// main.yaml 中的资源路径（相对 assets 目录）
+fn asset_path(assets_dir: &Path, path: &str) -> PathBuf {
+    assets_dir.join(path.trim_start_matches("assets/"))
+}
+
+// main.yaml 中同时包含键名和路径的那一行
+fn config_line(config_str: &str, key: &str, path: &str) -> Option<usize> {
+    config_str
+        .lines()
+        .position(|line| line.trim_start().starts_with(&format!("{}:", key)) && line.contains(path))
+        .map(|index| index + 1)
+}
+
+// 检查 main.yaml 中声明的资源文件是否存在
+fn check_asset_files(
+    config: &MainConfig,
+    config_str: &str,
+    config_path: &Path,
+    assets_dir: &Path,
+    report: &mut CheckReport,
+) {
+    let assets = &config.assets;
+    let mut entries: Vec<(&str, &String, String)> = Vec::new();
+    for (name, path) in &assets.backgrounds {
+        entries.push(("backgrounds", name, path.clone()));
+    }
+    for (name, path) in &assets.audio.bgm {
+        entries.push(("audio.bgm", name, path.clone()));
+    }
+    for (name, path) in &assets.audio.sfx {
+        entries.push(("audio.sfx", name, path.clone()));
+    }
+    for (name, path) in &assets.swf {
+        entries.push(("swf", name, path.clone()));
+    }
+    // 角色立绘目录下需要有 default.png
+    for (name, path) in &assets.characters {
+        entries.push(("characters", name, format!("{}/default.png", path)));
+    }
+    for (character, expressions) in &assets.expressions {
+        if !assets.characters.contains_key(character) {
+            report.push(
+                Severity::Error,
+                config_path,
+                config_line(config_str, character, ""),
+                format!("assets.expressions.{} 不是 assets.characters 中的角色", character),
+            );
+        }
+        for (name, path) in expressions {
+            entries.push(("expressions", name, path.clone()));
+        }
+    }
+
+    for (section, name, path) in entries {
+        if !asset_path(assets_dir, &path).exists() {
+            let key_path = path.trim_end_matches("/default.png");
+            report.push(
+                Severity::Error,
+                config_path,
+                config_line(config_str, name, key_path),
+                format!("assets.{}.{} 指向的文件 '{}' 不存在", section, name, path),
+            );
+        }
+    }
+
+    for (key, path) in [
+        ("click_sound", &assets.audio.click_sound),
+        ("backclick_sound", &assets.audio.backclick_sound),
+    ] {
+        if !asset_path(assets_dir, path).exists() {
+            report.push(
+                Severity::Warning,
+                config_path,
+                config_line(config_str, key, path),
+                format!("assets.audio.{} 指向的文件 '{}' 不存在", key, path),
+            );
+        }
+    }
+}
+
+// 检查角色表中的颜色和名字框图片
+fn check_characters(
+    config: &MainConfig,
+    config_str: &str,
+    config_path: &Path,
+    assets_dir: &Path,
+    report: &mut CheckReport,
+) {
+    for (id, character) in &config.characters {
+        let colors = [
+            ("color", &character.color),
+            ("text_color", &character.text_color),
+            ("namebox.tint", &character.namebox.tint),
+        ];
+        for (key, value) in colors {
+            if let Some(value) = value {
+                if parse_color(value).is_none() {
+                    report.push(
+                        Severity::Error,
+                        config_path,
+                        config_line(config_str, key.rsplit('.').next().unwrap_or(key), value),
+                        format!("characters.{}.{} 的颜色 '{}' 无效（应为 #rrggbb 格式）", id, key, value),
+                    );
+                }
+            }
+        }
+        if let Some(image) = &character.namebox.image {
+            if !asset_path(assets_dir, image).exists() {
+                report.push(
+                    Severity::Error,
+                    config_path,
+                    config_line(config_str, "image", image),
+                    format!("characters.{}.namebox.image 指向的文件 '{}' 不存在", id, image),
+                );
+            }
+        }
+    }
+}
+
+// 检查 main.yaml 中命名的变换动画
+fn check_transforms(
+    config: &MainConfig,
+    config_str: &str,
+    config_path: &Path,
+    report: &mut CheckReport,
+) {
+    for (name, source) in &config.transforms {
+        if let Err(e) = Motion::parse(source) {
+            report.push(
+                Severity::Error,
+                config_path,
+                config_line(config_str, name, ""),
+                format!("transforms.{} 无效: {}", name, e),
+            );
+        }
+    }
+}
+
+// 检查脚本中的跳转、条件和资源引用
+fn check_script(
+    script: &Script,
+    config: &MainConfig,
+    assets_dir: &Path,
+    expressions: &HashMap<String, HashMap<String, String>>,
+    report: &mut CheckReport,
+) {
+    let assets = &config.assets;
+
+    for (index, dialogue) in script.dialogues.iter().enumerate() {
+        let origin = &script.origins[index];
+        for key in dialogue.unknown.keys() {
+            report.push(
+                Severity::Warning,
+                &origin.file,
+                Some(origin.line),
+                format!("无法识别的键 '{}'，运行时会被忽略", key),
+            );
+        }
+        let mut error = |message: String| {
+            report.push(Severity::Error, &origin.file, Some(origin.line), message);
+        };
+
+        if let Some(jump) = &dialogue.jump {
+            if !script.labels.contains_key(jump.label()) {
+                error(format!("jump 目标标签 '{}' 不存在", jump.label()));
+            }
+            if let Some(condition) = jump.condition() {
+                check_expression(condition, "jump 条件", &mut error);
+            }
+        }
+
+        for choice in dialogue.choices.iter().flatten() {
+            match choice.goto.resolve(&script.labels) {
+                Some(line) if line < script.dialogues.len() => {}
+                Some(line) => error(format!(
+                    "选项 '{}' 的 goto 行号 {} 超出范围（共 {} 行）",
+                    choice.text,
+                    line,
+                    script.dialogues.len()
+                )),
+                None => error(format!(
+                    "选项 '{}' 的 goto 目标 '{}' 不存在",
+                    choice.text, choice.goto
+                )),
+            }
+            if let Some(condition) = &choice.condition {
+                check_expression(condition, "选项条件", &mut error);
+            }
+            for value in choice.set.iter().flat_map(|set| set.values()) {
+                check_set_value(value, &mut error);
+            }
+        }
+
+        if let Some(condition) = &dialogue.condition {
+            check_expression(condition, "if 条件", &mut error);
+        }
+        for value in dialogue.set.iter().flat_map(|set| set.values()) {
+            check_set_value(value, &mut error);
+        }
+
+        if dialogue.portrait != "none" && !assets.characters.contains_key(&dialogue.portrait) {
+            error(format!(
+                "立绘 '{}' 未在 main.yaml 的 assets.characters 中定义",
+                dialogue.portrait
+            ));
+        } else if let Some(expression) = &dialogue.expression {
+            check_expression_name(&dialogue.portrait, expression, expressions, &mut error);
+        }
+        for show in dialogue.show.iter().flatten() {
+            if !assets.characters.contains_key(&show.character) {
+                error(format!(
+                    "show 的角色 '{}' 未在 main.yaml 的 assets.characters 中定义",
+                    show.character
+                ));
+            } else if let Some(expression) = &show.expression {
+                check_expression_name(&show.character, expression, expressions, &mut error);
+            }
+            if let Some(transform) = &show.transform {
+                check_transform(transform, &config.transforms, &mut error);
+            }
+            if let Some(at) = &show.at {
+                if at.x_fraction().is_none() {
+                    error(format!(
+                        "角色 '{}' 的位置 '{}' 无效（可用: {}，或表示画面宽度比例的数字）",
+                        show.character,
+                        at,
+                        StagePosition::NAMES.join(" / ")
+                    ));
+                }
+            }
+        }
+        for hide in dialogue.hide.iter().flatten() {
+            if hide != "all" && !assets.characters.contains_key(hide) {
+                error(format!(
+                    "hide 的角色 '{}' 未在 main.yaml 的 assets.characters 中定义",
+                    hide
+                ));
+            }
+        }
+        if let Some(background) = &dialogue.background {
+            if !assets.backgrounds.contains_key(background) {
+                error(format!(
+                    "背景 '{}' 未在 main.yaml 的 assets.backgrounds 中定义",
+                    background
+                ));
+            }
+        }
+        if let Some(transition) = &dialogue.transition {
+            match Transition::parse(transition) {
+                None => error(format!(
+                    "转场 '{}' 无效（可用: {}；缓动: {}，例如 dissolve 0.8、wipe left 1.0 ease_out_cubic、imagedissolve gui/mask.png）",
+                    transition,
+                    Transition::NAMES.join(" / "),
+                    Easing::NAMES.join(" / ")
+                )),
+                Some(Transition {
+                    kind: TransitionKind::ImageDissolve(mask),
+                    ..
+                }) if !asset_path(assets_dir, &mask).exists() => {
+                    error(format!("转场的遮罩图片 '{}' 不存在", mask));
+                }
+                Some(_) => {}
+            }
+        }
+        for effect in dialogue.effect.iter().flat_map(|effect| effect.sources()) {
+            if CameraEffect::parse(effect).is_none() {
+                error(format!(
+                    "镜头效果 '{}' 无效（可用: {}）",
+                    effect,
+                    CameraEffect::NAMES.join(" / ")
+                ));
+            }
+        }
+        if let Some(transform) = &dialogue.transform {
+            check_transform(transform, &config.transforms, &mut error);
+        }
+        if let BgmChange::Play(bgm) = dialogue.bgm_change() {
+            if !assets.audio.bgm.contains_key(bgm) {
+                error(format!("BGM '{}' 未在 main.yaml 的 assets.audio.bgm 中定义", bgm));
+            }
+        }
+        if dialogue.bgm_fade.is_some_and(|fade| fade < 0.0) {
+            error("bgm_fade 不能为负数".to_string());
+        }
+        for cue in dialogue.sfx.iter().flat_map(|sfx| sfx.cues()) {
+            if !assets.audio.sfx.contains_key(cue.sound()) {
+                error(format!(
+                    "音效 '{}' 未在 main.yaml 的 assets.audio.sfx 中定义",
+                    cue.sound()
+                ));
+            }
+            if cue.delay() < 0.0 || !(0.0..=1.0).contains(&cue.volume()) {
+                error(format!(
+                    "音效 '{}' 的 delay 不能为负数，volume 应在 0.0 ~ 1.0 之间",
+                    cue.sound()
+                ));
+            }
+        }
+        if let Some(voice) = &dialogue.voice {
+            let path = voice_path(config, &dialogue.character, voice);
+            if !asset_path(assets_dir, &path).exists() {
+                error(format!("语音文件 '{}' 不存在", path));
+            }
+        }
+        if let Some(swf) = &dialogue.swf {
+            if !assets.swf.contains_key(swf) {
+                error(format!("SWF '{}' 未在 main.yaml 的 assets.swf 中定义", swf));
+            }
+        }
+    }
+
+    for duplicate in &script.duplicate_labels {
+        let origin = &script.origins[duplicate.duplicate];
+        report.push(
+            Severity::Error,
+            &origin.file,
+            Some(origin.line),
+            format!(
+                "标签 '{}' 重复定义（首次定义于 {}）",
+                duplicate.label,
+                script.describe_line(duplicate.first)
+            ),
+        );
+    }
+}
+
+// 变换动画可以是 transforms 中的名称，也可以直接写出
+fn check_transform(
+    source: &str,
+    named: &HashMap<String, String>,
+    error: &mut impl FnMut(String),
+) {
+    // 命名的变换动画在 check_transforms 中检查
+    if named.contains_key(source.trim()) {
+        return;
+    }
+    if let Err(e) = Motion::parse(source) {
+        error(format!(
+            "变换动画 '{}' 无效: {}（可以写 main.yaml transforms 中的名称）",
+            source, e
+        ));
+    }
+}
+
+// 表情需要是角色目录中的图片，或在 assets.expressions 中声明
+fn check_expression_name(
+    character: &str,
+    expression: &str,
+    expressions: &HashMap<String, HashMap<String, String>>,
+    error: &mut impl FnMut(String),
+) {
+    let known = expressions.get(character);
+    if !known.is_some_and(|known| known.contains_key(expression)) {
+        let mut names: Vec<&String> = known.into_iter().flat_map(|known| known.keys()).collect();
+        names.sort();
+        error(format!(
+            "角色 '{}' 没有表情 '{}'（可用: {}）",
+            character,
+            expression,
+            names.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(" / ")
+        ));
+    }
+}
+
+fn check_expression(source: &str, what: &str, error: &mut impl FnMut(String)) {
+    if let Err(e) = parse_expression(source) {
+        error(format!("{} '{}' 无法解析: {}", what, source, e));
+    }
+}
+
+// `set:` 中以 `=` 开头的值按表达式处理
+fn check_set_value(value: &VariableValue, error: &mut impl FnMut(String)) {
+    if let VariableValue::String(s) = value {
+        if let Some(source) = s.trim().strip_prefix('=') {
+            check_expression(source, "set 表达式", error);
+        }
+    }
+}
@@ -1,6 +1,12 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, env, fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+pub mod prefs;
 
 #[derive(Resource, Deserialize, Serialize, Clone)]
 pub struct MainConfig {
@@ -9,6 +15,48 @@ pub struct MainConfig {
     pub settings: SettingsConfig,
     pub global_variables: GlobalVariables,
     pub variables: HashMap<String, VariableValue>, // 简化为键值对
+    #[serde(default)]
+    pub script: Vec<String>, // 脚本文件清单（相对 assets 目录，按顺序合并）
+    #[serde(default)]
+    pub characters: HashMap<String, CharacterConfig>, // 角色表：脚本中的 character 写角色 id
+    #[serde(default)]
+    pub languages: Vec<LanguageConfig>, // 可选语言，第一项为脚本原文的语言；翻译位于 assets/locales/<id>/
+    #[serde(default)]
+    pub transforms: HashMap<String, String>, // 命名的变换动画，脚本中的 transform 可以直接写名称
+}
+
+// 设置界面中可选的语言
+#[derive(Deserialize, Serialize, Clone)]
+pub struct LanguageConfig {
+    pub id: String,   // 语言 id，也是翻译目录名，例如 "en"
+    pub name: String, // 按钮上显示的名称，例如 "English"
+}
+
+// 角色的显示设置，未设置的项使用界面默认值
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct CharacterConfig {
+    #[serde(default)]
+    pub name: Option<String>, // 显示名称（可以包含 {变量}），未设置时显示角色 id
+    #[serde(default)]
+    pub color: Option<String>, // 名字颜色，例如 "#c2ffcc"
+    #[serde(default)]
+    pub text_color: Option<String>, // 对话文字颜色
+    #[serde(default)]
+    pub expression: Option<String>, // 默认表情（立绘未写表情时使用）
+    #[serde(default)]
+    pub voice: Option<String>, // 语音标签：语音文件位于 voice/<标签>/，也用于 settings.voice_volumes
+    #[serde(default)]
+    pub namebox: NameboxStyle, // 名字框样式
+}
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct NameboxStyle {
+    #[serde(default)]
+    pub image: Option<String>, // 名字框背景图片
+    #[serde(default)]
+    pub tint: Option<String>, // 背景图片的颜色
+    #[serde(default)]
+    pub font_size: Option<f32>,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -18,8 +66,13 @@ pub struct AssetsConfig {
     pub audio: AudioConfig,
     pub videos: HashMap<String, String>,
     pub swf: HashMap<String, String>,
+    #[serde(default)]
+    pub expressions: HashMap<String, HashMap<String, String>>, // 角色表情：角色 -> 表情名 -> 图片路径（补充角色目录中的图片）
 }
 
+// 可作为角色表情的图片格式
+const EXPRESSION_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
 #[derive(Deserialize, Serialize, Clone)]
 pub struct AudioConfig {
     pub bgm: HashMap<String, String>,
@@ -39,6 +92,42 @@ pub struct SettingsConfig {
     pub logo_text: String,
     pub resizable: bool,     // 是否允许改变窗口大小
     pub maximizable: bool,   // 是否允许最大化按钮
+    #[serde(default)]
+    pub skip_unread: bool,   // 快进时是否跳过未读文本
+    #[serde(default)]
+    pub bold_font: Option<String>,   // {b} 标签使用的字体（未设置时沿用正文字体）
+    #[serde(default)]
+    pub italic_font: Option<String>, // {i} 标签使用的字体（未设置时沿用正文字体）
+    #[serde(default)]
+    pub voice_volumes: HashMap<String, f32>, // 各角色的语音音量（键为角色的语音标签），0.0 ~ 1.0
+    #[serde(default = "default_voice_ducking")]
+    pub voice_ducking: f32, // 播放语音时 BGM 降低到的音量比例
+    #[serde(default = "default_bgm_fade")]
+    pub bgm_fade: f32, // 切换 / 停止 BGM 时的淡出淡入秒数（0 为直接切换），对话行可用 bgm_fade 覆盖
+    #[serde(default = "default_auto_play_interval")]
+    pub auto_play_interval: f32, // 自动播放时每行停留的秒数
+    #[serde(default)]
+    pub fullscreen: bool, // 是否以全屏启动
+    #[serde(default)]
+    pub window_size: Option<[u32; 2]>, // 窗口模式下的窗口大小，未设置时为 resolution
+    #[serde(default)]
+    pub language: Option<String>, // 界面与对话的语言，未设置时使用脚本原文
+    #[serde(default)]
+    pub volumes: HashMap<String, f32>, // 各音量通道（bgm / sfx / voice / ui）的默认音量，0.0 ~ 1.0
+    #[serde(default)]
+    pub muted_channels: Vec<String>, // 静音的音量通道
+}
+
+fn default_voice_ducking() -> f32 {
+    0.4
+}
+
+fn default_bgm_fade() -> f32 {
+    1.0
+}
+
+fn default_auto_play_interval() -> f32 {
+    2.0
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -128,6 +217,7 @@ impl Default for MainConfig {
                 },
                 videos: HashMap::new(),
                 swf: HashMap::new(),
+                expressions: HashMap::new(),
             },
             settings: SettingsConfig {
                 initial_scene: "intro".to_string(),
@@ -139,12 +229,28 @@ impl Default for MainConfig {
                 rewind: false,
                 resizable: true,      // 默认允许调整大小
                 maximizable: true,    // 默认允许最大化
+                skip_unread: false,   // 默认只快进已读文本
+                bold_font: None,
+                italic_font: None,
+                voice_volumes: HashMap::new(),
+                voice_ducking: default_voice_ducking(),
+                bgm_fade: default_bgm_fade(),
+                auto_play_interval: default_auto_play_interval(),
+                fullscreen: false,
+                window_size: None,
+                language: None,
+                volumes: HashMap::new(),
+                muted_channels: Vec::new(),
             },
             global_variables: GlobalVariables {
                 player_name: "主角".to_string(),
                 affection_points: 0,
             },
-            variables: default_variables
+            variables: default_variables,
+            script: Vec::new(),
+            characters: HashMap::new(),
+            languages: Vec::new(),
+            transforms: HashMap::new(),
         }
     }
 }
@@ -162,10 +268,45 @@ impl MainConfig {
         self.assets.characters.get(character)
     }
     
+    // 角色的显示设置；不在角色表中的角色（例如直接写名字的旧脚本）返回 None
+    pub fn get_character(&self, character: &str) -> Option<&CharacterConfig> {
+        self.characters.get(character)
+    }
+
     pub fn get_background_path(&self, background: &str) -> Option<&String> {
         self.assets.backgrounds.get(background)
     }
+
+    // 角色的全部表情：角色目录中的每张图片（文件名即表情名，default 为默认表情），
+    // 再加上 assets.expressions 中声明的表情；路径相对 assets 目录
+    pub fn character_expressions(&self, assets_dir: &Path) -> HashMap<String, HashMap<String, String>> {
+        let mut expressions: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for (character, dir) in &self.assets.characters {
+            let dir = dir.trim_start_matches("assets/").replace('\\', "/");
+            expressions.insert(character.clone(), scan_expressions(assets_dir, &dir, ""));
+        }
+        for (character, map) in &self.assets.expressions {
+            let entries = expressions.entry(character.clone()).or_default();
+            for (expression, path) in map {
+                entries.insert(expression.clone(), path.trim_start_matches("assets/").to_string());
+            }
+        }
+        expressions
+    }
     
+    // 脚本原文的语言（languages 的第一项）
+    pub fn source_language(&self) -> Option<&LanguageConfig> {
+        self.languages.first()
+    }
+
+    // 当前语言的 id：settings.language，未设置时为原文的语言
+    pub fn current_language(&self) -> Option<&str> {
+        self.settings
+            .language
+            .as_deref()
+            .or_else(|| self.source_language().map(|language| language.id.as_str()))
+    }
+
     // 获取窗口是否可调整大小
     pub fn is_resizable(&self) -> bool {
         self.settings.resizable
@@ -177,7 +318,46 @@ impl MainConfig {
     }
 }
 
+/// 目录中的表情图片：表情名 -> 路径（相对 assets 目录）
+///
+/// 只取文件名以 prefix 开头的图片，表情名为去掉 prefix 后的文件名；
+/// prefix 为空时目录中的每张图片都是一个表情，为 `"sylvie green "` 时读取 `sylvie green smile.png` 这样的图片
+pub fn scan_expressions(assets_dir: &Path, dir: &str, prefix: &str) -> HashMap<String, String> {
+    let mut expressions = HashMap::new();
+    let Ok(read_dir) = fs::read_dir(assets_dir.join(dir)) else {
+        return expressions;
+    };
+    for path in read_dir.flatten().map(|entry| entry.path()) {
+        let is_image = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| EXPRESSION_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        if let (true, Some(stem), Some(file_name)) = (
+            is_image,
+            path.file_stem().and_then(|stem| stem.to_str()),
+            path.file_name().and_then(|name| name.to_str()),
+        ) {
+            if let Some(expression) = stem.strip_prefix(prefix).filter(|name| !name.is_empty()) {
+                let path = if dir.is_empty() {
+                    file_name.to_string()
+                } else {
+                    format!("{}/{}", dir, file_name)
+                };
+                expressions.insert(expression.to_string(), path);
+            }
+        }
+    }
+    expressions
+}
+
+/// 读取 main.yaml，并用玩家偏好覆盖其中的 settings
 pub fn load_main_config() -> MainConfig {
+    let mut config = load_project_config();
+    prefs::read_user_prefs(&config.title).apply(&mut config.settings);
+    config
+}
+
+fn load_project_config() -> MainConfig {
     let exe_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
     let yaml_path = exe_dir.join("assets/main.yaml");
     
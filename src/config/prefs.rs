@@ -0,0 +1,132 @@
+// src/config/prefs.rs
+// 玩家偏好：保存在系统的配置目录（与 main.yaml 分开），启动时覆盖到 settings 上
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, env, fs, path::PathBuf};
+use thiserror::Error;
+
+use super::SettingsConfig;
+
+/// 偏好文件名
+const PREFS_FILE: &str = "prefs.yaml";
+
+/// 玩家在设置界面中修改过的项，未修改的项沿用 main.yaml 的 settings
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserPrefs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_speed: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_play_interval: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_unread: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fullscreen: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_size: Option<[u32; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// 各音量通道的音量，按通道覆盖
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub volumes: HashMap<String, f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muted_channels: Option<Vec<String>>,
+}
+
+impl UserPrefs {
+    /// 用偏好覆盖 settings
+    pub fn apply(&self, settings: &mut SettingsConfig) {
+        if let Some(text_speed) = self.text_speed {
+            settings.text_speed = text_speed;
+        }
+        if let Some(interval) = self.auto_play_interval {
+            settings.auto_play_interval = interval;
+        }
+        if let Some(skip_unread) = self.skip_unread {
+            settings.skip_unread = skip_unread;
+        }
+        if let Some(fullscreen) = self.fullscreen {
+            settings.fullscreen = fullscreen;
+        }
+        if let Some(window_size) = self.window_size {
+            settings.window_size = Some(window_size);
+        }
+        if let Some(language) = &self.language {
+            settings.language = Some(language.clone());
+        }
+        for (channel, volume) in &self.volumes {
+            settings.volumes.insert(channel.clone(), volume.clamp(0.0, 1.0));
+        }
+        if let Some(muted) = &self.muted_channels {
+            settings.muted_channels = muted.clone();
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PrefsError {
+    #[error("找不到系统的配置目录")]
+    NoConfigDir,
+    #[error("读写偏好文件失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("偏好文件格式错误: {0}")]
+    Format(#[from] serde_yaml::Error),
+}
+
+/// 系统的配置目录：Windows 为 %APPDATA%，macOS 为 ~/Library/Application Support，
+/// 其他系统为 $XDG_CONFIG_HOME 或 ~/.config
+fn platform_config_dir() -> Option<PathBuf> {
+    let home = || env::var_os("HOME").map(PathBuf::from);
+    if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|home| home.join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home().map(|home| home.join(".config")))
+    }
+}
+
+/// 偏好文件所在目录，按游戏标题区分
+pub fn prefs_dir(title: &str) -> Option<PathBuf> {
+    let name: String = title
+        .trim()
+        .chars()
+        .map(|c| if r#"/\:*?"<>|"#.contains(c) { '_' } else { c })
+        .collect();
+    let name = if name.is_empty() { "Raven".to_string() } else { name };
+    platform_config_dir().map(|dir| dir.join(name))
+}
+
+/// 读取玩家偏好，文件不存在或损坏时返回空偏好
+pub fn read_user_prefs(title: &str) -> UserPrefs {
+    let Some(path) = prefs_dir(title).map(|dir| dir.join(PREFS_FILE)) else {
+        return UserPrefs::default();
+    };
+    if !path.exists() {
+        return UserPrefs::default();
+    }
+    let result = fs::read_to_string(&path)
+        .map_err(PrefsError::from)
+        .and_then(|yaml| serde_yaml::from_str(&yaml).map_err(PrefsError::from));
+    match result {
+        Ok(prefs) => {
+            println!("已读取玩家偏好: {:?}", path);
+            prefs
+        }
+        Err(e) => {
+            eprintln!("读取玩家偏好失败: {}，使用默认设置", e);
+            UserPrefs::default()
+        }
+    }
+}
+
+/// 写入玩家偏好
+pub fn write_user_prefs(title: &str, prefs: &UserPrefs) -> Result<(), PrefsError> {
+    let dir = prefs_dir(title).ok_or(PrefsError::NoConfigDir)?;
+    fs::create_dir_all(&dir)?;
+    let yaml = serde_yaml::to_string(prefs)?;
+    fs::write(dir.join(PREFS_FILE), yaml)?;
+    Ok(())
+}
@@ -0,0 +1,211 @@
+// src/display/mod.rs
+// 显示设置：窗口 / 全屏切换（设置界面按钮、Alt+Enter、F11）与窗口大小预设，修改后记录到偏好
+use bevy::{
+    prelude::*,
+    window::{MonitorSelection, PrimaryWindow, WindowMode},
+};
+
+use crate::{
+    config::{MainConfig, prefs::UserPrefs},
+    locale::{Locale, LocalizedText},
+    style::StyleUpdateTrigger,
+};
+
+/// 窗口大小预设相对 settings.resolution 的比例
+const RESOLUTION_SCALES: [f32; 4] = [0.75, 1.0, 1.25, 1.5];
+
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+
+/// 显示设置按钮
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum DisplayButton {
+    Windowed,
+    Fullscreen,
+    /// 在窗口与全屏之间切换，按钮文字显示开启 / 关闭
+    ToggleFullscreen,
+    /// 窗口模式下的窗口大小
+    Resolution([u32; 2]),
+}
+
+impl DisplayButton {
+    pub fn is_selected(self, config: &MainConfig) -> bool {
+        match self {
+            DisplayButton::Windowed => !config.settings.fullscreen,
+            DisplayButton::Fullscreen | DisplayButton::ToggleFullscreen => {
+                config.settings.fullscreen
+            }
+            DisplayButton::Resolution(size) => window_size(config) == size,
+        }
+    }
+}
+
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_fullscreen_hotkey,
+                handle_display_buttons,
+                apply_display_settings,
+                update_display_buttons,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// 由 settings.resolution 按比例得到的窗口大小预设
+pub fn resolution_presets(config: &MainConfig) -> Vec<[u32; 2]> {
+    let [width, height] = config.settings.resolution;
+    RESOLUTION_SCALES
+        .iter()
+        .map(|scale| {
+            [
+                (width as f32 * scale).round() as u32,
+                (height as f32 * scale).round() as u32,
+            ]
+        })
+        .collect()
+}
+
+/// 窗口模式下的窗口大小：玩家选择的预设，未选择时为 settings.resolution
+pub fn window_size(config: &MainConfig) -> [u32; 2] {
+    config.settings.window_size.unwrap_or(config.settings.resolution)
+}
+
+/// settings.fullscreen 对应的窗口模式
+pub fn window_mode(fullscreen: bool) -> WindowMode {
+    if fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    }
+}
+
+/// 本帧是否按下了切换全屏的快捷键（Alt+Enter 或 F11）
+pub fn fullscreen_hotkey_pressed(keys: &ButtonInput<KeyCode>) -> bool {
+    let alt = keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+    keys.just_pressed(KeyCode::F11) || (alt && keys.just_pressed(KeyCode::Enter))
+}
+
+/// 窗口大小按钮上的文字，例如 `1400×770`
+pub fn resolution_label(size: [u32; 2]) -> String {
+    format!("{}×{}", size[0], size[1])
+}
+
+/// 显示设置按钮的文字颜色
+pub fn display_color(selected: bool) -> Color {
+    if selected { SELECTED_COLOR } else { Color::WHITE }
+}
+
+fn toggle_fullscreen_hotkey(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut config: ResMut<MainConfig>,
+    mut prefs: Option<ResMut<UserPrefs>>,
+) {
+    if fullscreen_hotkey_pressed(&keys) {
+        let fullscreen = !config.settings.fullscreen;
+        set_fullscreen(&mut config, prefs.as_deref_mut(), fullscreen);
+    }
+}
+
+// 点击显示设置按钮：修改 settings 并记录到偏好
+fn handle_display_buttons(
+    mut config: ResMut<MainConfig>,
+    mut prefs: Option<ResMut<UserPrefs>>,
+    buttons: Query<(&Interaction, &DisplayButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            DisplayButton::Windowed => set_fullscreen(&mut config, prefs.as_deref_mut(), false),
+            DisplayButton::Fullscreen => set_fullscreen(&mut config, prefs.as_deref_mut(), true),
+            DisplayButton::ToggleFullscreen => {
+                let fullscreen = !config.settings.fullscreen;
+                set_fullscreen(&mut config, prefs.as_deref_mut(), fullscreen);
+            }
+            DisplayButton::Resolution(size) => {
+                if window_size(&config) != size {
+                    println!("窗口大小: {}", resolution_label(size));
+                    config.settings.window_size = Some(size);
+                    if let Some(prefs) = prefs.as_deref_mut() {
+                        prefs.window_size = Some(size);
+                    }
+                }
+                // 在全屏时选择窗口大小视为切换回窗口模式
+                set_fullscreen(&mut config, prefs.as_deref_mut(), false);
+            }
+        }
+    }
+}
+
+fn set_fullscreen(config: &mut MainConfig, prefs: Option<&mut UserPrefs>, fullscreen: bool) {
+    if config.settings.fullscreen == fullscreen {
+        return;
+    }
+    println!("显示模式: {}", if fullscreen { "全屏" } else { "窗口" });
+    config.settings.fullscreen = fullscreen;
+    if let Some(prefs) = prefs {
+        prefs.fullscreen = Some(fullscreen);
+    }
+}
+
+// 显示设置变化后调整主窗口，并重新计算媒体查询样式
+//
+// 只在 fullscreen / window_size 变化时调整，玩家手动拖动的窗口大小不会被还原
+fn apply_display_settings(
+    config: Res<MainConfig>,
+    mut applied: Local<Option<(bool, [u32; 2])>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut trigger: ResMut<StyleUpdateTrigger>,
+) {
+    let display = (config.settings.fullscreen, window_size(&config));
+    if *applied == Some(display) {
+        return;
+    }
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
+    let (fullscreen, [width, height]) = display;
+    let mode = window_mode(fullscreen);
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    if !fullscreen
+        && (window.resolution.width() != width as f32
+            || window.resolution.height() != height as f32)
+    {
+        window.resolution.set(width as f32, height as f32);
+    }
+    *applied = Some(display);
+    trigger.force_update = true;
+}
+
+// 当前的显示设置高亮显示；全屏开关的文字随之切换
+fn update_display_buttons(
+    config: Res<MainConfig>,
+    locale: Res<Locale>,
+    buttons: Query<(&DisplayButton, &Children)>,
+    mut texts: Query<(&mut TextColor, &mut Text, Option<&mut LocalizedText>)>,
+) {
+    if !config.is_changed() && !locale.is_changed() {
+        return;
+    }
+    for (button, children) in buttons.iter() {
+        let selected = button.is_selected(&config);
+        for child in children.iter() {
+            let Ok((mut color, mut text, localized)) = texts.get_mut(child) else {
+                continue;
+            };
+            color.0 = display_color(selected);
+            if let (DisplayButton::ToggleFullscreen, Some(mut localized)) = (button, localized) {
+                localized.0 = if selected { "common.on" } else { "common.off" };
+                text.0 = locale.ui(localized.0).to_string();
+            }
+        }
+    }
+}
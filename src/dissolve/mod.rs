@@ -1,6 +1,8 @@
 
 use bevy::prelude::*;
 
+use crate::easing::Easing;
+
 // ================== Ren'Py Dissolve 组件 ==================
 
 #[derive(Component)]
@@ -9,6 +11,7 @@ pub struct RenpyDissolve {
     pub elapsed: f32,
     pub from_alpha: f32,
     pub to_alpha: f32,
+    pub easing: Easing,
     pub completed: bool,
 }
 
@@ -19,6 +22,7 @@ impl RenpyDissolve {
             elapsed: 0.0,
             from_alpha: 0.0,
             to_alpha: 1.0,
+            easing: Easing::RenPy,
             completed: false,
         }
     }
@@ -29,9 +33,15 @@ impl RenpyDissolve {
             elapsed: 0.0,
             from_alpha: 1.0,
             to_alpha: 0.0,
+            easing: Easing::RenPy,
             completed: false,
         }
     }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
 }
 
 #[derive(Component)]
@@ -40,35 +50,12 @@ pub struct RenpyDissolveTransition {
     pub elapsed: f32,
     pub old_entity: Entity,
     pub new_entity: Entity,
+    pub easing: Easing,
     pub completed: bool,
 }
 
 // ================== 核心算法 ==================
 
-// Ren'Py 风格的缓动函数
-fn renpy_dissolve_ease(t: f32) -> f32 {
-    let t = t.clamp(0.0, 1.0);
-    
-    if t <= 0.1 {
-        // 前10%非常慢，几乎感觉不到变化
-        let local_t = t / 0.1;
-        let smooth_t = local_t * local_t * (3.0 - 2.0 * local_t); // smoothstep
-        smooth_t * 0.05
-    } else if t <= 0.8 {
-        // 中间70%相对线性但稍有加速
-        let local_t = (t - 0.1) / 0.7;
-        // 轻微的二次缓动
-        let eased_t = local_t * local_t * (3.0 - 2.0 * local_t);
-        0.05 + eased_t * 0.85
-    } else {
-        // 最后20%快速完成
-        let local_t = (t - 0.8) / 0.2;
-        // 更激进的缓动
-        let eased_t = 1.0 - (1.0 - local_t) * (1.0 - local_t);
-        0.9 + eased_t * 0.1
-    }
-}
-
 // 精确的颜色混合
 fn precise_alpha_blend(base_alpha: f32, target_alpha: f32, progress: f32) -> f32 {
     let blended = base_alpha + (target_alpha - base_alpha) * progress;
@@ -98,7 +85,7 @@ pub fn renpy_dissolve_system(
             commands.entity(entity).remove::<RenpyDissolve>();
         } else {
             let progress = dissolve.elapsed / dissolve.duration;
-            let eased_progress = renpy_dissolve_ease(progress);
+            let eased_progress = dissolve.easing.apply(progress);
             
             let current_alpha = precise_alpha_blend(
                 dissolve.from_alpha, 
@@ -138,7 +125,7 @@ pub fn renpy_dissolve_transition_system(
             commands.entity(entity).despawn();
         } else {
             let progress = transition.elapsed / transition.duration;
-            let eased_progress = renpy_dissolve_ease(progress);
+            let eased_progress = transition.easing.apply(progress);
             
             if let Ok(mut old_sprite) = sprite_query.get_mut(transition.old_entity) {
                 let alpha = precise_alpha_blend(1.0, 0.0, eased_progress);
@@ -0,0 +1,373 @@
+// src/easing/mod.rs
+// 缓动曲线：转场、渐变、动作都可以在脚本里按名称指定，例如 `ease_out_cubic`、`cubic_bezier(0.25, 0.1, 0.25, 1.0)`
+use std::f32::consts::PI;
+
+/// 缓动的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EaseMode {
+    /// 先慢后快
+    In,
+    /// 先快后慢
+    Out,
+    /// 两头慢中间快
+    InOut,
+}
+
+/// 缓动曲线的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    Quad,
+    Cubic,
+    Sine,
+    Expo,
+    /// 超出终点后回弹
+    Back,
+    /// 弹簧般振荡
+    Elastic,
+    /// 落地弹跳
+    Bounce,
+}
+
+impl Curve {
+    const ALL: [(Curve, &'static str); 7] = [
+        (Curve::Quad, "quad"),
+        (Curve::Cubic, "cubic"),
+        (Curve::Sine, "sine"),
+        (Curve::Expo, "expo"),
+        (Curve::Back, "back"),
+        (Curve::Elastic, "elastic"),
+        (Curve::Bounce, "bounce"),
+    ];
+
+    // In 方向的曲线，其余方向由它对称得到
+    fn ease_in(self, t: f32) -> f32 {
+        match self {
+            Curve::Quad => t * t,
+            Curve::Cubic => t * t * t,
+            Curve::Sine => 1.0 - (t * PI / 2.0).cos(),
+            Curve::Expo => {
+                if t <= 0.0 {
+                    0.0
+                } else {
+                    2.0_f32.powf(10.0 * t - 10.0)
+                }
+            }
+            Curve::Back => {
+                const C1: f32 = 1.70158;
+                (C1 + 1.0) * t * t * t - C1 * t * t
+            }
+            Curve::Elastic => {
+                if t <= 0.0 || t >= 1.0 {
+                    t
+                } else {
+                    -(2.0_f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+                }
+            }
+            Curve::Bounce => 1.0 - bounce_out(1.0 - t),
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+/// 缓动：把 0.0 ~ 1.0 的时间进度映射为动画进度（back / elastic 会短暂超出范围）
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    Curve(Curve, EaseMode),
+    /// Ren'Py 溶解使用的分段曲线：开头很慢、中段近似线性、结尾快速收尾
+    RenPy,
+    /// 与 CSS 相同的三次贝塞尔曲线，控制点为 (x1, y1)、(x2, y2)，x 需在 0 ~ 1 之间
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing {
+    /// 名称的写法（curve 为 quad / cubic / sine / expo / back / elastic / bounce）
+    pub const NAMES: [&'static str; 6] = [
+        "linear",
+        "ease_in_<curve>",
+        "ease_out_<curve>",
+        "ease_in_out_<curve>",
+        "renpy",
+        "cubic_bezier(x1, y1, x2, y2)",
+    ];
+
+    /// 按名称解析；`-` 与 `_` 等价，大小写不敏感，无法识别时返回 None
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        // 参数里的 `-` 是负号，只有名称部分把 `-` 当作 `_`
+        if let Some(args) = name
+            .strip_prefix("cubic_bezier(")
+            .or_else(|| name.strip_prefix("cubic-bezier("))
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            let values: Vec<f32> = args
+                .split(',')
+                .map(|value| value.trim().parse::<f32>())
+                .collect::<Result<_, _>>()
+                .ok()?;
+            let [x1, y1, x2, y2] = values[..] else {
+                return None;
+            };
+            // y 可以超出 0 ~ 1（回弹），但 nan / inf 会让进度无效
+            if !(0.0..=1.0).contains(&x1)
+                || !(0.0..=1.0).contains(&x2)
+                || !y1.is_finite()
+                || !y2.is_finite()
+            {
+                return None;
+            }
+            return Some(Easing::CubicBezier(x1, y1, x2, y2));
+        }
+        let name = name.replace('-', "_");
+        match name.as_str() {
+            "linear" => return Some(Easing::Linear),
+            "renpy" => return Some(Easing::RenPy),
+            _ => {}
+        }
+        // 先匹配 in_out，否则会被当作 in 加上名为 out_xxx 的曲线
+        let (mode, curve) = if let Some(curve) = name.strip_prefix("ease_in_out_") {
+            (EaseMode::InOut, curve)
+        } else if let Some(curve) = name.strip_prefix("ease_in_") {
+            (EaseMode::In, curve)
+        } else if let Some(curve) = name.strip_prefix("ease_out_") {
+            (EaseMode::Out, curve)
+        } else {
+            return None;
+        };
+        let (curve, _) = Curve::ALL.into_iter().find(|(_, name)| *name == curve)?;
+        Some(Easing::Curve(curve, mode))
+    }
+
+    /// 计算进度为 t（会限制在 0.0 ~ 1.0）时的动画进度
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::Curve(curve, EaseMode::In) => curve.ease_in(t),
+            Easing::Curve(curve, EaseMode::Out) => 1.0 - curve.ease_in(1.0 - t),
+            Easing::Curve(curve, EaseMode::InOut) => {
+                if t < 0.5 {
+                    curve.ease_in(t * 2.0) / 2.0
+                } else {
+                    1.0 - curve.ease_in((1.0 - t) * 2.0) / 2.0
+                }
+            }
+            Easing::RenPy => renpy(t),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(t, x1, y1, x2, y2),
+        }
+    }
+}
+
+fn renpy(t: f32) -> f32 {
+    if t <= 0.1 {
+        // 前10%非常慢，几乎感觉不到变化
+        let local_t = t / 0.1;
+        let smooth_t = local_t * local_t * (3.0 - 2.0 * local_t); // smoothstep
+        smooth_t * 0.05
+    } else if t <= 0.8 {
+        // 中间70%相对线性但稍有加速
+        let local_t = (t - 0.1) / 0.7;
+        let eased_t = local_t * local_t * (3.0 - 2.0 * local_t);
+        0.05 + eased_t * 0.85
+    } else {
+        // 最后20%快速完成
+        let local_t = (t - 0.8) / 0.2;
+        let eased_t = 1.0 - (1.0 - local_t) * (1.0 - local_t);
+        0.9 + eased_t * 0.1
+    }
+}
+
+// 先由 x 反解出曲线参数 s（牛顿迭代，失败时二分），再求 y
+fn cubic_bezier(x: f32, x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
+    let bezier = |s: f32, p1: f32, p2: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+    };
+    let slope = |s: f32, p1: f32, p2: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * p1 + 6.0 * inv * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+    };
+
+    let mut s = x;
+    for _ in 0..8 {
+        let error = bezier(s, x1, x2) - x;
+        if error.abs() < 1e-5 {
+            return bezier(s, y1, y2);
+        }
+        let d = slope(s, x1, x2);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        s = (s - error / d).clamp(0.0, 1.0);
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    s = x;
+    for _ in 0..32 {
+        if bezier(s, x1, x2) < x {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+    bezier(s, y1, y2)
+}
+
+/// 按空白分词，括号内的空白不分开（`cubic_bezier(0.25, 0.1, 0.25, 1.0)`、`[120, -40]` 各为一个词）
+pub fn split_words(source: &str) -> Vec<String> {
+    split_words_with(source, &[])
+}
+
+/// 与 split_words 相同，括号外的 separators 单独成为一个词（变换动画用来切分 `{`、`}`、`;` 与换行）
+pub fn split_words_with(source: &str, separators: &[char]) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0usize;
+    for c in source.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            c if depth == 0 && separators.contains(&c) => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                words.push(c.to_string());
+                continue;
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(Easing::parse("linear"), Some(Easing::Linear));
+        assert_eq!(
+            Easing::parse("Ease-In-Out-Sine"),
+            Some(Easing::Curve(Curve::Sine, EaseMode::InOut))
+        );
+        assert_eq!(
+            Easing::parse("ease_out_bounce"),
+            Some(Easing::Curve(Curve::Bounce, EaseMode::Out))
+        );
+        assert_eq!(Easing::parse("ease_in_wobble"), None);
+        assert_eq!(Easing::parse("cubic"), None);
+    }
+
+    #[test]
+    fn parse_cubic_bezier() {
+        assert_eq!(
+            Easing::parse("cubic_bezier(0.25, 0.1, 0.25, 1.0)"),
+            Some(Easing::CubicBezier(0.25, 0.1, 0.25, 1.0))
+        );
+        // y 可以超出 0 ~ 1，也可以写成 CSS 的 cubic-bezier
+        assert_eq!(
+            Easing::parse("cubic-bezier(0.3, -0.5, 0.7, 1.5)"),
+            Some(Easing::CubicBezier(0.3, -0.5, 0.7, 1.5))
+        );
+        for source in [
+            "cubic_bezier(1.2, 0, 0.5, 1)",
+            "cubic_bezier(0.2, nan, 0.8, 1)",
+            "cubic_bezier(0.2, 0, 0.8, inf)",
+            "cubic_bezier(0.2, 0, 0.8)",
+            "cubic_bezier(0.2, 0, 0.8, 1",
+        ] {
+            assert_eq!(Easing::parse(source), None, "{}", source);
+        }
+    }
+
+    #[test]
+    fn curves_start_at_zero_and_end_at_one() {
+        let mut easings = vec![
+            Easing::Linear,
+            Easing::RenPy,
+            Easing::CubicBezier(0.3, -0.5, 0.7, 1.5),
+        ];
+        for (curve, _) in Curve::ALL {
+            for mode in [EaseMode::In, EaseMode::Out, EaseMode::InOut] {
+                easings.push(Easing::Curve(curve, mode));
+            }
+        }
+        for easing in easings {
+            assert!(close(easing.apply(0.0), 0.0), "{:?}", easing);
+            assert!(close(easing.apply(1.0), 1.0), "{:?}", easing);
+            // 超出范围的进度会被限制
+            assert!(close(easing.apply(2.0), 1.0), "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn modes_are_mirrored() {
+        let t = 0.3;
+        let ease_in = Easing::Curve(Curve::Cubic, EaseMode::In).apply(t);
+        let ease_out = Easing::Curve(Curve::Cubic, EaseMode::Out).apply(1.0 - t);
+        assert!(close(ease_in, 0.027));
+        assert!(close(ease_out, 1.0 - ease_in));
+        assert!(close(Easing::Curve(Curve::Quad, EaseMode::InOut).apply(0.5), 0.5));
+    }
+
+    #[test]
+    fn cubic_bezier_solves_for_x() {
+        // 控制点在对角线上时为线性
+        for x in [0.1, 0.5, 0.9] {
+            assert!(close(cubic_bezier(x, 1.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0), x));
+        }
+        // CSS 的 ease 在 0.5 处约为 0.8024
+        assert!(close(cubic_bezier(0.5, 0.25, 0.1, 0.25, 1.0), 0.8024));
+        // 起点斜率为 0，牛顿迭代失败时改用二分
+        for x in [0.001, 0.2, 0.7] {
+            assert!(close(cubic_bezier(x, 0.0, 0.0, 0.0, 0.0), x));
+        }
+    }
+
+    #[test]
+    fn split_words_keeps_brackets_together() {
+        assert_eq!(
+            split_words("zoom 1.3 to [120, -40]  cubic_bezier(0.25, 0.1, 0.25, 1.0)"),
+            ["zoom", "1.3", "to", "[120, -40]", "cubic_bezier(0.25, 0.1, 0.25, 1.0)"]
+        );
+        assert_eq!(
+            split_words_with("repeat {a;b}\n", &['{', '}', ';', '\n']),
+            ["repeat", "{", "a", ";", "b", "}", "\n"]
+        );
+        // 多余的右括号不会让深度变为负数
+        assert_eq!(split_words("a) b"), ["a)", "b"]);
+    }
+}
@@ -0,0 +1,466 @@
+// src/effect/mod.rs
+// 镜头效果：震动、缩放、平移、闪光，作用于游戏镜头（背景、立绘等世界坐标中的精灵，不影响界面）
+// 写法例如 `shake 0.4 strength 12`、`zoom 1.3 to [120, -40] over 2s`、`pan left 3s`、`flash white`
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    easing::{Curve, EaseMode, Easing, split_words},
+    script::markup::parse_color,
+    transition::{Direction, FadeType, TransitionPlugin, TransitionSettings, create_fade_overlay},
+};
+
+/// 一个镜头效果
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraEffect {
+    /// 震动，幅度随时间衰减
+    Shake { duration: f32, strength: f32 },
+    /// 缩放到 scale 倍，focus 为画面中心移到的位置（像素，y 轴向上），省略时保持当前中心
+    Zoom {
+        scale: f32,
+        focus: Option<Vec2>,
+        duration: f32,
+        easing: Easing,
+    },
+    /// 镜头沿方向移动 distance 像素
+    Pan {
+        direction: Direction,
+        distance: f32,
+        duration: f32,
+        easing: Easing,
+    },
+    /// 全屏闪一下
+    Flash { color: Color, duration: f32 },
+    /// 恢复原来的缩放与位置
+    Reset { duration: f32, easing: Easing },
+}
+
+impl CameraEffect {
+    /// 省略秒数时震动的时长
+    pub const DEFAULT_SHAKE_DURATION: f32 = 0.5;
+    /// 省略 strength 时震动的幅度（像素）
+    pub const DEFAULT_SHAKE_STRENGTH: f32 = 10.0;
+    /// 省略秒数时缩放、平移、复位的时长
+    pub const DEFAULT_MOTION_DURATION: f32 = 1.0;
+    /// 省略 by 时平移的距离（像素）
+    pub const DEFAULT_PAN_DISTANCE: f32 = 200.0;
+    /// 省略秒数时闪光的时长
+    pub const DEFAULT_FLASH_DURATION: f32 = 0.3;
+    /// 省略缓动时镜头移动两头缓、中间快
+    pub const DEFAULT_EASING: Easing = Easing::Curve(Curve::Sine, EaseMode::InOut);
+
+    /// 效果的写法
+    pub const NAMES: [&'static str; 5] = [
+        "shake [秒数] [strength 像素]",
+        "zoom 倍数 [to [x, y]] [over 秒数] [缓动]",
+        "pan left|right|up|down [秒数] [by 像素] [缓动]",
+        "flash [white|black|red|#rrggbb] [秒数]",
+        "reset [秒数] [缓动]",
+    ];
+
+    /// 解析一个效果，写法无效时返回 None；秒数可以写成 `2` 或 `2s`
+    pub fn parse(source: &str) -> Option<Self> {
+        let words = split_words(source);
+        let mut words = words.iter().map(String::as_str);
+        let name = words.next()?;
+
+        let mut numbers = Vec::new();
+        let mut strength = None;
+        let mut focus = None;
+        let mut over = None;
+        let mut distance = None;
+        let mut easing = None;
+        let mut direction = None;
+        let mut color = None;
+        while let Some(word) = words.next() {
+            match word {
+                "strength" => strength = Some(parse_number(words.next()?)?),
+                "to" => focus = Some(parse_point(words.next()?)?),
+                "over" => over = Some(parse_seconds(words.next()?)?),
+                "by" => distance = Some(parse_number(words.next()?)?),
+                word => {
+                    if let Some(number) = parse_seconds(word) {
+                        numbers.push(number);
+                    } else if let Some(parsed) = Easing::parse(word) {
+                        easing = Some(parsed);
+                    } else if let Some(parsed) = Direction::parse(word) {
+                        direction = Some(parsed);
+                    } else if name == "flash" && color.is_none() {
+                        color = Some(Self::parse_color(word)?);
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        }
+        if numbers.iter().chain(&over).chain(&strength).any(|n| *n < 0.0) {
+            return None;
+        }
+
+        let easing = easing.unwrap_or(Self::DEFAULT_EASING);
+        let effect = match (name, numbers.as_slice()) {
+            ("shake", [] | [_]) => CameraEffect::Shake {
+                duration: numbers.first().copied().unwrap_or(Self::DEFAULT_SHAKE_DURATION),
+                strength: strength.unwrap_or(Self::DEFAULT_SHAKE_STRENGTH),
+            },
+            ("zoom", [scale] | [scale, _]) if *scale > 0.0 => CameraEffect::Zoom {
+                scale: *scale,
+                focus,
+                duration: over
+                    .or(numbers.get(1).copied())
+                    .unwrap_or(Self::DEFAULT_MOTION_DURATION),
+                easing,
+            },
+            ("pan", [] | [_]) => CameraEffect::Pan {
+                direction: direction?,
+                distance: distance.unwrap_or(Self::DEFAULT_PAN_DISTANCE),
+                duration: over
+                    .or(numbers.first().copied())
+                    .unwrap_or(Self::DEFAULT_MOTION_DURATION),
+                easing,
+            },
+            ("flash", [] | [_]) => CameraEffect::Flash {
+                color: color.unwrap_or(Color::WHITE),
+                duration: numbers.first().copied().unwrap_or(Self::DEFAULT_FLASH_DURATION),
+            },
+            ("reset", [] | [_]) => CameraEffect::Reset {
+                duration: over
+                    .or(numbers.first().copied())
+                    .unwrap_or(Self::DEFAULT_MOTION_DURATION),
+                easing,
+            },
+            _ => return None,
+        };
+        // 只有对应的效果才能写这些参数
+        let misplaced = match &effect {
+            CameraEffect::Shake { .. } => focus.is_some() || over.is_some() || distance.is_some(),
+            CameraEffect::Zoom { .. } => strength.is_some() || distance.is_some(),
+            CameraEffect::Pan { .. } => strength.is_some() || focus.is_some(),
+            CameraEffect::Flash { .. } => {
+                strength.is_some() || focus.is_some() || distance.is_some() || over.is_some()
+            }
+            CameraEffect::Reset { .. } => strength.is_some() || focus.is_some() || distance.is_some(),
+        };
+        let direction_allowed = matches!(effect, CameraEffect::Pan { .. });
+        if misplaced || (direction.is_some() && !direction_allowed) {
+            return None;
+        }
+        Some(effect)
+    }
+
+    /// 解析脚本中写的效果，写法无效时提示并忽略
+    pub fn parse_or_warn(source: &str) -> Option<Self> {
+        let effect = Self::parse(source);
+        if effect.is_none() {
+            eprintln!(
+                "无效的镜头效果 '{}'（可用: {}），已忽略",
+                source,
+                Self::NAMES.join(" / ")
+            );
+        }
+        effect
+    }
+
+    /// 闪光的颜色：white / black / red 或 `#rrggbb`
+    pub fn parse_color(word: &str) -> Option<Color> {
+        match word {
+            "white" => Some(Color::WHITE),
+            "black" => Some(Color::BLACK),
+            "red" => Some(Color::srgb(1.0, 0.0, 0.0)),
+            _ => parse_color(word).map(|[r, g, b, a]| Color::srgba(r, g, b, a)),
+        }
+    }
+}
+
+// nan / inf 视为无效
+fn parse_number(word: &str) -> Option<f32> {
+    word.parse::<f32>().ok().filter(|number| number.is_finite())
+}
+
+/// 秒数：`2` 或 `2s`（变换动画的写法也使用）；nan / inf 返回 None，否则效果永远不会结束
+pub(crate) fn parse_seconds(word: &str) -> Option<f32> {
+    let seconds: f32 = word.strip_suffix('s').unwrap_or(word).parse().ok()?;
+    seconds.is_finite().then_some(seconds)
+}
+
+/// 坐标：`[x, y]`
+pub(crate) fn parse_point(word: &str) -> Option<Vec2> {
+    let inner = word.strip_prefix('[')?.strip_suffix(']')?;
+    let (x, y) = inner.split_once(',')?;
+    let point = Vec2::new(x.trim().parse().ok()?, y.trim().parse().ok()?);
+    point.is_finite().then_some(point)
+}
+
+/// 镜头的缩放与中心位置（存档、回退时保存）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraView {
+    pub zoom: f32,
+    /// 画面中心的位置（像素，y 轴向上）
+    pub focus: [f32; 2],
+}
+
+impl Default for CameraView {
+    fn default() -> Self {
+        CameraView {
+            zoom: 1.0,
+            focus: [0.0, 0.0],
+        }
+    }
+}
+
+impl CameraView {
+    fn lerp(self, to: CameraView, t: f32) -> CameraView {
+        let focus = Vec2::from(self.focus).lerp(Vec2::from(to.focus), t);
+        CameraView {
+            zoom: self.zoom + (to.zoom - self.zoom) * t,
+            focus: focus.into(),
+        }
+    }
+}
+
+struct CameraMotion {
+    from: CameraView,
+    duration: f32,
+    elapsed: f32,
+    easing: Easing,
+}
+
+struct Shake {
+    duration: f32,
+    elapsed: f32,
+    strength: f32,
+}
+
+/// 镜头效果的状态
+#[derive(Resource, Default)]
+pub struct CameraEffects {
+    /// 缩放、平移结束后的镜头
+    view: CameraView,
+    motion: Option<CameraMotion>,
+    shake: Option<Shake>,
+}
+
+impl CameraEffects {
+    /// 缩放、平移结束后的镜头
+    pub fn view(&self) -> CameraView {
+        self.view
+    }
+
+    /// 停止所有效果，镜头直接切换到 view（回退、读档时）
+    pub fn restore(&mut self, view: CameraView) {
+        self.view = view;
+        self.motion = None;
+        self.shake = None;
+    }
+
+    /// 开始一个效果；快进时缩放、平移直接到位，震动被忽略
+    pub fn play(&mut self, commands: &mut Commands, effect: &CameraEffect) {
+        match *effect {
+            CameraEffect::Shake { duration, strength } => {
+                self.shake = Some(Shake {
+                    duration,
+                    elapsed: 0.0,
+                    strength,
+                });
+            }
+            CameraEffect::Zoom {
+                scale,
+                focus,
+                duration,
+                easing,
+            } => {
+                let focus = focus.map_or(self.view.focus, Vec2::into);
+                self.move_to(CameraView { zoom: scale, focus }, duration, easing);
+            }
+            CameraEffect::Pan {
+                direction,
+                distance,
+                duration,
+                easing,
+            } => {
+                let focus = Vec2::from(self.view.focus) + Vec2::from(direction.vector()) * distance;
+                let view = CameraView {
+                    focus: focus.into(),
+                    ..self.view
+                };
+                self.move_to(view, duration, easing);
+            }
+            CameraEffect::Flash { color, duration } => {
+                create_fade_overlay(commands, FadeType::Flash(color), duration, Easing::Linear);
+            }
+            CameraEffect::Reset { duration, easing } => {
+                self.move_to(CameraView::default(), duration, easing);
+            }
+        }
+    }
+
+    fn move_to(&mut self, view: CameraView, duration: f32, easing: Easing) {
+        // 从当前实际的位置开始，上一个动作尚未结束时不会跳动
+        let from = self.current_view();
+        self.view = view;
+        self.motion = (duration > 0.0).then_some(CameraMotion {
+            from,
+            duration,
+            elapsed: 0.0,
+            easing,
+        });
+    }
+
+    fn current_view(&self) -> CameraView {
+        match &self.motion {
+            Some(motion) => motion
+                .from
+                .lerp(self.view, motion.easing.apply(motion.elapsed / motion.duration)),
+            None => self.view,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.motion.is_some() || self.shake.is_some()
+    }
+}
+
+pub struct EffectPlugin;
+
+impl Plugin for EffectPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TransitionPlugin>() {
+            app.add_plugins(TransitionPlugin);
+        }
+        app.init_resource::<CameraEffects>()
+            .add_systems(Update, update_camera_effects);
+    }
+}
+
+// 推进效果并写入镜头的位置与缩放
+fn update_camera_effects(
+    time: Res<Time>,
+    settings: Res<TransitionSettings>,
+    mut effects: ResMut<CameraEffects>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    if !effects.is_active() && !effects.is_changed() {
+        return;
+    }
+    let delta = time.delta_secs();
+    if settings.instant {
+        effects.motion = None;
+        effects.shake = None;
+    }
+    if let Some(motion) = &mut effects.motion {
+        motion.elapsed += delta;
+        if motion.elapsed >= motion.duration {
+            effects.motion = None;
+        }
+    }
+    let mut offset = Vec2::ZERO;
+    if let Some(shake) = &mut effects.shake {
+        shake.elapsed += delta;
+        if shake.elapsed >= shake.duration {
+            effects.shake = None;
+        } else {
+            let remaining = 1.0 - shake.elapsed / shake.duration;
+            let jitter = Vec2::new(rand::random::<f32>(), rand::random::<f32>()) * 2.0 - 1.0;
+            offset = jitter * shake.strength * remaining;
+        }
+    }
+
+    let view = effects.current_view();
+    for mut transform in cameras.iter_mut() {
+        let focus = Vec2::from(view.focus) + offset;
+        transform.translation.x = focus.x;
+        transform.translation.y = focus.y;
+        transform.scale = Vec3::new(1.0 / view.zoom, 1.0 / view.zoom, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shake_strength_and_zoom_focus() {
+        assert_eq!(
+            CameraEffect::parse("shake 0.4 strength 12"),
+            Some(CameraEffect::Shake {
+                duration: 0.4,
+                strength: 12.0
+            })
+        );
+        assert_eq!(
+            CameraEffect::parse("zoom 1.3 to [120, -40] over 2s"),
+            Some(CameraEffect::Zoom {
+                scale: 1.3,
+                focus: Some(Vec2::new(120.0, -40.0)),
+                duration: 2.0,
+                easing: CameraEffect::DEFAULT_EASING,
+            })
+        );
+        // 第二个数字与 over 一样是秒数
+        assert_eq!(
+            CameraEffect::parse("zoom 2 1.5 linear"),
+            Some(CameraEffect::Zoom {
+                scale: 2.0,
+                focus: None,
+                duration: 1.5,
+                easing: Easing::Linear,
+            })
+        );
+    }
+
+    #[test]
+    fn pan_needs_a_direction() {
+        assert_eq!(
+            CameraEffect::parse("pan left 3s by 50"),
+            Some(CameraEffect::Pan {
+                direction: Direction::Left,
+                distance: 50.0,
+                duration: 3.0,
+                easing: CameraEffect::DEFAULT_EASING,
+            })
+        );
+        assert_eq!(CameraEffect::parse("pan 3s"), None);
+    }
+
+    #[test]
+    fn flash_colors() {
+        let color = |source| match CameraEffect::parse(source) {
+            Some(CameraEffect::Flash { color, .. }) => Some(color),
+            _ => None,
+        };
+        assert_eq!(color("flash"), Some(Color::WHITE));
+        assert_eq!(color("flash black 0.5"), Some(Color::BLACK));
+        assert_eq!(color("flash #ff0000"), Some(Color::srgba(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(color("flash purple"), None);
+    }
+
+    #[test]
+    fn rejects_negative_and_non_finite_numbers() {
+        for source in [
+            "shake -1",
+            "shake nan",
+            "shake inf",
+            "shake 0.4 strength nan",
+            "zoom 0",
+            "zoom 1.3 over inf",
+            "zoom 1.3 to [nan, 0]",
+            "pan left by inf",
+        ] {
+            assert_eq!(CameraEffect::parse(source), None, "{}", source);
+        }
+    }
+
+    #[test]
+    fn rejects_options_of_other_effects() {
+        for source in [
+            "shake 0.4 to [1, 2]",
+            "shake left",
+            "pan left strength 3",
+            "flash white over 1",
+            "reset 1 2",
+            "explode",
+        ] {
+            assert_eq!(CameraEffect::parse(source), None, "{}", source);
+        }
+    }
+}
@@ -0,0 +1,530 @@
+// src/game/backlog.rs
+// 历史记录（Backlog）：记录已显示的对话，可滚动查看并回退到任意一条
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    ui::FocusPolicy,
+};
+
+use super::{
+    SettingsMenu, rollback::RollbackToMessage, set_click_area_enabled, voice::PlayVoiceMessage,
+};
+use crate::{
+    GameScene, config::MainConfig, game::save_load::SlotMenuState, locale::Locale,
+    toolbar::OpenHistoryEventMessage,
+};
+
+const BACKLOG_FONT: &str = "fonts/GenSenMaruGothicTW-Bold.ttf";
+
+// 每行滚轮对应的像素
+const WHEEL_LINE_HEIGHT: f32 = 40.0;
+
+/// 一条历史记录
+#[derive(Debug, Clone)]
+pub struct BacklogEntry {
+    /// 对话所在的行
+    pub line: usize,
+    pub character: String,
+    pub text: String,
+    /// 在这一行做出的选择
+    pub choice: Option<String>,
+    /// 这一行的语音，可在历史记录中重新播放
+    pub voice: Option<PlayVoiceMessage>,
+    /// 对应的回退历史下标
+    pub history_index: usize,
+}
+
+/// 已显示过的对话
+#[derive(Resource, Default)]
+pub struct Backlog {
+    pub entries: Vec<BacklogEntry>,
+}
+
+impl Backlog {
+    /// 记录在当前行做出的选择
+    pub fn record_choice(&mut self, line: usize, choice: &str) {
+        if let Some(entry) = self.entries.last_mut() {
+            if entry.line == line {
+                entry.choice = Some(choice.to_string());
+            }
+        }
+    }
+}
+
+/// 历史记录界面是否打开
+#[derive(Resource, Default)]
+pub struct BacklogState {
+    pub open: bool,
+}
+
+#[derive(Component)]
+struct BacklogRoot;
+
+#[derive(Component)]
+struct BacklogScroll;
+
+#[derive(Component)]
+struct BacklogScrollbar;
+
+#[derive(Component)]
+struct BacklogThumb;
+
+#[derive(Component)]
+struct BacklogEntryButton(usize);
+
+#[derive(Component)]
+struct BacklogVoiceButton(usize);
+
+#[derive(Component)]
+struct CloseBacklogButton;
+
+pub struct BacklogPlugin;
+
+impl Plugin for BacklogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Backlog>()
+            .init_resource::<BacklogState>()
+            .add_systems(
+                Update,
+                (
+                    open_backlog,
+                    scroll_backlog.after(open_backlog),
+                    update_scrollbar_images,
+                    handle_backlog_entries,
+                    handle_voice_buttons,
+                    handle_close_backlog,
+                )
+                    .run_if(in_state(GameScene::Game)),
+            )
+            .add_systems(OnExit(GameScene::Game), cleanup_backlog);
+    }
+}
+
+fn open_backlog(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    mut history_events: MessageReader<OpenHistoryEventMessage>,
+    mut wheel_events: MessageReader<MouseWheel>,
+    mut backlog_state: ResMut<BacklogState>,
+    backlog: Res<Backlog>,
+    slot_menu: Res<SlotMenuState>,
+    mut settings_menu: Query<&mut Visibility, With<SettingsMenu>>,
+    mut named_query: Query<(&Name, &mut Visibility, &mut FocusPolicy), Without<SettingsMenu>>,
+) {
+    let requested = history_events.read().count() > 0;
+    // 没有其他界面打开时，滚轮向上也会打开历史记录
+    let settings_open = settings_menu
+        .iter()
+        .any(|visibility| *visibility == Visibility::Visible);
+    let wheel_up = !backlog_state.open
+        && !settings_open
+        && slot_menu.mode.is_none()
+        && wheel_events.read().any(|event| event.y > 0.0);
+
+    if backlog_state.open || !(requested || wheel_up) {
+        return;
+    }
+
+    // 从设置菜单打开时先关闭设置菜单
+    for mut visibility in settings_menu.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+
+    spawn_backlog(&mut commands, &asset_server, &locale, &backlog);
+    backlog_state.open = true;
+    set_click_area_enabled(&mut named_query, false);
+}
+
+// 处理滚轮滚动，并根据滚动位置更新滚动条
+fn scroll_backlog(
+    mut wheel_events: MessageReader<MouseWheel>,
+    backlog_state: Res<BacklogState>,
+    mut scroll_query: Query<(&mut ScrollPosition, &ComputedNode), With<BacklogScroll>>,
+    mut thumb_query: Query<&mut Node, With<BacklogThumb>>,
+) {
+    let delta: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * WHEEL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+
+    if !backlog_state.open {
+        return;
+    }
+
+    for (mut scroll, computed) in scroll_query.iter_mut() {
+        // 布局尚未计算完成
+        if computed.size().y <= 0.0 {
+            continue;
+        }
+        let viewport = computed.size().y * computed.inverse_scale_factor();
+        let content = computed.content_size.y * computed.inverse_scale_factor();
+        let max_offset = (content - viewport).max(0.0);
+
+        let target = (scroll.y - delta).clamp(0.0, max_offset);
+        if scroll.y != target {
+            scroll.y = target;
+        }
+
+        // 滚动条滑块的位置与长度
+        if let Ok(mut thumb) = thumb_query.single_mut() {
+            let (top, height) = if content > viewport && content > 0.0 {
+                let height = (viewport / content * 100.0).max(5.0);
+                let top = if max_offset > 0.0 {
+                    target / max_offset * (100.0 - height)
+                } else {
+                    0.0
+                };
+                (top, height)
+            } else {
+                (0.0, 100.0)
+            };
+            thumb.top = Val::Percent(top);
+            thumb.height = Val::Percent(height);
+        }
+    }
+}
+
+// 悬停滚动条时切换为 hover 贴图
+fn update_scrollbar_images(
+    asset_server: Res<AssetServer>,
+    mut bar_query: Query<(&Interaction, &mut ImageNode), (Changed<Interaction>, With<BacklogScrollbar>)>,
+    mut thumb_query: Query<&mut ImageNode, (With<BacklogThumb>, Without<BacklogScrollbar>)>,
+) {
+    for (interaction, mut bar_image) in bar_query.iter_mut() {
+        let state = match interaction {
+            Interaction::None => "idle",
+            _ => "hover",
+        };
+        bar_image.image = asset_server.load(format!("gui/scrollbar/vertical_{}_bar.png", state));
+        for mut thumb_image in thumb_query.iter_mut() {
+            thumb_image.image =
+                asset_server.load(format!("gui/scrollbar/vertical_{}_thumb.png", state));
+        }
+    }
+}
+
+// 点击某条记录：回退到那一行
+fn handle_backlog_entries(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &BacklogEntryButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    backlog: Res<Backlog>,
+    mut backlog_state: ResMut<BacklogState>,
+    mut rollback_to: MessageWriter<RollbackToMessage>,
+    config: Res<MainConfig>,
+    backlog_root: Query<Entity, With<BacklogRoot>>,
+    mut named_query: Query<(&Name, &mut Visibility, &mut FocusPolicy)>,
+) {
+    for (interaction, entry_button, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Hovered => {
+                *color = BackgroundColor(Color::srgba(1.0, 0.6, 0.2, 0.2));
+                continue;
+            }
+            Interaction::None => {
+                *color = BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.05));
+                continue;
+            }
+            Interaction::Pressed => {}
+        }
+        let Some(entry) = backlog.entries.get(entry_button.0) else {
+            continue;
+        };
+        // 回退受 settings.rewind 控制，关闭时历史记录只能查看
+        if !config.settings.rewind {
+            println!("回退功能未开启（settings.rewind）");
+            continue;
+        }
+
+        println!("从历史记录回退到第 {} 行", entry.line);
+        rollback_to.write(RollbackToMessage(entry.history_index));
+
+        for entity in backlog_root.iter() {
+            commands.entity(entity).despawn();
+        }
+        backlog_state.open = false;
+        set_click_area_enabled(&mut named_query, true);
+        break;
+    }
+}
+
+// 点击语音按钮：重新播放那一行的语音
+fn handle_voice_buttons(
+    interaction_query: Query<(&Interaction, &BacklogVoiceButton), Changed<Interaction>>,
+    backlog: Res<Backlog>,
+    mut play_voice: MessageWriter<PlayVoiceMessage>,
+) {
+    for (interaction, voice_button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(voice) = backlog
+            .entries
+            .get(voice_button.0)
+            .and_then(|entry| entry.voice.clone())
+        {
+            println!("重新播放语音: {}", voice.path);
+            play_voice.write(voice);
+        }
+    }
+}
+
+fn handle_close_backlog(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<CloseBacklogButton>)>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut backlog_state: ResMut<BacklogState>,
+    backlog_root: Query<Entity, With<BacklogRoot>>,
+    mut named_query: Query<(&Name, &mut Visibility, &mut FocusPolicy)>,
+) {
+    if !backlog_state.open {
+        return;
+    }
+    // 返回按钮或鼠标右键关闭
+    let close_pressed = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if close_pressed || mouse.just_pressed(MouseButton::Right) {
+        for entity in backlog_root.iter() {
+            commands.entity(entity).despawn();
+        }
+        backlog_state.open = false;
+        set_click_area_enabled(&mut named_query, true);
+    }
+}
+
+fn cleanup_backlog(
+    mut commands: Commands,
+    mut backlog: ResMut<Backlog>,
+    mut backlog_state: ResMut<BacklogState>,
+    backlog_root: Query<Entity, With<BacklogRoot>>,
+) {
+    for entity in backlog_root.iter() {
+        commands.entity(entity).despawn();
+    }
+    backlog.entries.clear();
+    backlog_state.open = false;
+}
+
+fn spawn_backlog(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    locale: &Locale,
+    backlog: &Backlog,
+) {
+    let font = asset_server.load(BACKLOG_FONT);
+
+    commands
+        .spawn((
+            Name::new("backlog"),
+            BacklogRoot,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::axes(Val::Px(60.0), Val::Px(30.0)),
+                row_gap: Val::Px(15.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+            GlobalZIndex(25000),
+            Interaction::default(),
+            FocusPolicy::Block, // 阻止点击穿透
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                locale.ui_text("backlog.title"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.6, 0.2)),
+            ));
+
+            // 记录列表 + 滚动条
+            parent
+                .spawn(Node {
+                    width: Val::Percent(100.0),
+                    flex_grow: 1.0,
+                    min_height: Val::Px(0.0),
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            BacklogScroll,
+                            Node {
+                                flex_grow: 1.0,
+                                height: Val::Percent(100.0),
+                                flex_direction: FlexDirection::Column,
+                                row_gap: Val::Px(8.0),
+                                overflow: Overflow::scroll_y(),
+                                ..default()
+                            },
+                            // 打开时定位到最新的记录
+                            ScrollPosition(Vec2::new(0.0, f32::MAX)),
+                        ))
+                        .with_children(|parent| {
+                            if backlog.entries.is_empty() {
+                                parent.spawn((
+                                    locale.ui_text("backlog.empty"),
+                                    TextFont {
+                                        font: font.clone(),
+                                        font_size: 16.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::srgb(0.6, 0.6, 0.7)),
+                                ));
+                            }
+
+                            for (index, entry) in backlog.entries.iter().enumerate() {
+                                parent
+                                    .spawn((
+                                        Button,
+                                        BacklogEntryButton(index),
+                                        Node {
+                                            flex_direction: FlexDirection::Column,
+                                            width: Val::Percent(100.0),
+                                            flex_shrink: 0.0,
+                                            padding: UiRect::all(Val::Px(8.0)),
+                                            row_gap: Val::Px(4.0),
+                                            ..default()
+                                        },
+                                        BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.05)),
+                                    ))
+                                    .with_children(|parent| {
+                                        if entry.character != "none" {
+                                            parent.spawn((
+                                                Text::new(entry.character.clone()),
+                                                TextFont {
+                                                    font: font.clone(),
+                                                    font_size: 16.0,
+                                                    ..default()
+                                                },
+                                                TextColor(Color::srgb(1.0, 0.8, 0.3)),
+                                            ));
+                                        }
+                                        if entry.voice.is_some() {
+                                            parent
+                                                .spawn((
+                                                    Button,
+                                                    BacklogVoiceButton(index),
+                                                    Node {
+                                                        position_type: PositionType::Absolute,
+                                                        top: Val::Px(8.0),
+                                                        right: Val::Px(8.0),
+                                                        padding: UiRect::axes(
+                                                            Val::Px(8.0),
+                                                            Val::Px(2.0),
+                                                        ),
+                                                        ..default()
+                                                    },
+                                                    BackgroundColor(Color::srgba(
+                                                        1.0, 1.0, 1.0, 0.1,
+                                                    )),
+                                                ))
+                                                .with_children(|parent| {
+                                                    parent.spawn((
+                                                        locale.ui_text("backlog.voice"),
+                                                        TextFont {
+                                                            font: font.clone(),
+                                                            font_size: 14.0,
+                                                            ..default()
+                                                        },
+                                                        TextColor(Color::srgb(0.6, 0.8, 1.0)),
+                                                    ));
+                                                });
+                                        }
+                                        parent.spawn((
+                                            Text::new(entry.text.clone()),
+                                            TextFont {
+                                                font: font.clone(),
+                                                font_size: 16.0,
+                                                ..default()
+                                            },
+                                            TextColor(Color::WHITE),
+                                        ));
+                                        if let Some(choice) = &entry.choice {
+                                            parent.spawn((
+                                                Text::new(format!("→ {}", choice)),
+                                                TextFont {
+                                                    font: font.clone(),
+                                                    font_size: 14.0,
+                                                    ..default()
+                                                },
+                                                TextColor(Color::srgb(0.6, 0.8, 1.0)),
+                                            ));
+                                        }
+                                    });
+                            }
+                        });
+
+                    // 竖直滚动条
+                    parent
+                        .spawn((
+                            BacklogScrollbar,
+                            Interaction::default(),
+                            Node {
+                                width: Val::Px(12.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            ImageNode::new(asset_server.load("gui/scrollbar/vertical_idle_bar.png")),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                BacklogThumb,
+                                Node {
+                                    position_type: PositionType::Absolute,
+                                    width: Val::Percent(100.0),
+                                    top: Val::Percent(0.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                ImageNode::new(
+                                    asset_server.load("gui/scrollbar/vertical_idle_thumb.png"),
+                                ),
+                            ));
+                        });
+                });
+
+            // 返回按钮
+            parent
+                .spawn((
+                    Button,
+                    CloseBacklogButton,
+                    Node {
+                        width: Val::Px(120.0),
+                        height: Val::Px(40.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.3, 0.3, 0.5)),
+                    BorderColor::all(Color::srgb(0.5, 0.5, 0.7)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        locale.ui_text("common.back"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+        });
+}
@@ -0,0 +1,312 @@
+// src/game/dialogue_text.rs
+// 对话文本显示：把内联标签解析为 TextSpan，并按打字速度逐字显示
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use super::{GameState, rollback::RollbackHistory, skip::SkipState};
+use crate::{
+    GameScene,
+    config::MainConfig,
+    locale::Locale,
+    script::{
+        Markup,
+        markup::{SpanStyle, parse_color},
+        parse_markup,
+    },
+};
+
+/// 对话框的打字机状态
+#[derive(Resource, Default)]
+pub struct DialogueTypewriter {
+    // 当前显示的（行号, 回退历史长度），变化时重新生成文本
+    shown: Option<(usize, usize)>,
+    markup: Markup,
+    spans: Vec<Entity>,
+    revealed: usize,
+    rendered: Option<usize>,
+    total: usize,
+    // 累积的待显示字符数，小数部分跨帧保留
+    progress: f32,
+    next_pause: usize,
+    pause_timer: f32,
+    waiting_click: bool,
+    no_wait_done: bool,
+}
+
+impl DialogueTypewriter {
+    /// 文本尚未显示完（包括 {w} 暂停中）
+    pub fn is_typing(&self) -> bool {
+        self.revealed < self.total || self.waiting_click || self.pause_timer > 0.0
+    }
+
+    /// 玩家点击：暂停中则继续，否则直接显示到下一个 {w}
+    pub fn click(&mut self) {
+        self.progress = 0.0;
+        if self.waiting_click || self.pause_timer > 0.0 {
+            self.waiting_click = false;
+            self.pause_timer = 0.0;
+            return;
+        }
+        match self.markup.pauses.get(self.next_pause) {
+            Some(pause) if pause.at < self.total => {
+                self.revealed = self.revealed.max(pause.at);
+                self.start_pause();
+            }
+            _ => self.revealed = self.total,
+        }
+    }
+
+    /// 立即显示全部文本
+    pub fn finish(&mut self) {
+        self.revealed = self.total;
+        self.next_pause = self.markup.pauses.len();
+        self.waiting_click = false;
+        self.pause_timer = 0.0;
+    }
+
+    fn start_pause(&mut self) {
+        let pause = self.markup.pauses[self.next_pause];
+        self.next_pause += 1;
+        match pause.seconds {
+            Some(seconds) => self.pause_timer = seconds,
+            None => self.waiting_click = true,
+        }
+    }
+
+    fn tick(&mut self, delta: f32, base_cps: f32) {
+        if self.waiting_click {
+            return;
+        }
+        if self.pause_timer > 0.0 {
+            self.pause_timer = (self.pause_timer - delta).max(0.0);
+            return;
+        }
+
+        let cps = self
+            .markup
+            .speed_at(self.revealed)
+            .map_or(base_cps, |speed| speed.apply(base_cps));
+        // 速度为 0 时立即显示到下一个暂停点
+        self.progress = if cps > 0.0 {
+            self.progress + delta * cps
+        } else {
+            f32::MAX
+        };
+
+        while self.progress >= 1.0 && self.revealed < self.total {
+            // 到达 {w} 的位置时停下
+            if let Some(pause) = self.markup.pauses.get(self.next_pause) {
+                if pause.at <= self.revealed {
+                    self.start_pause();
+                    self.progress = 0.0;
+                    return;
+                }
+            }
+            self.revealed += 1;
+            self.progress -= 1.0;
+        }
+        if self.revealed >= self.total {
+            self.progress = 0.0;
+        }
+    }
+}
+
+pub struct DialogueTextPlugin;
+
+impl Plugin for DialogueTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DialogueTypewriter>()
+            .add_systems(
+                Update,
+                update_dialogue_text
+                    .after(super::update_dialogue)
+                    .after(super::enter_current_line)
+                    .run_if(in_state(GameScene::Game)),
+            )
+            .add_systems(OnExit(GameScene::Game), reset_typewriter);
+    }
+}
+
+fn update_dialogue_text(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    config: Res<MainConfig>,
+    mut game_state: ResMut<GameState>,
+    history: Res<RollbackHistory>,
+    skip_state: Res<SkipState>,
+    mut typewriter: ResMut<DialogueTypewriter>,
+    locale: Res<Locale>,
+    textbox_query: Query<(Entity, &Name, &TextFont, &TextColor), With<Text>>,
+    mut text_query: Query<&mut Text>,
+    mut span_query: Query<&mut TextSpan>,
+    mut warned: Local<HashSet<&'static str>>,
+) {
+    let current_line = game_state.current_line;
+    let key = (current_line, history.entries.len());
+
+    // 切换语言后按新语言重新生成当前行
+    if locale.is_changed() && !locale.is_added() {
+        typewriter.shown = None;
+    }
+
+    // 进入新行时重新生成文本
+    if game_state.entered_line == Some(current_line) && typewriter.shown != Some(key) {
+        let Some((textbox, _, base_font, base_color)) = textbox_query
+            .iter()
+            .find(|(_, name, _, _)| name.as_str() == "textbox")
+        else {
+            return;
+        };
+
+        for entity in typewriter.spans.drain(..) {
+            commands.entity(entity).despawn();
+        }
+
+        // 角色表中设置了文字颜色时代替对话框的默认颜色
+        let base_color = game_state
+            .dialogues
+            .get(current_line)
+            .and_then(|dialogue| config.get_character(&dialogue.character))
+            .and_then(|entry| entry.text_color.as_deref())
+            .and_then(parse_color)
+            .map_or(base_color.0, |[r, g, b, a]| Color::srgba(r, g, b, a));
+
+        let markup = match game_state.dialogues.get(current_line) {
+            Some(dialogue) => {
+                if let Ok(mut text) = text_query.get_mut(textbox) {
+                    text.0.clear();
+                }
+                if dialogue.text == "none" {
+                    Markup::default()
+                } else {
+                    parse_markup(locale.dialogue_text(dialogue), &game_state.variables)
+                }
+            }
+            None => Markup::default(),
+        };
+
+        let bold_font = config
+            .settings
+            .bold_font
+            .as_ref()
+            .map(|path| asset_server.load(path.clone()));
+        let italic_font = config
+            .settings
+            .italic_font
+            .as_ref()
+            .map(|path| asset_server.load(path.clone()));
+
+        // 没有设置对应字体时 {b} / {i} 不改变字形，只提示一次
+        let missing_fonts = [
+            (
+                "{b}",
+                "bold_font",
+                bold_font.is_none() && markup.runs.iter().any(|run| run.style.bold),
+            ),
+            (
+                "{i}",
+                "italic_font",
+                italic_font.is_none() && markup.runs.iter().any(|run| run.style.italic),
+            ),
+        ];
+        for (tag, key, missing) in missing_fonts {
+            if missing && warned.insert(tag) {
+                eprintln!(
+                    "文本使用了 {} 标签，但 main.yaml 的 settings 没有设置 {}，沿用正文字体",
+                    tag, key
+                );
+            }
+        }
+
+        let mut spans = Vec::new();
+        commands.entity(textbox).with_children(|parent| {
+            for run in &markup.runs {
+                let font = span_font(&run.style, base_font, &bold_font, &italic_font);
+                let color = run
+                    .style
+                    .color
+                    .map_or(base_color, |[r, g, b, a]| Color::srgba(r, g, b, a));
+                spans.push(
+                    parent
+                        .spawn((TextSpan::new(""), font, TextColor(color)))
+                        .id(),
+                );
+            }
+        });
+
+        *typewriter = DialogueTypewriter {
+            shown: Some(key),
+            total: markup.char_count(),
+            markup,
+            spans,
+            ..default()
+        };
+    }
+
+    if typewriter.shown.is_none() {
+        return;
+    }
+
+    let text_speed = config.settings.text_speed;
+    if skip_state.is_skipping() || text_speed == 0 {
+        typewriter.finish();
+    } else {
+        typewriter.tick(time.delta_secs(), text_speed as f32);
+    }
+
+    // 更新各段已显示的文字
+    if typewriter.rendered != Some(typewriter.revealed) {
+        let mut remaining = typewriter.revealed;
+        let mut all_found = true;
+        for (run, entity) in typewriter.markup.runs.iter().zip(&typewriter.spans) {
+            let shown: String = run.text.chars().take(remaining).collect();
+            remaining -= shown.chars().count();
+            match span_query.get_mut(*entity) {
+                Ok(mut span) => span.0 = shown,
+                // 刚生成的 TextSpan 下一帧才能查询到
+                Err(_) => all_found = false,
+            }
+        }
+        if all_found {
+            typewriter.rendered = Some(typewriter.revealed);
+        }
+    }
+
+    // {nw}：显示完毕后自动进入下一行（有选项或暂停时不跳过）
+    if typewriter.markup.no_wait && !typewriter.is_typing() && !typewriter.no_wait_done {
+        typewriter.no_wait_done = true;
+        let paused = game_state
+            .dialogues
+            .get(current_line)
+            .is_some_and(|dialogue| dialogue.pause == Some(true));
+        if !paused && game_state.visible_choices(current_line).is_empty() {
+            game_state.advance();
+        }
+    }
+}
+
+fn span_font(
+    style: &SpanStyle,
+    base: &TextFont,
+    bold_font: &Option<Handle<Font>>,
+    italic_font: &Option<Handle<Font>>,
+) -> TextFont {
+    let font = match (style.bold, style.italic) {
+        (true, _) if bold_font.is_some() => bold_font.clone(),
+        (_, true) if italic_font.is_some() => italic_font.clone(),
+        _ => None,
+    };
+    TextFont {
+        font: font.unwrap_or_else(|| base.font.clone()),
+        font_size: style
+            .size
+            .map_or(base.font_size, |size| size.apply(base.font_size)),
+        ..base.clone()
+    }
+}
+
+fn reset_typewriter(mut typewriter: ResMut<DialogueTypewriter>) {
+    *typewriter = DialogueTypewriter::default();
+}
@@ -1,9 +1,22 @@
 pub mod media_query;
+mod backlog;
+mod dialogue_text;
+mod rollback;
+mod save_load;
+mod sfx;
+mod skip;
+mod stage;
+mod voice;
 // ============================================================================
 // 依赖导入
 // ============================================================================
 
-use std::{collections::HashMap, env, fmt::Debug, fs, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    fmt::Debug,
+    time::Duration,
+};
 
 // Bevy 引擎
 use bevy::{audio::PlaybackSettings, prelude::*, ui::{FocusPolicy, debug::print_ui_layout_tree}};
@@ -21,22 +34,41 @@ use serde::Deserialize;
 // 项目内部模块
 use crate::{
     GameScene,
-    config::{MainConfig, load_main_config},
+    config::{MainConfig, VariableValue, load_main_config},
+    display::{
+        DisplayButton, display_color, fullscreen_hotkey_pressed, resolution_label,
+        resolution_presets,
+    },
+    locale::{LanguageButton, Locale, language_color},
     style::UiStyleSheet,
-    toolbar::{RollbackEventMessage, ToggleAutoPlayEventMessage, ToggleMenuEventMessage},
-    transition::fade_in,
-    audio::play_audio,
+    toolbar::{
+        OpenHistoryEventMessage, OpenLoadMenuEventMessage, OpenSaveMenuEventMessage,
+        RollbackEventMessage, ToggleAutoPlayEventMessage, ToggleMenuEventMessage,
+    },
+    effect::{CameraEffects, CameraView},
+    motion::{Motion, MotionPlayer, play_motion},
+    transition::{fade_in, finish_transition, start_transition},
+    audio::{AudioChannel, MixerSound, crossfade_music, fade_out_audio, play_audio},
 };
 use Raven::style::ElementId;
-// Raven 库
-use Raven::{
-    dissolve::{RenpyDissolve, RenpyDissolvePlugin},
-    typewriter::TypewriterText,
-};
+use crate::dissolve::RenpyDissolve;
 
 use Raven::style::StyleUpdateTrigger;
 use crate::game::media_query::MediaQueryPlugin;
 use crate::game::media_query::MediaElementId;
+use crate::game::backlog::{Backlog, BacklogEntry, BacklogPlugin, BacklogState};
+use crate::game::dialogue_text::{DialogueTextPlugin, DialogueTypewriter};
+use crate::game::rollback::{HistoryEntry, Presentation, RollbackHistory, RollbackPlugin};
+use crate::game::sfx::SfxPlugin;
+use crate::game::stage::{Stage, StagePlugin};
+use crate::game::voice::{PlayVoiceMessage, VOICE_AUTO_ADVANCE_DELAY, VoicePlugin, VoiceState};
+use crate::game::save_load::{SaveLoadPlugin, SlotMenuState};
+use crate::game::skip::{SkipOption, SkipOptionButton, SkipPlugin, SkipState};
+use crate::script::{
+    Choice, Dialogue, Script, apply_set,
+    dialogue::BgmChange,
+    evaluate_condition, load_script, markup::parse_color, parse_markup, voice_path, voice_tag,
+};
 // ============================================================================
 // 常量定义
 // ============================================================================
@@ -47,6 +79,10 @@ const NORMAL_BUTTON: Color = Color::srgba(0.0, 0.0, 0.0, 0.0);
 const HOVERED_BUTTON: Color = Color::srgba(1.0, 1.0, 1.0, 0.0);
 const PRESSED_BUTTON: Color = Color::srgba(1.0, 1.0, 1.0, 0.0);
 
+// 名字框默认样式（角色表中未设置 namebox 时使用）
+const NAMEBOX_FONT_SIZE: f32 = 28.0;
+const NAMEBOX_IMAGE: &str = "gui/textbox2.png";
+
 // ============================================================================
 // 事件定义
 // ============================================================================
@@ -102,6 +138,10 @@ pub struct SettingsMenu;
 #[derive(Component)]
 struct Background;
 
+/// 切换背景时旧背景的副本，按转场消失后移除
+#[derive(Component)]
+struct OutgoingBackground;
+
 #[derive(Component)]
 struct Portrait;
 
@@ -118,6 +158,10 @@ struct MyMusic;
 #[derive(Component)]
 struct ClickHandler(String);
 
+// 选项在当前行 choices 中的下标
+#[derive(Component)]
+struct ChoiceIndex(usize);
+
 #[derive(Component)]
 struct ButtonImages {
     normal: Handle<Image>,
@@ -207,40 +251,6 @@ enum UnblockCondition {
 // 数据结构定义
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
-struct Choice {
-    text: String,
-    goto: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct Dialogue {
-    character: String,
-    text: String,
-    portrait: String,
-    
-    #[serde(default)]
-    bgm: Option<String>,
-    
-    #[serde(default)]
-    background: Option<String>,
-    
-    #[serde(default)]
-    swf: Option<String>,
-    
-    #[serde(default)]
-    label: Option<String>,
-    
-    #[serde(default)]
-    jump: Option<String>,
-    
-    #[serde(default)]
-    choices: Option<Vec<Choice>>,
-    
-    #[serde(default)]
-    pause: Option<bool>,
-}
-
 #[derive(Debug, Deserialize)]
 struct AssetPaths {
     characters: HashMap<String, String>,
@@ -286,7 +296,71 @@ struct GameState {
     is_blocked: bool,
     is_auto_playing: bool,
     auto_play_timer: f32,
-    auto_play_interval: f32,
+    variables: HashMap<String, VariableValue>,
+    entered_line: Option<usize>, // 已执行过 set 的行，避免重复赋值
+}
+
+impl GameState {
+    // 条件为空视为成立；表达式出错时打印错误并视为不成立
+    fn condition_met(&self, condition: Option<&str>) -> bool {
+        let Some(condition) = condition else {
+            return true;
+        };
+        match evaluate_condition(condition, &self.variables) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("条件 '{}' 求值失败: {}", condition, e);
+                false
+            }
+        }
+    }
+
+    // 指定行是否满足显示条件
+    fn line_active(&self, line: usize) -> bool {
+        self.dialogues
+            .get(line)
+            .map_or(true, |d| self.condition_met(d.condition.as_deref()))
+    }
+
+    // 指定行的跳转目标（跳转条件不成立时返回 None）
+    fn jump_target(&self, line: usize) -> Option<String> {
+        let jump = self.dialogues.get(line)?.jump.as_ref()?;
+        self.condition_met(jump.condition())
+            .then(|| jump.label().to_string())
+    }
+
+    // 当前行可见的选项（保留原始下标）
+    fn visible_choices(&self, line: usize) -> Vec<(usize, &Choice)> {
+        self.dialogues
+            .get(line)
+            .and_then(|d| d.choices.as_ref())
+            .map(|choices| {
+                choices
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, choice)| self.condition_met(choice.condition.as_deref()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // 前进一步：有跳转则交给 apply_jump，否则进入下一行
+    fn advance(&mut self) {
+        if let Some(jump_label) = self.jump_target(self.current_line) {
+            self.jump_label = Some(jump_label);
+        } else {
+            self.current_line += 1;
+        }
+        self.can_go_back = true;
+    }
+
+    fn apply_sets(&mut self, sets: &HashMap<String, VariableValue>) {
+        for (name, value) in sets {
+            if let Err(e) = apply_set(&mut self.variables, name, value) {
+                eprintln!("变量 '{}' 赋值失败: {}", name, e);
+            }
+        }
+    }
 }
 
 #[derive(Debug, Resource)]
@@ -295,6 +369,27 @@ struct LabelMap(HashMap<String, usize>);
 #[derive(Debug, Resource)]
 struct PortraitAssets {
     handles: HashMap<String, Handle<Image>>,
+    // 角色 -> 表情名 -> 图片
+    expressions: HashMap<String, HashMap<String, Handle<Image>>>,
+}
+
+impl PortraitAssets {
+    // 角色某个表情的立绘，找不到该表情时使用默认立绘
+    // 找不到表情时使用默认立绘（提示由调用方负责，避免每帧输出）
+    fn get(&self, character: &str, expression: Option<&str>) -> Option<&Handle<Image>> {
+        expression
+            .and_then(|expression| self.expressions.get(character)?.get(expression))
+            .or_else(|| self.handles.get(character))
+    }
+
+    // 没有写表情，或角色有这个表情
+    fn has_expression(&self, character: &str, expression: Option<&str>) -> bool {
+        expression.is_none_or(|expression| {
+            self.expressions
+                .get(character)
+                .is_some_and(|expressions| expressions.contains_key(expression))
+        })
+    }
 }
 
 #[derive(Resource)]
@@ -307,6 +402,8 @@ struct BackClickSound(Handle<AudioSource>);
 pub struct CurrentAudio {
     pub current_bgm: Option<String>,
     pub current_entity: Option<Entity>,
+    // 已经处理过 bgm 的（行号, 回退历史长度）
+    entered: Option<(usize, usize)>,
 }
 pub struct GamePlugin;
 
@@ -319,6 +416,14 @@ impl Plugin for GamePlugin {
             // 只在启动时加载资源，不创建UI
         .insert_resource(StyleUpdateTrigger::default())
         .add_plugins(MediaQueryPlugin) // 添加媒体查询插件
+        .add_plugins(SaveLoadPlugin) // 存档/读档界面
+        .add_plugins(BacklogPlugin) // 历史记录界面
+        .add_plugins(SkipPlugin) // 快进模式
+        .add_plugins(RollbackPlugin) // 回退历史
+        .add_plugins(DialogueTextPlugin) // 对话文本标签与打字机
+        .add_plugins(StagePlugin) // 多角色同屏
+        .add_plugins(SfxPlugin) // 对话行音效
+        .add_plugins(VoicePlugin) // 语音
         .add_systems(
             Startup,
             load_main_config_system,
@@ -352,11 +457,14 @@ impl Plugin for GamePlugin {
             OnEnter(GameScene::Game),
             load_swf_assets.after(setup_ui),
         )
-            .add_plugins(RenpyDissolvePlugin)
             // .add_plugins(StylePlugin)
             .insert_resource(CurrentAudio::default())
             // .add_plugins(TypewriterPlugin)
-            .add_systems(OnExit(GameScene::Game), cleanup_game)
+            .add_systems(OnExit(GameScene::Game), (cleanup_game, reset_camera_effects))
+            .add_systems(
+                Update,
+                handle_settings_menu_items.run_if(in_state(GameScene::Game)),
+            )
             .add_systems(
                 Update,
                 (
@@ -367,22 +475,26 @@ impl Plugin for GamePlugin {
                     handle_close_settings_button, // 处理关闭按钮
                     output_game_state,
                     update_dialogue,
-                    handle_rollback_event,
                     handle_auto_play_event,
-                    update_audio,
+                    update_audio.after(enter_current_line),
                     // typewriter_system.after(update_dialogue),
                     update_portrait,
                     apply_jump,
+                    enter_current_line
+                        .after(handle_input)
+                        .after(apply_jump)
+                        .after(handle_choice_buttons)
+                        .before(update_dialogue),
                     update_background,
                     update_swf.run_if(in_state(GameScene::Game)),
-                    keyboard_system,
                     handle_choice_buttons,
+                    refresh_choice_language.before(create_dynamic_buttons),
                     create_dynamic_buttons
                         .run_if(in_state(GameScene::Game))  // 先检查是否在游戏状态
                         .run_if(should_create_buttons),     // 再检查是否需要创建按钮
                     button_interaction_system,
                     button_image_system,
-                    update_typewriter, // fade_animation_system
+                    // fade_animation_system
                 )
                     .run_if(in_state(GameScene::Game)),
             );
@@ -407,15 +519,7 @@ fn setup_game_state(
 ) {
     commands.spawn(Camera2d);
 
-    let dialogues: Vec<Dialogue> = load_dialogues(&config);
-
-    // 创建标签映射
-    let mut label_map = HashMap::new();
-    for (index, dialogue) in dialogues.iter().enumerate() {
-        if let Some(label) = dialogue.label.as_ref() {
-            label_map.insert(label.clone(), index);
-        }
-    }
+    let (dialogues, label_map) = load_game_script(&config);
 
     commands.insert_resource(GameState {
         current_line: 0,
@@ -426,58 +530,96 @@ fn setup_game_state(
         is_blocked: false,
         is_auto_playing: false, // 新增字段
         auto_play_timer: 0.0,
-        auto_play_interval: 2.0, // 默认2秒间隔
+        variables: initial_variables(&config),
+        entered_line: None,
     });
 
-    commands.insert_resource(LabelMap(label_map));
+    commands.insert_resource(label_map);
 }
 
 // 清理游戏场景
 
 // 函数库
-fn load_dialogues(config: &MainConfig) -> Vec<Dialogue> {
-    // let base_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+// 脚本变量的初始值，来自 main.yaml 的 variables
+fn initial_variables(config: &MainConfig) -> HashMap<String, VariableValue> {
+    let mut variables = HashMap::new();
+    variables.insert(
+        "player_name".to_string(),
+        VariableValue::String(config.global_variables.player_name.clone()),
+    );
+    variables.insert(
+        "affection_points".to_string(),
+        VariableValue::Number(config.global_variables.affection_points as f64),
+    );
+    variables.extend(config.variables.clone());
+    variables
+}
+
+// 加载 main.yaml 中列出的全部脚本文件，并建立标签映射
+fn load_game_script(config: &MainConfig) -> (Vec<Dialogue>, LabelMap) {
     let exe_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let script = load_script(config, &exe_dir.join("assets"))
+        .unwrap_or_else(|e| panic!("对话脚本加载失败: {}", e));
     println!(
-        "相对的对话路径有: {:?}",
-        exe_dir.join("assets/dialogues.yaml")
+        "已加载对话脚本: {} 行, {} 个标签",
+        script.dialogues.len(),
+        script.labels.len()
     );
-    let yaml_path2 = exe_dir.join("assets/dialogues.yaml");
-    let yaml_str = fs::read_to_string(yaml_path2).expect("找不到对话文件 assets/dialogues.yaml");
-
-    // 对YAML字符串进行变量替换
-    let mut processed_yaml = yaml_str.clone();
-
-    // 替换全局变量
-    // for (var_name, var_value) in &config.global_variables {
-    //     processed_yaml = processed_yaml.replace(&format!("${}", var_name), var_value);
-    // }
-
-    // 替换标题
-    processed_yaml = processed_yaml.replace("$title", &config.title);
-
-    // 替换资源路径（简化处理）
-    // 背景图片替换
-    for (bg_name, bg_path) in &config.assets.backgrounds {
-        processed_yaml = processed_yaml.replace(&format!("$backgrounds.{}", bg_name), bg_path);
-    }
+    report_script_errors(&script);
+    (script.dialogues, LabelMap(script.labels))
+}
 
-    // 音频替换
-    for (bgm_name, bgm_path) in &config.assets.audio.bgm {
-        processed_yaml = processed_yaml.replace(&format!("$audio.bgm.{}", bgm_name), bgm_path);
+// 加载脚本时检查重复标签以及 jump / goto 的目标是否存在，避免点击时才发现
+fn report_script_errors(script: &Script) {
+    for duplicate in &script.duplicate_labels {
+        eprintln!(
+            "脚本错误: 标签 '{}' 在 {} 重复定义（首次定义于 {}），已忽略",
+            duplicate.label,
+            script.describe_line(duplicate.duplicate),
+            script.describe_line(duplicate.first)
+        );
     }
 
-    for (sfx_name, sfx_path) in &config.assets.audio.sfx {
-        processed_yaml = processed_yaml.replace(&format!("$audio.sfx.{}", sfx_name), sfx_path);
-    }
+    let dialogues = &script.dialogues;
+    for (index, dialogue) in dialogues.iter().enumerate() {
+        for key in dialogue.unknown.keys() {
+            eprintln!(
+                "脚本警告: {} 无法识别的键 '{}'，已忽略",
+                script.describe_line(index),
+                key
+            );
+        }
+        if let Some(jump) = &dialogue.jump {
+            if !script.labels.contains_key(jump.label()) {
+                eprintln!(
+                    "脚本错误: {} 的 jump 目标 '{}' 不存在",
+                    script.describe_line(index),
+                    jump.label()
+                );
+            }
+        }
 
-    // 角色立绘替换
-    for (char_name, char_path) in &config.assets.characters {
-        processed_yaml = processed_yaml.replace(&format!("$characters.{}", char_name), char_path);
+        for choice in dialogue.choices.iter().flatten() {
+            match choice.goto.resolve(&script.labels) {
+                Some(line) if line < dialogues.len() => {}
+                Some(line) => eprintln!(
+                    "脚本错误: {} 选项 '{}' 的 goto 行号 {} 超出范围（共 {} 行）",
+                    script.describe_line(index),
+                    choice.text,
+                    line,
+                    dialogues.len()
+                ),
+                None => eprintln!(
+                    "脚本错误: {} 选项 '{}' 的 goto 目标 '{}' 不存在",
+                    script.describe_line(index),
+                    choice.text,
+                    choice.goto
+                ),
+            }
+        }
     }
-    // debug_print("var4",&processed_yaml);
-    serde_yaml::from_str(&processed_yaml).expect("YAML解析失败，请检查格式")
 }
+
 // 初始化游戏的状态
 fn setup_camera(mut commands: Commands, config: Res<MainConfig>) {
     // commands.spawn((
@@ -488,37 +630,34 @@ fn setup_camera(mut commands: Commands, config: Res<MainConfig>) {
 
     
 
-    let dialogues: Vec<Dialogue> = load_dialogues(&config);
-    // 创建映射代码
-    // 创建标签映射
-    let mut label_map = HashMap::new();
-    for (index, dialogue) in dialogues.iter().enumerate() {
-        if let Some(label) = dialogue.label.as_ref() {
-            // 使用 as_ref() 获取引用
-            label_map.insert(label.clone(), index);
-        }
-    }
+    let (dialogues, label_map) = load_game_script(&config);
     commands.insert_resource(GameState {
         current_line: 0,
-        dialogues: load_dialogues(&config),
+        dialogues,
         can_go_back: false, // 初始时不能返回
         jump_label: None,
         in_branch_selection: false,
         is_blocked: false,
         is_auto_playing: false, // 新增字段
         auto_play_timer: 0.0,
-        auto_play_interval: 2.0, // 默认2秒间隔
+        variables: initial_variables(&config),
+        entered_line: None,
     });
     // println!("label_map: {:?}", label_map[1].jump);
-    commands.insert_resource(LabelMap(label_map));
+    commands.insert_resource(label_map);
     // 插入标签映射资源
 }
 // 加载立绘资源 - 使用标准库的Path和PathBuf修改后的版本
 fn load_portraits(mut commands: Commands, asset_server: Res<AssetServer>, config: Res<MainConfig>) {
     let mut portrait_assets = PortraitAssets {
         handles: HashMap::new(),
+        expressions: HashMap::new(),
     };
 
+    // 角色目录中的每张图片都是一个表情，另加 main.yaml 中声明的表情
+    let exe_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let character_expressions = config.character_expressions(&exe_dir.join("assets"));
+
     // 遍历配置文件中的所有角色
     for (character_name, character_path) in &config.assets.characters {
         // 确保路径不包含重复的assets前缀
@@ -528,17 +667,32 @@ fn load_portraits(mut commands: Commands, asset_server: Res<AssetServer>, config
             character_path.clone()
         };
 
+        let expressions: HashMap<String, Handle<Image>> = character_expressions
+            .get(character_name)
+            .into_iter()
+            .flatten()
+            .map(|(expression, path)| (expression.clone(), asset_server.load(path.clone())))
+            .collect();
+
         // 使用正斜杠来确保路径格式一致
         let path_string = format!("{}/default.png", character_path.replace('\\', "/"));
         // println!("{}", path_string);
-        let handle = asset_server.load(&path_string);
+        let handle = expressions
+            .get("default")
+            .cloned()
+            .unwrap_or_else(|| asset_server.load(&path_string));
         portrait_assets
             .handles
             .insert(character_name.clone(), handle);
+        portrait_assets
+            .expressions
+            .insert(character_name.clone(), expressions);
     }
     println!("=== 所有立绘路径 ===");
-    for character_name in portrait_assets.handles.keys() {
-        println!("角色: {}", character_name);
+    for (character_name, expressions) in &portrait_assets.expressions {
+        let mut names: Vec<&String> = expressions.keys().collect();
+        names.sort();
+        println!("角色: {} 表情: {:?}", character_name, names);
     }
 
     println!("==================");
@@ -549,6 +703,7 @@ fn setup_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     config: Res<MainConfig>,
+    locale: Res<Locale>,
     stylesheet: Res<UiStyleSheet>,
     mut trigger: ResMut<StyleUpdateTrigger>,
 ) {
@@ -721,12 +876,6 @@ fn setup_ui(
                 },
                 // 其他你需要的组件
                 // CurrentText,
-                TypewriterText {
-                    full_text: "".to_string(),
-                    current_length: 0,
-                    timer: Timer::from_seconds(0.02, TimerMode::Repeating), // 每50毫秒显示一个字符
-                    is_active: true,                                        // 添加这一行
-                },
             ));
         });
     commands.spawn((
@@ -736,7 +885,7 @@ fn setup_ui(
         Visibility::Visible,
         TextFont {
             font: asset_server.load("fonts/SarasaFixedHC-Light.ttf"),
-            font_size: 28.0,
+            font_size: NAMEBOX_FONT_SIZE,
             line_height: bevy::text::LineHeight::Px(50.),
             ..default()
         },
@@ -760,7 +909,7 @@ fn setup_ui(
         // 对话框背景颜色
         // BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
         // GlobalZIndex(1),
-        ImageNode::new(asset_server.load("gui/textbox2.png")),
+        ImageNode::new(asset_server.load(NAMEBOX_IMAGE)),
         // AnimatedText,
         
     ));
@@ -843,7 +992,7 @@ fn setup_ui(
                         .with_children(|left_menu| {
                             // 设置标题
                             left_menu.spawn((
-                                Text::new("设置"),
+                                locale.ui_text("settings.title"),
                                 TextFont {
                                     font: font_handle.clone(),
                                     font_size: 32.0,
@@ -858,9 +1007,15 @@ fn setup_ui(
                             ));
 
                             // 左侧菜单选项
-                            let menu_items =
-                                vec!["历史", "保存", "读取游戏", "设置", "标题界面", "关于"];
-                            for (index, item) in menu_items.iter().enumerate() {
+                            let menu_items = vec![
+                                ("game_menu.history", SelectedMenuItem::History),
+                                ("game_menu.save", SelectedMenuItem::Save),
+                                ("game_menu.load", SelectedMenuItem::Load),
+                                ("game_menu.settings", SelectedMenuItem::Settings),
+                                ("game_menu.title", SelectedMenuItem::Title),
+                                ("game_menu.about", SelectedMenuItem::About),
+                            ];
+                            for (index, (item, menu_item)) in menu_items.iter().enumerate() {
                                 let is_selected = index == 3; // "设置" 被选中
                                 left_menu
                                     .spawn((
@@ -880,10 +1035,11 @@ fn setup_ui(
                                             Color::srgba(0.0, 0.0, 0.0, 0.0) // 透明背景
                                         }),
                                         ZIndex(1003),
+                                        SettingsMenuItem(*menu_item),
                                     ))
                                     .with_children(|button| {
                                         button.spawn((
-                                            Text::new(*item),
+                                            locale.ui_text(item),
                                             TextFont {
                                                 font: font_handle.clone(),
                                                 font_size: 18.0,
@@ -938,7 +1094,7 @@ fn setup_ui(
                                 .with_children(|display_column| {
                                     // 显示标题
                                     display_column.spawn((
-                                        Text::new("显示"),
+                                        locale.ui_text("settings.display"),
                                         TextFont {
                                             font: font_handle.clone(),
                                             font_size: 24.0,
@@ -952,12 +1108,20 @@ fn setup_ui(
                                         ZIndex(1004),
                                     ));
 
-                                    // 显示选项
-                                    let display_options = vec!["窗口", "全屏"];
+                                    // 显示选项：窗口 / 全屏，以及窗口模式下的窗口大小
+                                    let display_options =
+                                        [DisplayButton::Windowed, DisplayButton::Fullscreen]
+                                            .into_iter()
+                                            .chain(
+                                                resolution_presets(&config)
+                                                    .into_iter()
+                                                    .map(DisplayButton::Resolution),
+                                            );
                                     for option in display_options {
                                         display_column
                                             .spawn((
                                                 Button,
+                                                option,
                                                 Node {
                                                     width: Val::Percent(100.0),
                                                     height: Val::Px(30.0),
@@ -971,16 +1135,26 @@ fn setup_ui(
                                                 ZIndex(1004),
                                             ))
                                             .with_children(|button| {
-                                                button.spawn((
-                                                    Text::new(option),
+                                                let mut text = button.spawn((
                                                     TextFont {
                                                         font: font_handle.clone(),
                                                         font_size: 16.0,
                                                         ..default()
                                                     },
-                                                    TextColor(Color::WHITE),
+                                                    TextColor(display_color(option.is_selected(&config))),
                                                     ZIndex(1005),
                                                 ));
+                                                match option {
+                                                    DisplayButton::Resolution(size) => {
+                                                        text.insert(Text::new(resolution_label(size)));
+                                                    }
+                                                    DisplayButton::Fullscreen => {
+                                                        text.insert(locale.ui_text("settings.full"));
+                                                    }
+                                                    _ => {
+                                                        text.insert(locale.ui_text("settings.window"));
+                                                    }
+                                                }
                                             });
                                     }
                                 });
@@ -1001,7 +1175,7 @@ fn setup_ui(
                                 .with_children(|speed_column| {
                                     // 快进标题
                                     speed_column.spawn((
-                                        Text::new("快进"),
+                                        locale.ui_text("settings.skip"),
                                         TextFont {
                                             font: font_handle.clone(),
                                             font_size: 24.0,
@@ -1016,35 +1190,39 @@ fn setup_ui(
                                     ));
 
                                     // 快进选项
-                                    let speed_options = vec!["未读文本", "选项后继续", "忽略转场"];
-                                    for option in speed_options {
-                                        speed_column
-                                            .spawn((
-                                                Button,
-                                                Node {
-                                                    width: Val::Percent(100.0),
-                                                    height: Val::Px(30.0),
-                                                    justify_content: JustifyContent::FlexStart,
-                                                    align_items: AlignItems::Center,
-                                                    margin: UiRect::bottom(Val::Px(10.0)),
-                                                    padding: UiRect::all(Val::Px(5.0)),
+                                    let speed_options = vec![
+                                        ("settings.skip_unread_option", SkipOption::Unread),
+                                        ("settings.skip_after_choices", SkipOption::AfterChoices),
+                                        ("settings.skip_transitions", SkipOption::Transitions),
+                                    ];
+                                    for (option, skip_option) in speed_options {
+                                        speed_column.spawn((
+                                            Button,
+                                            SkipOptionButton(skip_option),
+                                            Node {
+                                                width: Val::Percent(100.0),
+                                                height: Val::Px(30.0),
+                                                justify_content: JustifyContent::FlexStart,
+                                                align_items: AlignItems::Center,
+                                                margin: UiRect::bottom(Val::Px(10.0)),
+                                                padding: UiRect::all(Val::Px(5.0)),
+                                                ..default()
+                                            },
+                                            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
+                                            ZIndex(1004),
+                                        ))
+                                        .with_children(|button| {
+                                            button.spawn((
+                                                locale.ui_text(option),
+                                                TextFont {
+                                                    font: font_handle.clone(),
+                                                    font_size: 16.0,
                                                     ..default()
                                                 },
-                                                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
-                                                ZIndex(1004),
-                                            ))
-                                            .with_children(|button| {
-                                                button.spawn((
-                                                    Text::new(option),
-                                                    TextFont {
-                                                        font: font_handle.clone(),
-                                                        font_size: 16.0,
-                                                        ..default()
-                                                    },
-                                                    TextColor(Color::WHITE),
-                                                    ZIndex(1005),
-                                                ));
-                                            });
+                                                TextColor(Color::WHITE),
+                                                ZIndex(1005),
+                                            ));
+                                        });
                                     }
                                 });
 
@@ -1063,7 +1241,7 @@ fn setup_ui(
                                 .with_children(|language_column| {
                                     // 语言标题
                                     language_column.spawn((
-                                        Text::new("语言"),
+                                        locale.ui_text("settings.language"),
                                         TextFont {
                                             font: font_handle.clone(),
                                             font_size: 24.0,
@@ -1078,26 +1256,13 @@ fn setup_ui(
                                     ));
 
                                     // 语言选项
-                                    let languages = vec![
-                                        ("English", false),
-                                        ("Español", false),
-                                        ("Česky", false),
-                                        ("Українська", false),
-                                        ("Dansk", false),
-                                        ("日本語", false),
-                                        ("Français", false),
-                                        ("한국어", false),
-                                        ("Italiano", false),
-                                        ("简体中文", true),
-                                        ("Bahasa Melayu", false),
-                                        ("繁體中文", false),
-                                        ("Русский", false),
-                                    ];
-
-                                    for (language, is_selected) in languages {
+                                    for language in &config.languages {
+                                        let is_selected =
+                                            config.current_language() == Some(language.id.as_str());
                                         language_column
                                             .spawn((
                                                 Button,
+                                                LanguageButton(language.id.clone()),
                                                 Node {
                                                     width: Val::Percent(100.0),
                                                     height: Val::Px(30.0),
@@ -1107,22 +1272,18 @@ fn setup_ui(
                                                     padding: UiRect::all(Val::Px(5.0)),
                                                     ..default()
                                                 },
-                                                BackgroundColor(if is_selected {
-                                                    Color::srgba(1.0, 0.6, 0.2, 0.3)
-                                                } else {
-                                                    Color::srgba(0.0, 0.0, 0.0, 0.0) // 透明背景
-                                                }),
+                                                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)), // 透明背景
                                                 ZIndex(1004),
                                             ))
                                             .with_children(|button| {
                                                 button.spawn((
-                                                    Text::new(language),
+                                                    Text::new(language.name.clone()),
                                                     TextFont {
                                                         font: font_handle.clone(),
                                                         font_size: 16.0,
                                                         ..default()
                                                     },
-                                                    TextColor(Color::WHITE),
+                                                    TextColor(language_color(is_selected)),
                                                     ZIndex(1005),
                                                 ));
                                             });
@@ -1144,37 +1305,30 @@ fn update_dialogue(
     mut dialog_query: Query<(&Name, &mut Visibility, &mut Node), Without<Text>>, // 查询对话框容器
     mut query: Query<(&Name, &mut Text, &mut Visibility, Option<&mut TextColor>)>,
     time: Res<Time>, // 添加时间资源
+    typewriter: Res<DialogueTypewriter>,
+    config: Res<MainConfig>,
+    asset_server: Res<AssetServer>,
+    mut namebox_query: Query<(&Name, &mut TextFont, &mut ImageNode), With<Text>>,
+    voice_state: Res<VoiceState>,
+    locale: Res<Locale>,
 ) {
-    // 处理自动播放计时器
-    if game_state.is_auto_playing {
+    // 处理自动播放计时器（文字显示完、语音播放完后才开始计时）
+    if game_state.is_auto_playing && !typewriter.is_typing() && !voice_state.playing {
         game_state.auto_play_timer += time.delta_secs();
 
-        // 如果计时器达到设定时间，自动进入下一行
-        if game_state.auto_play_timer >= game_state.auto_play_interval {
+        // 有语音的行在语音结束后稍等片刻即进入下一行，否则按设定的间隔
+        let interval = if voice_state.line_has_voice {
+            VOICE_AUTO_ADVANCE_DELAY
+        } else {
+            config.settings.auto_play_interval
+        };
+        if game_state.auto_play_timer >= interval {
             game_state.auto_play_timer = 0.0;
 
-            // 先获取当前对话的跳转信息（如果有）
-            let jump_info = game_state
-                .dialogues
-                .get(game_state.current_line)
-                .and_then(|dialogue| dialogue.jump.as_ref())
-                .cloned(); // 克隆跳转标签以避免借用冲突
-
-            // 检查是否还有下一行对话
+            // 检查是否还有下一行对话，跳转与点击前进相同
             if game_state.current_line + 1 < game_state.dialogues.len() {
-                // 处理跳转或正常前进
-                if let Some(jump_label) = jump_info {
-                    if let Some(&new_line) = label_map.0.get(&jump_label) {
-                        game_state.current_line = new_line;
-                        println!("自动播放跳转到标签 '{}', 行号: {}", jump_label, new_line);
-                    } else {
-                        println!("错误: 找不到标签 '{}' 的跳转目标", jump_label);
-                        game_state.current_line += 1;
-                    }
-                } else {
-                    game_state.current_line += 1;
-                }
-                println!("自动播放进入第 {} 行", game_state.current_line);
+                game_state.advance();
+                println!("自动播放进入下一行");
             } else {
                 // 已经是最后一行，停止自动播放
                 game_state.is_auto_playing = false;
@@ -1195,7 +1349,7 @@ fn update_dialogue(
                 *visibility = Visibility::Hidden; // 隐藏 namebox
             }
             if name.as_str() == "textbox" {
-                text.0 = "感谢体验，按下ESC退出".to_string();
+                text.0 = locale.ui("game.end").to_string();
             }
         }
         println!("对话结束，当前行超出范围");
@@ -1203,30 +1357,59 @@ fn update_dialogue(
     };
 
     // 2. 显示当前对话内容
+    let character = config.get_character(&current_dialogue.character);
+
+    // 名字框样式
+    let namebox_style = character.map(|entry| &entry.namebox);
+    for (name, mut font, mut image) in namebox_query.iter_mut() {
+        if name.as_str() != "namebox" {
+            continue;
+        }
+        let font_size = namebox_style
+            .and_then(|style| style.font_size)
+            .unwrap_or(NAMEBOX_FONT_SIZE);
+        if font.font_size != font_size {
+            font.font_size = font_size;
+        }
+        let handle: Handle<Image> = asset_server.load(
+            namebox_style
+                .and_then(|style| style.image.clone())
+                .unwrap_or_else(|| NAMEBOX_IMAGE.to_string()),
+        );
+        if image.image != handle {
+            image.image = handle;
+        }
+        let tint = namebox_style
+            .and_then(|style| style.tint.as_deref())
+            .and_then(parse_color)
+            .map_or(Color::WHITE, |[r, g, b, a]| Color::srgba(r, g, b, a));
+        if image.color != tint {
+            image.color = tint;
+        }
+    }
+
     for (name, mut text, mut visibility, text_color) in &mut query {
         if name.as_str() == "namebox" {
             if current_dialogue.character == "none" {
                 *visibility = Visibility::Hidden; // 如果 character 为 "none", 隐藏 namebox
             } else {
                 *visibility = Visibility::Visible;
-                text.0 = current_dialogue.character.to_string();
+                text.0 = locale.character_name(&config, &current_dialogue.character, &game_state.variables);
 
-                // 根据角色名称设置不同颜色
+                // 名字颜色来自 main.yaml 的角色表，未设置时为白色
                 if let Some(mut color) = text_color {
-                    match current_dialogue.character.as_str() {
-                        "希尔薇" => color.0 = Color::srgb(0.761, 1.0, 0.8), // 粉红色
-                        "我" => color.0 = Color::srgb(0.3, 0.7, 1.0),       // 蓝色
-                        "艾莉娅" => color.0 = Color::srgb(0.8, 0.6, 1.0),   // 紫色
-                        "莉莉" => color.0 = Color::srgb(1.0, 0.8, 0.3),     // 金色
-                        _ => color.0 = Color::WHITE,                        // 默认白色
+                    let name_color = character
+                        .and_then(|entry| entry.color.as_deref())
+                        .and_then(parse_color)
+                        .map_or(Color::WHITE, |[r, g, b, a]| Color::srgba(r, g, b, a));
+                    if color.0 != name_color {
+                        color.0 = name_color;
                     }
                 }
             }
         }
 
-        if name.as_str() == "textbox" {
-            text.0 = current_dialogue.text.to_string();
-        }
+        // textbox 的文字由 dialogue_text 按标签和打字速度生成
     }
 
     // 查找对话框容器
@@ -1241,7 +1424,8 @@ fn update_dialogue(
         }
     }
 
-    if let Some(jump_label) = &current_dialogue.jump {
+    if let Some(jump) = &current_dialogue.jump {
+        let jump_label = jump.label();
         if let Some(&new_line) = label_map.0.get(jump_label) {
             println!(
                 "显示行 {}: 角色='{}', 标签={:?}, 跳转={:?}",
@@ -1267,6 +1451,11 @@ fn handle_input(
     music_controller: Query<&AudioSink, With<MyMusic>>,
     mut commands: Commands,
     config: Res<MainConfig>,
+    slot_menu: Res<SlotMenuState>,
+    backlog_state: Res<BacklogState>,
+    mut rollback_events: MessageWriter<RollbackEventMessage>,
+    mut typewriter: ResMut<DialogueTypewriter>,
+    mut motions: Query<&mut MotionPlayer>,
 ) {
     // println!("===============");
     if let Some(dialogue) = game_state.dialogues.get(game_state.current_line) {
@@ -1295,6 +1484,11 @@ fn handle_input(
         std::process::exit(0);
     }
 
+    // 存档/读档或历史界面打开时不推进对话
+    if slot_menu.mode.is_some() || backlog_state.open {
+        return;
+    }
+
     // 数字键快速跳转（始终可用）
     for key in keys.get_just_pressed() {
         match key {
@@ -1313,22 +1507,12 @@ fn handle_input(
     for key in keys.get_just_pressed() {
         println!("handle_input 检测到按键: {:?}", key);
     }
-    if back_pressed
-        && config.settings.rewind
-        && game_state.can_go_back
-        && game_state.current_line > 0
-    {
-        game_state.current_line -= 1;
+    // 按实际经过的路径回退（是否启用由 settings.rewind 决定）
+    if back_pressed && config.settings.rewind && game_state.can_go_back {
+        rollback_events.write(RollbackEventMessage);
         play_sound(&back_sound.0, commands.reborrow());
-
-        // 只有在到达第一行时才禁用回退
-        if game_state.current_line == 0 {
-            game_state.can_go_back = false;
-        }
-    } else {
-        if config.settings.rewind == false {
-            game_state.can_go_back = false;
-        }
+    } else if !config.settings.rewind {
+        game_state.can_go_back = false;
     }
 
     // 如果在分支选择状态，禁用前进操作
@@ -1337,7 +1521,9 @@ fn handle_input(
     }
 
     // 检测前进输入（键盘 + 鼠标 + 点击区域）
-    let keyboard_click = keys.just_pressed(KeyCode::Space) || keys.just_pressed(KeyCode::Enter);
+    // Alt+Enter 用于切换全屏，不推进对话
+    let keyboard_click = keys.just_pressed(KeyCode::Space)
+        || (keys.just_pressed(KeyCode::Enter) && !fullscreen_hotkey_pressed(&keys));
     let mouse_click = mouse.just_pressed(MouseButton::Left);
 
     // 检查点击区域
@@ -1354,17 +1540,24 @@ fn handle_input(
     let should_advance = keyboard_click || mouse_click || click_area_pressed;
     let should_advance = keyboard_click || click_area_pressed;
     if should_advance && game_state.current_line < game_state.dialogues.len() {
-        let current_dialogue = &game_state.dialogues[game_state.current_line];
-
-        // 检查是否有跳转指令
-        if let Some(jump_label) = &current_dialogue.jump {
-            game_state.jump_label = Some(jump_label.clone());
-        } else {
-            // 没有跳转指令则前进到下一行
-            game_state.current_line += 1;
+        // 文字还没显示完时，先显示到下一个暂停点
+        if typewriter.is_typing() {
+            typewriter.click();
+            return;
         }
-
-        game_state.can_go_back = true;
+        // 变换动画还在播放时，先直接播放完（一直重复的动画不等待）
+        let mut finished_motion = false;
+        for mut player in motions.iter_mut() {
+            if player.is_finite() && player.is_playing() {
+                player.finish();
+                finished_motion = true;
+            }
+        }
+        if finished_motion {
+            return;
+        }
+        // 检查是否有跳转指令（条件不成立时按普通行处理）
+        game_state.advance();
         play_sound(&back_sound.0, commands.reborrow());
     }
 }
@@ -1395,9 +1588,12 @@ fn handle_input(
 //     }
 // }
 fn update_portrait(
+    config: Res<MainConfig>,
     game_state: Res<GameState>,
     portraits: Res<PortraitAssets>,
     mut query: Query<(&mut Sprite, &mut Name, &mut Visibility)>,
+    // 已经提示过的（立绘, 表情），每帧都会执行，只提示一次
+    mut warned: Local<HashSet<(String, String)>>,
 ) {
     // 先保存查询结果到变量
     // 遍历所有实体，检查名称
@@ -1407,8 +1603,24 @@ fn update_portrait(
             if let Some(dialogue) = game_state.dialogues.get(game_state.current_line) {
                 // println!("数据测试,{}",game_state.current_line);
                 // 从资源映射中获取立绘路径
-                match portraits.handles.get(&dialogue.portrait) {
+                // 未写表情时使用角色表中的默认表情
+                let expression = dialogue.expression.as_deref().or_else(|| {
+                    config
+                        .get_character(&dialogue.portrait)
+                        .and_then(|entry| entry.expression.as_deref())
+                });
+                let warn_key = || (dialogue.portrait.clone(), expression.unwrap_or_default().to_string());
+                match portraits.get(&dialogue.portrait, expression) {
                     Some(handle) => {
+                        if !portraits.has_expression(&dialogue.portrait, expression)
+                            && warned.insert(warn_key())
+                        {
+                            eprintln!(
+                                "找不到角色 {} 的表情 {}，使用默认立绘",
+                                dialogue.portrait,
+                                expression.unwrap_or_default()
+                            );
+                        }
                         // println!("{:?}", "=============");
                         // println!("{:?}", texture_handle);
                         // custom_size:Some(Vec2 { x: 400.0, y: 600.0 });
@@ -1420,7 +1632,9 @@ fn update_portrait(
                     None => {
                         // 找不到立绘时隐藏
                         *visibility = Visibility::Hidden;
-                        eprintln!("找不到立绘资源: {}", dialogue.portrait);
+                        if warned.insert(warn_key()) {
+                            eprintln!("找不到立绘资源: {}", dialogue.portrait);
+                        }
                     }
                 }
             } else {
@@ -1506,6 +1720,7 @@ fn play_sound(audio_handle: &Handle<AudioSource>, mut commands: Commands) {
     commands.spawn((
         AudioPlayer::new(audio_handle.clone()),
         PlaybackSettings::ONCE,
+        MixerSound::new(AudioChannel::Ui),
     ));
 }
 fn apply_jump(label_map: Res<LabelMap>, mut game_state: ResMut<GameState>) {
@@ -1520,6 +1735,107 @@ fn apply_jump(label_map: Res<LabelMap>, mut game_state: ResMut<GameState>) {
     }
 }
 
+// 进入新的一行：跳过条件不成立的行，记录历史，并执行这一行的 set
+fn enter_current_line(
+    mut commands: Commands,
+    config: Res<MainConfig>,
+    mut game_state: ResMut<GameState>,
+    mut backlog: ResMut<Backlog>,
+    mut history: ResMut<RollbackHistory>,
+    mut stage: ResMut<Stage>,
+    mut camera: ResMut<CameraEffects>,
+    current_audio: Res<CurrentAudio>,
+    locale: Res<Locale>,
+    background_query: Query<(&Name, &Visibility), With<Background>>,
+    swf_query: Query<(&Name, &Visibility), With<Flash>>,
+    mut portrait_query: Query<(Entity, &Name, &mut Sprite, &mut Transform, Option<&MotionPlayer>)>,
+) {
+    if game_state.entered_line == Some(game_state.current_line) {
+        return;
+    }
+
+    while game_state.current_line < game_state.dialogues.len()
+        && !game_state.line_active(game_state.current_line)
+    {
+        println!("条件不成立，跳过第 {} 行", game_state.current_line);
+        game_state.current_line += 1;
+    }
+
+    let current_line = game_state.current_line;
+    game_state.entered_line = Some(current_line);
+
+    // 记录回退历史（进入这一行之前的画面和变量）
+    let history_index = history.entries.len();
+    history.entries.push(HistoryEntry {
+        line: current_line,
+        presentation: Presentation::capture(
+            background_query.iter(),
+            swf_query.iter(),
+            &current_audio,
+            &stage,
+            &camera,
+        ),
+        variables: game_state.variables.clone(),
+        in_branch_selection: game_state.in_branch_selection,
+    });
+
+    let sets = game_state
+        .dialogues
+        .get(current_line)
+        .and_then(|d| d.set.clone());
+    if let Some(sets) = sets {
+        game_state.apply_sets(&sets);
+        println!("第 {} 行执行赋值: {:?}", current_line, sets);
+    }
+
+    // 角色登场/退场，保持到被 hide 为止
+    if let Some(dialogue) = game_state.dialogues.get(current_line) {
+        if dialogue.show.is_some() || dialogue.hide.is_some() {
+            stage.transition = dialogue.stage_transition();
+            stage.apply_directions(dialogue.show.as_deref(), dialogue.hide.as_deref());
+        }
+        for effect in dialogue.camera_effects() {
+            camera.play(&mut commands, &effect);
+        }
+        // 说话角色头像的变换动画只在这一行播放，没有写的行回到原来的样子
+        let motion = dialogue
+            .transform
+            .as_deref()
+            .and_then(|source| Motion::parse_or_warn(source, &config.transforms));
+        for (entity, name, mut sprite, mut transform, player) in portrait_query.iter_mut() {
+            if name.as_str() != "spritebox" {
+                continue;
+            }
+            match motion.clone() {
+                Some(motion) => play_motion(&mut commands, entity, motion),
+                None => {
+                    if let Some(player) = player {
+                        player.reset(&mut sprite, &mut transform);
+                        commands.entity(entity).remove::<MotionPlayer>();
+                    }
+                }
+            }
+        }
+    }
+
+    // 记录到历史（text 为 none 的行不显示对话框，不记录）；变量按显示时的值替换
+    if let Some(dialogue) = game_state.dialogues.get(current_line) {
+        if dialogue.text != "none" {
+            backlog.entries.push(BacklogEntry {
+                line: current_line,
+                character: locale.character_name(&config, &dialogue.character, &game_state.variables),
+                text: parse_markup(locale.dialogue_text(dialogue), &game_state.variables).plain_text(),
+                choice: None,
+                voice: dialogue.voice.as_ref().map(|voice| PlayVoiceMessage {
+                    path: voice_path(&config, &dialogue.character, voice),
+                    tag: voice_tag(&config, &dialogue.character).to_string(),
+                }),
+                history_index,
+            });
+        }
+    }
+}
+
 // 预加载系统
 // fn preload_sounds(asset_server: Res<AssetServer>) {
 //     asset_server.load::<AudioSource>("button.ogg");
@@ -1641,7 +1957,8 @@ fn update_swf(
 // 更新背景
 fn update_background(
     game_state: Res<GameState>,
-    mut query: Query<(&Name, &mut Visibility), With<Background>>,
+    mut query: Query<(Entity, &Name, &Sprite, &mut Visibility), With<Background>>,
+    outgoing: Query<Entity, With<OutgoingBackground>>,
     mut commands: Commands,
 ) {
     if let Some(dialogue) = game_state.dialogues.get(game_state.current_line) {
@@ -1650,68 +1967,73 @@ fn update_background(
 
             // 检查当前是否已经显示了这个背景
             let mut current_visible = None;
-            let mut target_exists = false;
+            let mut target = None;
 
-            for (name, visibility) in query.iter() {
+            for (entity, name, _, visibility) in query.iter() {
                 if *visibility == Visibility::Visible {
-                    current_visible = Some(name.as_str());
+                    current_visible = Some((entity, name.as_str()));
                 }
                 if name.as_str() == target_bg {
-                    target_exists = true;
+                    target = Some(entity);
                 }
             }
 
-            // 如果目标背景存在且与当前背景不同，执行渐变切换
-            if target_exists && current_visible.as_ref() != Some(&target_bg.as_str()) {
-                println!("切换背景: {:?} -> {}", current_visible, target_bg);
+            // 如果目标背景存在且与当前背景不同，按这一行的转场切换
+            let Some(target) = target else {
+                return;
+            };
+            if current_visible.is_some_and(|(entity, _)| entity == target) {
+                return;
+            }
+            let transition = dialogue.background_transition();
+            println!(
+                "切换背景: {:?} -> {} ({:?})",
+                current_visible.map(|(_, name)| name),
+                target_bg,
+                transition
+            );
 
-                // 直接调用你的渐变函数
-                fade_in(&mut commands, 0.8);
+            // 上一次切换的转场尚未结束时直接结束
+            for entity in outgoing.iter().chain(query.iter().map(|(entity, ..)| entity)) {
+                finish_transition(&mut commands, entity);
+            }
 
-                // 更新背景可见性
-                for (name, mut visibility) in query.iter_mut() {
-                    if name.as_str() == target_bg {
-                        *visibility = Visibility::Visible;
-                    } else {
-                        *visibility = Visibility::Hidden;
-                    }
+            // 旧背景的副本放在新背景之下，按转场消失后移除；背景固定在 (0, 0, -10)
+            let old_copy = current_visible
+                .and_then(|(entity, _)| query.get(entity).ok())
+                .map(|(_, _, sprite, _)| {
+                    commands
+                        .spawn((
+                            Name::new("outgoing_background"),
+                            OutgoingBackground,
+                            Sprite {
+                                image: sprite.image.clone(),
+                                custom_size: sprite.custom_size,
+                                ..default()
+                            },
+                            Transform::from_xyz(0.0, 0.0, -10.5),
+                            Visibility::Visible,
+                        ))
+                        .id()
+                });
+            start_transition(&mut commands, transition, old_copy, Some(target));
+
+            // 更新背景可见性
+            for (entity, _, _, mut visibility) in query.iter_mut() {
+                if entity == target {
+                    *visibility = Visibility::Visible;
+                } else {
+                    *visibility = Visibility::Hidden;
                 }
             }
         } else {
             // 没有背景时，隐藏所有背景
-            for (_, mut visibility) in query.iter_mut() {
+            for (_, _, _, mut visibility) in query.iter_mut() {
                 *visibility = Visibility::Hidden;
             }
         }
     }
 }
-fn keyboard_system(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut game_state: ResMut<GameState>,
-    back_click_sound: Res<BackClickSound>,
-    mut commands: Commands,
-) {
-    // 向左箭头键（回退）在分支选择状态下仍然可用
-    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
-        if game_state.can_go_back && game_state.current_line > 0 {
-            game_state.current_line -= 1;
-            // play_sound(&back_click_sound.0, commands);
-
-            if game_state.current_line == 0 {
-                game_state.can_go_back = false;
-            }
-
-            // 如果回退导致离开了分支选择的位置，退出分支选择状态
-            // 这里你可以根据具体逻辑调整
-            if game_state.in_branch_selection && game_state.current_line < 5 {
-                // 假设第5行是分支选择
-                game_state.in_branch_selection = false;
-            }
-
-            println!("回退到第 {} 行", game_state.current_line);
-        }
-    }
-}
 fn button_interaction_system(
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor, &mut BorderColor, &Name),
@@ -1749,6 +2071,7 @@ fn button_interaction_system(
 fn create_dynamic_buttons(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
     mut game_state: ResMut<GameState>,
     existing_buttons: Query<Entity, With<DynamicButton>>,
     button_container: Query<Entity, With<ButtonContainer>>,
@@ -1758,12 +2081,7 @@ fn create_dynamic_buttons(
 
     // 先检查是否有对话和选择，但不借用
     let has_dialogue = game_state.dialogues.get(current_line).is_some();
-    let has_choices = game_state
-        .dialogues
-        .get(current_line)
-        .and_then(|d| d.choices.as_ref())
-        .map(|choices| choices.len() > 0)
-        .unwrap_or(false);
+    let has_choices = !game_state.visible_choices(current_line).is_empty();
 
     if has_dialogue {
         if has_choices {
@@ -1781,71 +2099,76 @@ fn create_dynamic_buttons(
                 commands.entity(entity).despawn();
             }
 
-            if let Some(dialogue) = game_state.dialogues.get(current_line) {
-                if let Some(choices) = &dialogue.choices {
-                    println!("发现 {} 个选择分支", choices.len());
-
-                    if let Ok(container) = button_container.single() {
-                        for (index, choice) in choices.iter().enumerate() {
-                            // 创建按钮的代码...
-                            commands.entity(container).with_children(|parent| {
-                                parent
-                                    .spawn((
-                                        Button,
-                                        DynamicButton,
-                                        ClickHandler(choice.goto.to_string()),
-                                        Interaction::default(),
-                                        Name::new(format!("choice_{}", index)),
-                                        // 你的按钮样式代码...
-                                        Node {
-                                            position_type: PositionType::Relative,
-                                            bottom: Val::Px(100.0),
-                                            top: Val::Px(-220.0),
-                                            left: Val::Px(320.0),
-                                            width: Val::Px(700.0),
-                                            height: Val::Px(40.0),
-                                            border: UiRect::all(Val::Px(2.0)),
-                                            justify_content: JustifyContent::Center,
-                                            align_items: AlignItems::Center,
-                                            padding: UiRect {
-                                                left: Val::Px(2.0),
-                                                right: Val::Px(2.0),
-                                                top: Val::Px(5.0),
-                                                bottom: Val::Px(5.0),
-                                            },
-                                            ..default()
-                                        },
-                                        ImageNode::new(
-                                            asset_server.load("gui/choice_idle_background2.png"),
-                                        ),
-                                        ButtonImages {
-                                            normal: asset_server
-                                                .load("gui/choice_idle_background2.png"),
-                                            hovered: asset_server
-                                                .load("gui/choice_hover_background2.png"),
-                                            pressed: asset_server
-                                                .load("gui/choice_hover_background2.png"),
-                                        },
-                                        // BackgroundColor(NORMAL_BUTTON),
-                                        // BorderColor(Color::BLACK),
-                                        // BorderRadius::all(Val::Px(5.0)),
-                                        Visibility::Visible,
-                                    ))
-                                    .with_children(|button| {
-                                        button.spawn((
-                                            Text::new(choice.text.clone()),
-                                            TextFont {
-                                                font: asset_server
-                                                    .load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                font_size: 17.0,
-                                                ..default()
-                                            },
-                                            TextColor(Color::WHITE),
-                                        ));
-                                    });
+            let choices = game_state.visible_choices(current_line);
+            let dialogue = &game_state.dialogues[current_line];
+            println!("发现 {} 个选择分支", choices.len());
+
+            if let Ok(container) = button_container.single() {
+                for (index, choice) in choices {
+                    // 创建按钮的代码...
+                    commands.entity(container).with_children(|parent| {
+                        parent
+                            .spawn((
+                                Button,
+                                DynamicButton,
+                                ClickHandler(choice.goto.to_string()),
+                                ChoiceIndex(index),
+                                Interaction::default(),
+                                Name::new(format!("choice_{}", index)),
+                                // 你的按钮样式代码...
+                                Node {
+                                    position_type: PositionType::Relative,
+                                    bottom: Val::Px(100.0),
+                                    top: Val::Px(-220.0),
+                                    left: Val::Px(320.0),
+                                    width: Val::Px(700.0),
+                                    height: Val::Px(40.0),
+                                    border: UiRect::all(Val::Px(2.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    padding: UiRect {
+                                        left: Val::Px(2.0),
+                                        right: Val::Px(2.0),
+                                        top: Val::Px(5.0),
+                                        bottom: Val::Px(5.0),
+                                    },
+                                    ..default()
+                                },
+                                ImageNode::new(
+                                    asset_server.load("gui/choice_idle_background2.png"),
+                                ),
+                                ButtonImages {
+                                    normal: asset_server
+                                        .load("gui/choice_idle_background2.png"),
+                                    hovered: asset_server
+                                        .load("gui/choice_hover_background2.png"),
+                                    pressed: asset_server
+                                        .load("gui/choice_hover_background2.png"),
+                                },
+                                // BackgroundColor(NORMAL_BUTTON),
+                                // BorderColor(Color::BLACK),
+                                // BorderRadius::all(Val::Px(5.0)),
+                                Visibility::Visible,
+                            ))
+                            .with_children(|button| {
+                                button.spawn((
+                                    Text::new(
+                                        parse_markup(
+                                            locale.choice_text(dialogue, index),
+                                            &game_state.variables,
+                                        )
+                                        .plain_text(),
+                                    ),
+                                    TextFont {
+                                        font: asset_server
+                                            .load("fonts/GenSenMaruGothicTW-Bold.ttf"),
+                                        font_size: 17.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::WHITE),
+                                ));
                             });
-                        }
-                    }
+                    });
                 }
             }
         } else {
@@ -1862,17 +2185,28 @@ fn create_dynamic_buttons(
         }
     }
 }
+// 切换语言后移除选项按钮，由 create_dynamic_buttons 按新语言重新生成
+fn refresh_choice_language(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    buttons: Query<Entity, With<DynamicButton>>,
+) {
+    if !locale.is_changed() || locale.is_added() {
+        return;
+    }
+    for entity in buttons.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
 fn should_create_buttons(
     game_state: Res<GameState>,
     existing_buttons: Query<(), With<DynamicButton>>,
 ) -> bool {
     let current_line = game_state.current_line;
 
-    // 检查当前行是否有选择分支
-    let has_choices = game_state.dialogues.get(current_line)
-        .and_then(|d| d.choices.as_ref())
-        .map(|choices| !choices.is_empty())
-        .unwrap_or(false);
+    // 检查当前行是否有（满足条件的）选择分支
+    let has_choices = !game_state.visible_choices(current_line).is_empty();
 
     // 检查是否已经有按钮存在
     let buttons_exist = !existing_buttons.is_empty();
@@ -1883,29 +2217,63 @@ fn should_create_buttons(
 
 fn handle_choice_buttons(
     mut interaction_query: Query<
-        (&Interaction, &ClickHandler),
+        (&Interaction, &ClickHandler, &ChoiceIndex),
         (Changed<Interaction>, With<DynamicButton>),
     >,
     mut game_state: ResMut<GameState>,
+    mut backlog: ResMut<Backlog>,
+    label_map: Res<LabelMap>,
     click_sound: Res<ClickSound>,
+    locale: Res<Locale>,
     mut commands: Commands,
 ) {
-    for (interaction, click_handler) in &interaction_query {
+    for (interaction, click_handler, choice_index) in &interaction_query {
         if *interaction == Interaction::Pressed {
             // play_sound(&click_sound.0, commands);
 
-            // 解析跳转目标
-            if let Ok(goto_line) = click_handler.0.parse::<usize>() {
-                game_state.current_line = goto_line;
-                game_state.can_go_back = true;
-                game_state.in_branch_selection = false;
-                println!("跳转到第 {} 行", goto_line);
+            let Some(dialogue) = game_state.dialogues.get(game_state.current_line) else {
+                continue;
+            };
+            let Some(choice) = dialogue
+                .choices
+                .as_ref()
+                .and_then(|choices| choices.get(choice_index.0))
+            else {
+                continue;
+            };
+            let sets = choice.set.clone();
+            // 解析跳转目标（标签或行号）
+            let target = choice.goto.resolve(&label_map.0);
+            backlog.record_choice(
+                game_state.current_line,
+                &parse_markup(locale.choice_text(dialogue, choice_index.0), &game_state.variables)
+                    .plain_text(),
+            );
+
+            // 执行选项的赋值
+            if let Some(sets) = sets {
+                game_state.apply_sets(&sets);
+            }
+
+            match target {
+                Some(goto_line) => {
+                    game_state.current_line = goto_line;
+                    game_state.can_go_back = true;
+                    game_state.in_branch_selection = false;
+                    println!("跳转到第 {} 行", goto_line);
+                }
+                None => eprintln!("错误: 找不到选项的跳转目标 '{}'", click_handler.0),
             }
         }
     }
 }
 
 // 函数库
+// 回到菜单时镜头恢复原样
+fn reset_camera_effects(mut camera: ResMut<CameraEffects>) {
+    camera.restore(CameraView::default());
+}
+
 fn cleanup_game(
     mut commands: Commands,
     // 查询所有需要清理的实体
@@ -1914,6 +2282,7 @@ fn cleanup_game(
         Or<(
             With<Portrait>,
             With<Background>,
+            With<OutgoingBackground>,
             With<ClickArea>,
             With<ButtonContainer>,
             With<DynamicButton>,
@@ -1945,6 +2314,9 @@ fn cleanup_game(
             _ if name.as_str().starts_with("swf_") => {
                 commands.entity(entity).despawn();
             }
+            _ if name.as_str().starts_with("stage_") => {
+                commands.entity(entity).despawn();
+            }
             _ if name.as_str().starts_with("choice_") => {
                 commands.entity(entity).despawn();
             }
@@ -2071,54 +2443,7 @@ fn menu_exit_system(mut commands: Commands) {
 //     }
 // }
 
-// 缓动函数 - 超级平滑的渐入效果
-fn ease_out_expo(t: f32) -> f32 {
-    if t == 1.0 {
-        1.0
-    } else {
-        1.0 - 2.0_f32.powf(-10.0 * t)
-    }
-}
-fn ren_py_dissolve(t: f32) -> f32 {
-    // Ren'Py 实际使用的是一个分段的平滑曲线
-    if t < 0.1 {
-        // 前10%非常缓慢
-        t * t * 5.0
-    } else if t < 0.8 {
-        // 中间70%线性但稍有加速
-        0.05 + (t - 0.1) * 1.2
-    } else {
-        // 最后20%快速完成
-        0.89 + (t - 0.8) * 0.55 * (2.0 - t)
-    }
-}
-// 可选的其他缓动函数
-fn ease_out_sine(t: f32) -> f32 {
-    (t * std::f32::consts::PI / 2.0).sin()
-}
 
-fn ease_out_cubic(t: f32) -> f32 {
-    1.0 - (1.0 - t).powi(3)
-}
-
-fn ease_in_out_cubic(t: f32) -> f32 {
-    if t < 0.5 {
-        4.0 * t * t * t
-    } else {
-        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
-    }
-}
-
-// 函数测试
-fn update_typewriter(
-    mut query: Query<(&mut Text, &mut TypewriterText)>, // 查询同时拥有Text和TypewriterText组件的实体
-) {
-    for (mut text, mut typewriter) in query.iter_mut() {
-        if typewriter.is_active { // 这里 typewriter 是 Mut<TypewriterText> 而不是 TypewriterText
-            // println!("{}",typewriter.is_active);
-        }
-    }
-}
 
 // fn update_sidebox(
 //     game_state: Res<GameState>,
@@ -2178,52 +2503,88 @@ fn update_typewriter(
 //     }
 
 // }
+// 进入新的一行时按它的 bgm 切换、停止或继续播放 BGM
 fn update_audio(
     game_state: Res<GameState>,
+    history: Res<RollbackHistory>,
+    skip_state: Res<SkipState>,
     mut current_audio: ResMut<CurrentAudio>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     config: Res<MainConfig>, // 添加配置资源
 ) {
-    if let Some(dialogue) = game_state.dialogues.get(game_state.current_line) {
-        if let Some(bgm_key) = &dialogue.bgm {
-            let target_bgm = bgm_key.clone();
-            let current_playing = current_audio.current_bgm.as_ref();
-            let bgm_changed = current_playing != Some(&target_bgm);
+    let current_line = game_state.current_line;
+    let key = (current_line, history.entries.len());
+    if game_state.entered_line != Some(current_line) || current_audio.entered == Some(key) {
+        return;
+    }
+    current_audio.entered = Some(key);
 
-            if bgm_changed {
-                println!("切换BGM: {:?} -> {}", current_playing, target_bgm);
+    if let Some(dialogue) = game_state.dialogues.get(current_line) {
+        let bgm = match dialogue.bgm_change() {
+            BgmChange::Keep => return,
+            BgmChange::Stop => None,
+            BgmChange::Play(bgm) => Some(bgm),
+        };
+        // 快进时直接切换
+        let fade = if skip_state.is_skipping() {
+            0.0
+        } else {
+            dialogue.bgm_fade.unwrap_or(config.settings.bgm_fade)
+        };
+        switch_bgm(
+            &mut commands,
+            &asset_server,
+            &config,
+            &mut current_audio,
+            bgm,
+            fade,
+        );
+    }
+}
 
-                // 停止当前播放的音频实体
-                if let Some(entity) = current_audio.current_entity {
-                    commands.entity(entity).despawn();
-                }
+// 切换到指定BGM，None 表示停止当前播放（存档读取时也会用到）
+// fade 为淡出旧 BGM、淡入新 BGM 的秒数，两者同时进行（交叉淡化）
+fn switch_bgm(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    config: &MainConfig,
+    current_audio: &mut CurrentAudio,
+    bgm_key: Option<&str>,
+    fade: f32,
+) {
+    let current_playing = current_audio.current_bgm.as_deref();
+    if current_playing == bgm_key {
+        return;
+    }
+    println!("切换BGM: {:?} -> {:?}（淡入淡出 {} 秒）", current_playing, bgm_key, fade);
 
-                // 从配置文件获取正确的音频路径
-                if let Some(audio_path) = config.assets.audio.bgm.get(bgm_key) {
-                    println!("加载BGM文件: {}", audio_path);
-                    
-                    // 加载并播放音频
-                    let audio_handle: Handle<AudioSource> = asset_server.load(audio_path);
-                    let new_entity = commands.spawn((
-                        AudioPlayer::new(audio_handle),
-                        PlaybackSettings::LOOP, // 循环播放BGM
-                    )).id();
-
-                    // 更新状态
-                    current_audio.current_bgm = Some(target_bgm);
-                    current_audio.current_entity = Some(new_entity);
-                } else {
-                    eprintln!("错误: 在配置文件中找不到BGM '{}'", bgm_key);
-                }
-            }
-        } else {
-            // 没有BGM时，停止当前播放
-            if let Some(entity) = current_audio.current_entity {
-                commands.entity(entity).despawn();
-                current_audio.current_bgm = None;
-                current_audio.current_entity = None;
-            }
+    // 淡出当前播放的音频实体
+    let previous = current_audio.current_entity.take();
+    current_audio.current_bgm = None;
+
+    // 没有BGM时，停止当前播放
+    let Some(bgm_key) = bgm_key else {
+        if let Some(entity) = previous {
+            fade_out_audio(commands, entity, fade);
+        }
+        return;
+    };
+
+    // 从配置文件获取正确的音频路径
+    if let Some(audio_path) = config.assets.audio.bgm.get(bgm_key) {
+        println!("加载BGM文件: {}", audio_path);
+
+        // 加载并循环播放BGM
+        let new_entity = crossfade_music(commands, asset_server, previous, audio_path, fade);
+
+        // 更新状态
+        current_audio.current_bgm = Some(bgm_key.to_string());
+        current_audio.current_entity = Some(new_entity);
+    } else {
+        eprintln!("错误: 在配置文件中找不到BGM '{}'", bgm_key);
+        if let Some(entity) = previous {
+            fade_out_audio(commands, entity, fade);
         }
     }
 }
@@ -2309,21 +2670,44 @@ fn handle_close_settings_button(
     }
 }
 
-// 回退系统
-fn handle_rollback_event(
-    mut rollback_events: EventReader<RollbackEventMessage>,
-    mut game_state: ResMut<GameState>, // 假设 GameState 在这里定义或导入
+// 设置菜单左侧的菜单项：历史 / 保存 / 读取游戏
+fn handle_settings_menu_items(
+    interaction_query: Query<(&Interaction, &SettingsMenuItem), Changed<Interaction>>,
+    mut menu_query: Query<&mut Visibility, With<SettingsMenu>>,
+    mut open_history_event: MessageWriter<OpenHistoryEventMessage>,
+    mut open_save_menu_event: MessageWriter<OpenSaveMenuEventMessage>,
+    mut open_load_menu_event: MessageWriter<OpenLoadMenuEventMessage>,
 ) {
-    for _event in rollback_events.read() {
-        if game_state.current_line > 0 {
-            game_state.current_line -= 1;
-            println!("回退到第 {} 行", game_state.current_line);
-        } else {
-            println!("已经是第一行,无法回退");
+    for (interaction, menu_item) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let opened = match menu_item.0 {
+            SelectedMenuItem::History => {
+                open_history_event.write(OpenHistoryEventMessage);
+                true
+            }
+            SelectedMenuItem::Save => {
+                open_save_menu_event.write(OpenSaveMenuEventMessage);
+                true
+            }
+            SelectedMenuItem::Load => {
+                open_load_menu_event.write(OpenLoadMenuEventMessage);
+                true
+            }
+            _ => false,
+        };
+        // 打开其他界面时收起设置菜单
+        if opened {
+            if let Ok(mut visibility) = menu_query.single_mut() {
+                *visibility = Visibility::Hidden;
+            }
         }
     }
 }
 
+// 回退系统
+
 // 自动播放系统
 fn handle_auto_play_event(
     mut auto_play_events: EventReader<ToggleAutoPlayEventMessage>,
@@ -2343,6 +2727,25 @@ fn handle_auto_play_event(
     }
 }
 
+// 打开/关闭覆盖层时切换点击区域（关闭时恢复推进对话）
+fn set_click_area_enabled<F: bevy::ecs::query::QueryFilter>(
+    named_query: &mut Query<(&Name, &mut Visibility, &mut FocusPolicy), F>,
+    enabled: bool,
+) {
+    for (name, mut visibility, mut focus_policy) in named_query.iter_mut() {
+        if name.as_str() == "click_area" {
+            if enabled {
+                *visibility = Visibility::Visible;
+                *focus_policy = FocusPolicy::Pass;
+            } else {
+                *visibility = Visibility::Hidden;
+                *focus_policy = FocusPolicy::Block;
+            }
+            break;
+        }
+    }
+}
+
 // 设置控制系统
 fn handle_settings_close_and_show_click_area(
     mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<CloseSettingsButton>)>,
@@ -0,0 +1,226 @@
+// src/game/rollback.rs
+// 回退：记录实际经过的每一行及当时的画面状态，按真实路径回退
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_flash::player::Flash;
+
+use super::{
+    Background, CurrentAudio, DynamicButton, GameState, backlog::Backlog, skip::SkipState,
+    stage::Stage, switch_bgm,
+};
+use crate::{
+    GameScene,
+    config::{MainConfig, VariableValue},
+    effect::{CameraEffects, CameraView},
+    script::dialogue::ShowCharacter,
+    toolbar::RollbackEventMessage,
+};
+
+/// 画面状态：当前显示的背景、BGM、SWF、同屏角色（均为 main.yaml 中的名称）与镜头
+#[derive(Debug, Clone, Default)]
+pub struct Presentation {
+    pub background: Option<String>,
+    pub bgm: Option<String>,
+    pub swf: Option<String>,
+    pub characters: Vec<ShowCharacter>,
+    pub camera: CameraView,
+}
+
+impl Presentation {
+    /// 读取当前画面状态
+    pub fn capture<'a>(
+        backgrounds: impl Iterator<Item = (&'a Name, &'a Visibility)>,
+        swfs: impl Iterator<Item = (&'a Name, &'a Visibility)>,
+        current_audio: &CurrentAudio,
+        stage: &Stage,
+        camera: &CameraEffects,
+    ) -> Self {
+        Self {
+            background: visible_name(backgrounds, "background_"),
+            bgm: current_audio.current_bgm.clone(),
+            swf: visible_name(swfs, "swf_"),
+            characters: stage.characters.clone(),
+            camera: camera.view(),
+        }
+    }
+
+    /// 恢复画面状态，bgm_fade 为切换 BGM 的淡入淡出秒数
+    pub fn apply<'a>(
+        &self,
+        backgrounds: impl Iterator<Item = (&'a Name, Mut<'a, Visibility>)>,
+        swfs: impl Iterator<Item = (&'a Name, Mut<'a, Visibility>)>,
+        commands: &mut Commands,
+        asset_server: &AssetServer,
+        config: &MainConfig,
+        current_audio: &mut CurrentAudio,
+        stage: &mut Stage,
+        camera: &mut CameraEffects,
+        bgm_fade: f32,
+    ) {
+        show_only(backgrounds, "background_", self.background.as_deref());
+        show_only(swfs, "swf_", self.swf.as_deref());
+        switch_bgm(
+            commands,
+            asset_server,
+            config,
+            current_audio,
+            self.bgm.as_deref(),
+            bgm_fade,
+        );
+        stage.characters = self.characters.clone();
+        stage.transition = None;
+        stage.transforms.clear();
+        stage.reset_motions = true;
+        // 进行中的镜头效果停止，直接回到当时的镜头
+        camera.restore(self.camera);
+    }
+}
+
+fn visible_name<'a>(
+    entities: impl Iterator<Item = (&'a Name, &'a Visibility)>,
+    prefix: &str,
+) -> Option<String> {
+    entities
+        .filter(|(_, visibility)| **visibility == Visibility::Visible)
+        .find_map(|(name, _)| name.as_str().strip_prefix(prefix).map(str::to_string))
+}
+
+fn show_only<'a>(
+    entities: impl Iterator<Item = (&'a Name, Mut<'a, Visibility>)>,
+    prefix: &str,
+    target: Option<&str>,
+) {
+    for (name, mut visibility) in entities {
+        let is_target = name.as_str().strip_prefix(prefix) == target;
+        *visibility = if is_target {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// 进入某一行时的状态
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub line: usize,
+    /// 进入这一行之前的画面
+    pub presentation: Presentation,
+    /// 进入这一行之前的变量（尚未执行这一行的 set）
+    pub variables: HashMap<String, VariableValue>,
+    pub in_branch_selection: bool,
+}
+
+/// 已经过的行，按实际经过的顺序排列（最后一条为当前行）
+#[derive(Resource, Default)]
+pub struct RollbackHistory {
+    pub entries: Vec<HistoryEntry>,
+}
+
+/// 回退到历史中的第 N 条记录
+#[derive(Message)]
+pub struct RollbackToMessage(pub usize);
+
+pub struct RollbackPlugin;
+
+impl Plugin for RollbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RollbackHistory>()
+            .add_message::<RollbackToMessage>()
+            .add_systems(
+                Update,
+                (
+                    handle_rollback_event,
+                    apply_rollback
+                        .after(handle_rollback_event)
+                        .before(super::enter_current_line),
+                )
+                    .run_if(in_state(GameScene::Game)),
+            )
+            .add_systems(OnExit(GameScene::Game), clear_history);
+    }
+}
+
+// 回退一步：回到上一条历史记录
+fn handle_rollback_event(
+    mut rollback_events: MessageReader<RollbackEventMessage>,
+    mut rollback_to: MessageWriter<RollbackToMessage>,
+    history: Res<RollbackHistory>,
+    config: Res<MainConfig>,
+) {
+    for _event in rollback_events.read() {
+        if !config.settings.rewind {
+            println!("回退功能未开启（settings.rewind）");
+            continue;
+        }
+        if history.entries.len() >= 2 {
+            rollback_to.write(RollbackToMessage(history.entries.len() - 2));
+        } else {
+            println!("已经是第一行,无法回退");
+        }
+        // 同一帧内多次回退只处理一次
+        break;
+    }
+}
+
+fn apply_rollback(
+    mut commands: Commands,
+    mut rollback_to: MessageReader<RollbackToMessage>,
+    mut history: ResMut<RollbackHistory>,
+    mut game_state: ResMut<GameState>,
+    mut backlog: ResMut<Backlog>,
+    mut skip_state: ResMut<SkipState>,
+    mut current_audio: ResMut<CurrentAudio>,
+    mut stage: ResMut<Stage>,
+    mut camera: ResMut<CameraEffects>,
+    asset_server: Res<AssetServer>,
+    config: Res<MainConfig>,
+    mut background_query: Query<(&Name, &mut Visibility), (With<Background>, Without<Flash>)>,
+    mut swf_query: Query<(&Name, &mut Visibility), (With<Flash>, Without<Background>)>,
+    choice_buttons: Query<Entity, With<DynamicButton>>,
+) {
+    let Some(index) = rollback_to.read().map(|message| message.0).min() else {
+        return;
+    };
+    let Some(entry) = history.entries.get(index).cloned() else {
+        return;
+    };
+
+    println!("回退到第 {} 行", entry.line);
+    // 这一行会在重新进入时再次记录
+    history.entries.truncate(index);
+    backlog.entries.retain(|backlog_entry| backlog_entry.history_index < index);
+
+    game_state.current_line = entry.line;
+    game_state.variables = entry.variables;
+    game_state.in_branch_selection = entry.in_branch_selection;
+    game_state.entered_line = None;
+    game_state.jump_label = None;
+    game_state.is_blocked = false;
+    game_state.is_auto_playing = false;
+    game_state.can_go_back = index > 0;
+    skip_state.active = false;
+
+    entry.presentation.apply(
+        background_query.iter_mut(),
+        swf_query.iter_mut(),
+        &mut commands,
+        &asset_server,
+        &config,
+        &mut current_audio,
+        &mut stage,
+        &mut camera,
+        // 回退时直接切换，与画面一致
+        0.0,
+    );
+
+    // 清除旧的选项按钮，回到选项行时重新生成
+    for entity in choice_buttons.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn clear_history(mut history: ResMut<RollbackHistory>) {
+    history.entries.clear();
+}
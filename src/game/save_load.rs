@@ -0,0 +1,479 @@
+// src/game/save_load.rs
+// 游戏内的存档/读档槽选择界面
+use bevy::{prelude::*, ui::FocusPolicy};
+use bevy_flash::player::Flash;
+
+use super::{
+    Background, CurrentAudio, GameState,
+    backlog::Backlog,
+    initial_variables,
+    rollback::{Presentation, RollbackHistory},
+    set_click_area_enabled,
+    stage::Stage,
+};
+use crate::{
+    GameScene,
+    audio::fade_out_audio,
+    config::MainConfig,
+    effect::CameraEffects,
+    locale::Locale,
+    script::parse_markup,
+    save::{PendingLoad, SAVE_SLOT_COUNT, SaveData, list_slots, now_timestamp, read_slot, write_slot},
+    toolbar::{OpenLoadMenuEventMessage, OpenSaveMenuEventMessage},
+};
+
+const SLOT_FONT: &str = "fonts/GenSenMaruGothicTW-Bold.ttf";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SlotMenuMode {
+    Save,
+    Load,
+}
+
+/// 当前打开的存档槽界面（None 表示未打开）
+#[derive(Resource, Default)]
+pub struct SlotMenuState {
+    pub mode: Option<SlotMenuMode>,
+}
+
+#[derive(Component)]
+struct SlotMenuRoot;
+
+#[derive(Component)]
+struct SlotButton(usize);
+
+#[derive(Component)]
+struct CloseSlotMenuButton;
+
+pub struct SaveLoadPlugin;
+
+impl Plugin for SaveLoadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SlotMenuState>()
+            .add_systems(
+                Update,
+                (
+                    open_slot_menu,
+                    handle_slot_buttons,
+                    handle_close_slot_menu,
+                    apply_pending_load,
+                )
+                    .run_if(in_state(GameScene::Game)),
+            )
+            .add_systems(OnExit(GameScene::Game), close_slot_menu_on_exit);
+    }
+}
+
+// 从当前画面生成存档快照
+fn snapshot(
+    config: &MainConfig,
+    locale: &Locale,
+    game_state: &GameState,
+    history: &RollbackHistory,
+    current_audio: &CurrentAudio,
+    stage: &Stage,
+    camera: &CameraEffects,
+    background_query: &Query<(&Name, &mut Visibility), (With<Background>, Without<Flash>)>,
+    swf_query: &Query<(&Name, &mut Visibility), (With<Flash>, Without<Background>)>,
+) -> SaveData {
+    let mut presentation = Presentation::capture(
+        background_query.iter(),
+        swf_query.iter(),
+        current_audio,
+        stage,
+        camera,
+    );
+    // 同屏角色、镜头与变量一样保存进入当前行之前的状态，读档后重新执行这一行的 show / hide 与 effect
+    if let Some(entry) = history
+        .entries
+        .last()
+        .filter(|entry| entry.line == game_state.current_line)
+    {
+        presentation.characters = entry.presentation.characters.clone();
+        presentation.camera = entry.presentation.camera;
+    }
+
+    // 保存进入当前行之前的变量，读档后重新进入这一行时再执行它的 set
+    let variables = history
+        .entries
+        .last()
+        .filter(|entry| entry.line == game_state.current_line)
+        .map(|entry| entry.variables.clone())
+        .unwrap_or_else(|| game_state.variables.clone());
+
+    let (character, excerpt) = game_state
+        .dialogues
+        .get(game_state.current_line)
+        .map(|d| {
+            (
+                locale.character_name(config, &d.character, &game_state.variables),
+                SaveData::make_excerpt(
+                    &parse_markup(locale.dialogue_text(d), &game_state.variables).plain_text(),
+                ),
+            )
+        })
+        .unwrap_or_default();
+
+    SaveData {
+        current_line: game_state.current_line,
+        background: presentation.background,
+        bgm: presentation.bgm,
+        swf: presentation.swf,
+        characters: presentation.characters,
+        camera: presentation.camera,
+        variables,
+        character,
+        excerpt,
+        saved_at: now_timestamp(),
+    }
+}
+
+fn open_slot_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    mut save_events: MessageReader<OpenSaveMenuEventMessage>,
+    mut load_events: MessageReader<OpenLoadMenuEventMessage>,
+    mut menu_state: ResMut<SlotMenuState>,
+    existing_menu: Query<Entity, With<SlotMenuRoot>>,
+    mut named_query: Query<(&Name, &mut Visibility, &mut FocusPolicy)>,
+) {
+    let mut mode = None;
+    for _ in save_events.read() {
+        mode = Some(SlotMenuMode::Save);
+    }
+    for _ in load_events.read() {
+        mode = Some(SlotMenuMode::Load);
+    }
+    let Some(mode) = mode else {
+        return;
+    };
+
+    for entity in existing_menu.iter() {
+        commands.entity(entity).despawn();
+    }
+    spawn_slot_menu(&mut commands, &asset_server, &locale, mode);
+    menu_state.mode = Some(mode);
+
+    // 打开时禁用点击区域，避免点击穿透推进对话
+    set_click_area_enabled(&mut named_query, false);
+}
+
+fn handle_slot_buttons(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    interaction_query: Query<(&Interaction, &SlotButton), Changed<Interaction>>,
+    mut menu_state: ResMut<SlotMenuState>,
+    config: Res<MainConfig>,
+    locale: Res<Locale>,
+    game_state: Res<GameState>,
+    history: Res<RollbackHistory>,
+    current_audio: Res<CurrentAudio>,
+    stage: Res<Stage>,
+    camera: Res<CameraEffects>,
+    background_query: Query<(&Name, &mut Visibility), (With<Background>, Without<Flash>)>,
+    swf_query: Query<(&Name, &mut Visibility), (With<Flash>, Without<Background>)>,
+    menu_root: Query<Entity, With<SlotMenuRoot>>,
+    mut named_query: Query<
+        (&Name, &mut Visibility, &mut FocusPolicy),
+        (Without<Background>, Without<Flash>),
+    >,
+) {
+    let Some(mode) = menu_state.mode else {
+        return;
+    };
+
+    for (interaction, slot_button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let slot = slot_button.0;
+
+        match mode {
+            SlotMenuMode::Save => {
+                let data = snapshot(
+                    &config,
+                    &locale,
+                    &game_state,
+                    &history,
+                    &current_audio,
+                    &stage,
+                    &camera,
+                    &background_query,
+                    &swf_query,
+                );
+                match write_slot(slot, &data) {
+                    Ok(()) => println!("已保存到存档槽 {}", slot),
+                    Err(e) => eprintln!("保存存档槽 {} 失败: {}", slot, e),
+                }
+                // 重建界面以显示新的存档信息
+                for entity in menu_root.iter() {
+                    commands.entity(entity).despawn();
+                }
+                spawn_slot_menu(&mut commands, &asset_server, &locale, mode);
+            }
+            SlotMenuMode::Load => match read_slot(slot) {
+                Ok(Some(data)) => {
+                    println!("读取存档槽 {}", slot);
+                    commands.insert_resource(PendingLoad(data));
+                    for entity in menu_root.iter() {
+                        commands.entity(entity).despawn();
+                    }
+                    menu_state.mode = None;
+                    set_click_area_enabled(&mut named_query, true);
+                }
+                Ok(None) => println!("存档槽 {} 为空", slot),
+                Err(e) => eprintln!("读取存档槽 {} 失败: {}", slot, e),
+            },
+        }
+        break;
+    }
+}
+
+fn handle_close_slot_menu(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<CloseSlotMenuButton>)>,
+    mut menu_state: ResMut<SlotMenuState>,
+    menu_root: Query<Entity, With<SlotMenuRoot>>,
+    mut named_query: Query<(&Name, &mut Visibility, &mut FocusPolicy)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            for entity in menu_root.iter() {
+                commands.entity(entity).despawn();
+            }
+            menu_state.mode = None;
+            set_click_area_enabled(&mut named_query, true);
+        }
+    }
+}
+
+fn close_slot_menu_on_exit(
+    mut commands: Commands,
+    mut menu_state: ResMut<SlotMenuState>,
+    menu_root: Query<Entity, With<SlotMenuRoot>>,
+) {
+    for entity in menu_root.iter() {
+        commands.entity(entity).despawn();
+    }
+    menu_state.mode = None;
+}
+
+/// 应用待读取的存档：恢复对话行、变量、背景、BGM 与 SWF
+pub fn apply_pending_load(
+    mut commands: Commands,
+    pending: Option<Res<PendingLoad>>,
+    game_state: Option<ResMut<GameState>>,
+    config: Res<MainConfig>,
+    asset_server: Res<AssetServer>,
+    mut current_audio: ResMut<CurrentAudio>,
+    mut stage: ResMut<Stage>,
+    mut camera: ResMut<CameraEffects>,
+    mut backlog: ResMut<Backlog>,
+    mut history: ResMut<RollbackHistory>,
+    mut background_query: Query<(&Name, &mut Visibility), (With<Background>, Without<Flash>)>,
+    mut swf_query: Query<(&Name, &mut Visibility), (With<Flash>, Without<Background>)>,
+) {
+    let (Some(pending), Some(mut game_state)) = (pending, game_state) else {
+        return;
+    };
+    let data = &pending.0;
+
+    game_state.current_line = data.current_line.min(game_state.dialogues.len());
+    game_state.variables = initial_variables(&config);
+    game_state.variables.extend(data.variables.clone());
+    game_state.jump_label = None;
+    game_state.in_branch_selection = false;
+    game_state.is_blocked = false;
+    game_state.is_auto_playing = false;
+    game_state.auto_play_timer = 0.0;
+    game_state.can_go_back = false;
+    // 重新进入这一行：执行它的 set 并记录回退历史
+    game_state.entered_line = None;
+    backlog.entries.clear();
+    history.entries.clear();
+
+    // 存档中的 BGM 从头重新播放（即使与当前播放的相同）
+    let bgm_fade = config.settings.bgm_fade;
+    if let Some(entity) = current_audio.current_entity.take() {
+        fade_out_audio(&mut commands, entity, bgm_fade);
+    }
+    current_audio.current_bgm = None;
+
+    Presentation {
+        background: data.background.clone(),
+        bgm: data.bgm.clone(),
+        swf: data.swf.clone(),
+        characters: data.characters.clone(),
+        camera: data.camera,
+    }
+    .apply(
+        background_query.iter_mut(),
+        swf_query.iter_mut(),
+        &mut commands,
+        &asset_server,
+        &config,
+        &mut current_audio,
+        &mut stage,
+        &mut camera,
+        bgm_fade,
+    );
+
+    println!("存档已读取，跳转到第 {} 行", game_state.current_line);
+    commands.remove_resource::<PendingLoad>();
+}
+
+fn spawn_slot_menu(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    locale: &Locale,
+    mode: SlotMenuMode,
+) {
+    let font = asset_server.load(SLOT_FONT);
+    let title = match mode {
+        SlotMenuMode::Save => "slots.save_title",
+        SlotMenuMode::Load => "slots.load_title",
+    };
+    let slot_label = locale.ui("slots.slot");
+    let slots = list_slots();
+
+    commands
+        .spawn((
+            Name::new("slot_menu"),
+            SlotMenuRoot,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            GlobalZIndex(30000),
+            Interaction::default(),
+            FocusPolicy::Block, // 阻止点击穿透
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(700.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(20.0),
+                        padding: UiRect::all(Val::Px(30.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.3)),
+                    BorderColor::all(Color::srgb(0.6, 0.6, 0.8)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        locale.ui_text(title),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 28.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.9, 1.0)),
+                    ));
+
+                    // 存档槽列表
+                    parent
+                        .spawn(Node {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Stretch,
+                            row_gap: Val::Px(10.0),
+                            width: Val::Percent(100.0),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for (slot, data) in slots.iter().take(SAVE_SLOT_COUNT) {
+                                // 读档模式下空槽不可点击
+                                let clickable = mode == SlotMenuMode::Save || data.is_some();
+                                let mut row = parent.spawn((
+                                    Node {
+                                        flex_direction: FlexDirection::Column,
+                                        justify_content: JustifyContent::Center,
+                                        width: Val::Percent(100.0),
+                                        height: Val::Px(60.0),
+                                        padding: UiRect::horizontal(Val::Px(15.0)),
+                                        border: UiRect::all(Val::Px(1.0)),
+                                        row_gap: Val::Px(4.0),
+                                        ..default()
+                                    },
+                                    BackgroundColor(if data.is_some() {
+                                        Color::srgb(0.3, 0.3, 0.4)
+                                    } else {
+                                        Color::srgb(0.25, 0.25, 0.3)
+                                    }),
+                                    BorderColor::all(Color::srgb(0.5, 0.5, 0.6)),
+                                ));
+                                if clickable {
+                                    row.insert((Button, SlotButton(*slot)));
+                                }
+                                row.with_children(|parent| {
+                                    let (heading, detail) = match data {
+                                        Some(data) => (
+                                            format!("{} {}    {}", slot_label, slot, data.formatted_time()),
+                                            data.summary(),
+                                        ),
+                                        None => (
+                                            format!("{} {}", slot_label, slot),
+                                            locale.ui("slots.empty").to_string(),
+                                        ),
+                                    };
+                                    parent.spawn((
+                                        Text::new(heading),
+                                        TextFont {
+                                            font: font.clone(),
+                                            font_size: 16.0,
+                                            ..default()
+                                        },
+                                        TextColor(Color::WHITE),
+                                    ));
+                                    parent.spawn((
+                                        Text::new(detail),
+                                        TextFont {
+                                            font: font.clone(),
+                                            font_size: 14.0,
+                                            ..default()
+                                        },
+                                        TextColor(Color::srgb(0.8, 0.8, 0.9)),
+                                    ));
+                                });
+                            }
+                        });
+
+                    // 返回按钮
+                    parent
+                        .spawn((
+                            Button,
+                            CloseSlotMenuButton,
+                            Node {
+                                width: Val::Px(120.0),
+                                height: Val::Px(45.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                border: UiRect::all(Val::Px(2.0)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.3, 0.3, 0.5)),
+                            BorderColor::all(Color::srgb(0.5, 0.5, 0.7)),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                locale.ui_text("common.back"),
+                                TextFont {
+                                    font: font.clone(),
+                                    font_size: 16.0,
+                                    ..default()
+                                },
+                                TextColor(Color::WHITE),
+                            ));
+                        });
+                });
+        });
+}
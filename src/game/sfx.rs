@@ -0,0 +1,168 @@
+// src/game/sfx.rs
+// 音效：显示某一行时播放它的 sfx，回退或快进时停止仍在播放的音效
+use bevy::prelude::*;
+
+use super::{GameState, rollback::RollbackHistory, rollback::RollbackToMessage, skip::SkipState};
+use crate::{
+    GameScene,
+    audio::{AudioChannel, MixerSound},
+    config::MainConfig,
+    script::dialogue::SfxCue,
+};
+
+/// 音效通道上播放的声音
+#[derive(Component)]
+pub struct SfxSound;
+
+// 等待延迟播放的音效
+struct PendingSfx {
+    path: String,
+    volume: f32,
+    remaining: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct SfxState {
+    // 已经触发过音效的（行号, 回退历史长度）
+    played: Option<(usize, usize)>,
+    pending: Vec<PendingSfx>,
+}
+
+pub struct SfxPlugin;
+
+impl Plugin for SfxPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SfxState>()
+            .add_systems(
+                Update,
+                (
+                    stop_sfx_on_rollback_or_skip,
+                    play_line_sfx,
+                    play_pending_sfx,
+                )
+                    .chain()
+                    .after(super::enter_current_line)
+                    .run_if(in_state(GameScene::Game)),
+            )
+            .add_systems(OnExit(GameScene::Game), stop_all_sfx);
+    }
+}
+
+// 进入新的一行时播放（或排队）它的音效
+fn play_line_sfx(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<MainConfig>,
+    game_state: Res<GameState>,
+    history: Res<RollbackHistory>,
+    skip_state: Res<SkipState>,
+    mut sfx_state: ResMut<SfxState>,
+) {
+    let current_line = game_state.current_line;
+    let key = (current_line, history.entries.len());
+    if game_state.entered_line != Some(current_line) || sfx_state.played == Some(key) {
+        return;
+    }
+    sfx_state.played = Some(key);
+
+    // 快进时不播放
+    if skip_state.is_skipping() {
+        return;
+    }
+    let Some(sfx) = game_state
+        .dialogues
+        .get(current_line)
+        .and_then(|dialogue| dialogue.sfx.as_ref())
+    else {
+        return;
+    };
+
+    for cue in sfx.cues() {
+        let Some(path) = sfx_path(&config, cue) else {
+            continue;
+        };
+        if cue.delay() > 0.0 {
+            sfx_state.pending.push(PendingSfx {
+                path,
+                volume: cue.volume(),
+                remaining: cue.delay(),
+            });
+        } else {
+            spawn_sfx(&mut commands, &asset_server, path, cue.volume());
+        }
+    }
+}
+
+// 延迟到时的音效
+fn play_pending_sfx(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut sfx_state: ResMut<SfxState>,
+) {
+    if sfx_state.pending.is_empty() {
+        return;
+    }
+    let delta = time.delta_secs();
+    let mut ready = Vec::new();
+    sfx_state.pending.retain_mut(|pending| {
+        pending.remaining -= delta;
+        if pending.remaining <= 0.0 {
+            ready.push((pending.path.clone(), pending.volume));
+            false
+        } else {
+            true
+        }
+    });
+    for (path, volume) in ready {
+        spawn_sfx(&mut commands, &asset_server, path, volume);
+    }
+}
+
+fn stop_sfx_on_rollback_or_skip(
+    mut commands: Commands,
+    mut rollback_to: MessageReader<RollbackToMessage>,
+    skip_state: Res<SkipState>,
+    mut sfx_state: ResMut<SfxState>,
+    sounds: Query<Entity, With<SfxSound>>,
+) {
+    let rolled_back = rollback_to.read().count() > 0;
+    if !rolled_back && !skip_state.is_skipping() {
+        return;
+    }
+    sfx_state.pending.clear();
+    for entity in sounds.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn stop_all_sfx(
+    mut commands: Commands,
+    mut sfx_state: ResMut<SfxState>,
+    sounds: Query<Entity, With<SfxSound>>,
+) {
+    *sfx_state = SfxState::default();
+    for entity in sounds.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn sfx_path(config: &MainConfig, cue: &SfxCue) -> Option<String> {
+    match config.assets.audio.sfx.get(cue.sound()) {
+        Some(path) => Some(path.trim_start_matches("assets/").to_string()),
+        None => {
+            eprintln!("错误: 在配置文件中找不到音效 '{}'", cue.sound());
+            None
+        }
+    }
+}
+
+fn spawn_sfx(commands: &mut Commands, asset_server: &AssetServer, path: String, volume: f32) {
+    println!("播放音效: {}", path);
+    commands.spawn((
+        SfxSound,
+        AudioPlayer::new(asset_server.load::<AudioSource>(path)),
+        PlaybackSettings::DESPAWN,
+        MixerSound::new(AudioChannel::Sfx).with_volume(volume),
+    ));
+}
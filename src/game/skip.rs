@@ -0,0 +1,343 @@
+// src/game/skip.rs
+// 快进模式：快速跳过已读文本，遇到未读文本和选项时停止；按住 Ctrl 临时快进
+use bevy::prelude::*;
+
+use super::{GameState, SettingsMenu, backlog::BacklogState, save_load::SlotMenuState};
+use crate::{
+    GameScene,
+    config::{MainConfig, prefs::UserPrefs},
+    locale::Locale,
+    save::{PersistentData, read_persistent, write_persistent},
+    script::line_key,
+    toolbar::ToggleSkipEventMessage,
+    transition::TransitionSettings,
+};
+
+// 快进时每行停留的时间（秒）
+const SKIP_INTERVAL: f32 = 0.05;
+
+// 已读记录写盘的间隔（秒）
+const SEEN_FLUSH_INTERVAL: f32 = 1.0;
+
+const SELECTED_OPTION_COLOR: Color = Color::srgba(1.0, 0.6, 0.2, 0.3);
+const HOVERED_OPTION_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.1);
+
+/// 已读文本记录（跨存档、跨启动保存）
+#[derive(Resource)]
+pub struct SeenLines {
+    data: PersistentData,
+    dirty: bool,
+    flush_timer: f32,
+}
+
+impl SeenLines {
+    fn flush(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        match write_persistent(&self.data) {
+            Ok(()) => self.dirty = false,
+            Err(e) => eprintln!("保存已读记录失败: {}", e),
+        }
+    }
+}
+
+/// 快进状态
+#[derive(Resource, Default)]
+pub struct SkipState {
+    /// 通过快进按钮开启的快进模式
+    pub active: bool,
+    /// 按住 Ctrl 的临时快进
+    pub held: bool,
+    // 当前行在进入前是否已读
+    current_read: bool,
+    tracked_line: Option<usize>,
+    timer: f32,
+}
+
+impl SkipState {
+    pub fn is_skipping(&self) -> bool {
+        self.active || self.held
+    }
+}
+
+/// 快进选项（是否快进未读文本见 settings.skip_unread，会保存到玩家偏好）
+#[derive(Resource)]
+pub struct SkipSettings {
+    /// 做出选择后继续快进
+    pub after_choices: bool,
+    /// 忽略转场：背景与角色直接切换
+    pub transitions: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SkipOption {
+    Unread,
+    AfterChoices,
+    Transitions,
+}
+
+/// 设置菜单中的快进选项按钮
+#[derive(Component)]
+pub struct SkipOptionButton(pub SkipOption);
+
+#[derive(Component)]
+struct SkipIndicator;
+
+pub struct SkipPlugin;
+
+impl Plugin for SkipPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SkipState>()
+            .add_systems(
+                Startup,
+                init_skip_resources.after(super::load_main_config_system),
+            )
+            .add_systems(OnEnter(GameScene::Game), spawn_skip_indicator)
+            .add_systems(OnExit(GameScene::Game), (stop_skip_on_exit, cleanup_skip_indicator))
+            .add_systems(
+                Update,
+                (
+                    handle_toggle_skip,
+                    track_seen_lines.after(super::enter_current_line),
+                    skip_advance
+                        .after(track_seen_lines)
+                        .after(handle_toggle_skip),
+                    flush_seen_lines,
+                    sync_instant_transitions.after(skip_advance),
+                    update_skip_indicator,
+                    handle_skip_option_buttons,
+                )
+                    .run_if(in_state(GameScene::Game)),
+            );
+    }
+}
+
+fn init_skip_resources(mut commands: Commands) {
+    let data = read_persistent();
+    println!("已读文本记录: {} 行", data.seen_lines.len());
+    commands.insert_resource(SeenLines {
+        data,
+        dirty: false,
+        flush_timer: 0.0,
+    });
+    commands.insert_resource(SkipSettings {
+        after_choices: false,
+        transitions: false,
+    });
+}
+
+fn handle_toggle_skip(
+    mut toggle_events: MessageReader<ToggleSkipEventMessage>,
+    mut skip_state: ResMut<SkipState>,
+) {
+    for _event in toggle_events.read() {
+        skip_state.active = !skip_state.active;
+        skip_state.timer = 0.0;
+        println!("快进已{}", if skip_state.active { "开启" } else { "关闭" });
+    }
+}
+
+// 进入新行时记录已读，并记下这一行此前是否读过
+fn track_seen_lines(
+    game_state: Res<GameState>,
+    mut seen_lines: ResMut<SeenLines>,
+    mut skip_state: ResMut<SkipState>,
+) {
+    if game_state.entered_line == skip_state.tracked_line {
+        return;
+    }
+    skip_state.tracked_line = game_state.entered_line;
+
+    let Some(dialogue) = game_state.dialogues.get(game_state.current_line) else {
+        return;
+    };
+    let key = line_key(&dialogue.character, &dialogue.text);
+    let newly_seen = seen_lines.data.seen_lines.insert(key);
+    skip_state.current_read = !newly_seen;
+    if newly_seen {
+        seen_lines.dirty = true;
+    }
+}
+
+fn skip_advance(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut game_state: ResMut<GameState>,
+    mut skip_state: ResMut<SkipState>,
+    skip_settings: Res<SkipSettings>,
+    config: Res<MainConfig>,
+    slot_menu: Res<SlotMenuState>,
+    backlog_state: Res<BacklogState>,
+    settings_menu: Query<&Visibility, With<SettingsMenu>>,
+) {
+    skip_state.held = keys.pressed(KeyCode::ControlLeft) || keys.pressed(KeyCode::ControlRight);
+    if !skip_state.is_skipping() {
+        return;
+    }
+
+    // 有其他界面打开时暂停快进
+    let settings_open = settings_menu
+        .iter()
+        .any(|visibility| *visibility == Visibility::Visible);
+    if slot_menu.mode.is_some() || backlog_state.open || settings_open {
+        return;
+    }
+
+    // 等待进入新行后再判断（跳转尚未完成时也等待）
+    if game_state.entered_line != Some(game_state.current_line)
+        || game_state.jump_label.is_some()
+    {
+        return;
+    }
+
+    let Some(dialogue) = game_state.dialogues.get(game_state.current_line) else {
+        // 脚本结束
+        skip_state.active = false;
+        return;
+    };
+
+    if !skip_state.current_read && !config.settings.skip_unread {
+        if skip_state.active {
+            println!("遇到未读文本，停止快进");
+        }
+        skip_state.active = false;
+        return;
+    }
+
+    if dialogue.pause == Some(true) {
+        skip_state.active = false;
+        return;
+    }
+
+    // 遇到选项时停下等待玩家选择
+    if !game_state.visible_choices(game_state.current_line).is_empty() {
+        if !skip_settings.after_choices {
+            skip_state.active = false;
+        }
+        return;
+    }
+
+    skip_state.timer += time.delta_secs();
+    if skip_state.timer < SKIP_INTERVAL {
+        return;
+    }
+    skip_state.timer = 0.0;
+    game_state.advance();
+}
+
+// 快进或忽略转场时，转场立即完成
+fn sync_instant_transitions(
+    skip_state: Res<SkipState>,
+    skip_settings: Res<SkipSettings>,
+    mut transition_settings: ResMut<TransitionSettings>,
+) {
+    let instant = skip_state.is_skipping() || skip_settings.transitions;
+    if transition_settings.instant != instant {
+        transition_settings.instant = instant;
+    }
+}
+
+fn flush_seen_lines(time: Res<Time>, mut seen_lines: ResMut<SeenLines>) {
+    seen_lines.flush_timer += time.delta_secs();
+    if seen_lines.flush_timer >= SEEN_FLUSH_INTERVAL {
+        seen_lines.flush_timer = 0.0;
+        seen_lines.flush();
+    }
+}
+
+fn stop_skip_on_exit(
+    mut skip_state: ResMut<SkipState>,
+    mut seen_lines: ResMut<SeenLines>,
+    mut transition_settings: ResMut<TransitionSettings>,
+) {
+    *skip_state = SkipState::default();
+    transition_settings.instant = false;
+    seen_lines.flush();
+}
+
+fn spawn_skip_indicator(mut commands: Commands, asset_server: Res<AssetServer>, locale: Res<Locale>) {
+    commands.spawn((
+        Name::new("skip_indicator"),
+        SkipIndicator,
+        locale.ui_text("skip.indicator"),
+        TextFont {
+            font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.6, 0.2)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(15.0),
+            left: Val::Px(20.0),
+            ..default()
+        },
+        GlobalZIndex(500),
+        Visibility::Hidden,
+    ));
+}
+
+fn update_skip_indicator(
+    skip_state: Res<SkipState>,
+    mut indicator: Query<&mut Visibility, With<SkipIndicator>>,
+) {
+    if !skip_state.is_changed() {
+        return;
+    }
+    for mut visibility in indicator.iter_mut() {
+        *visibility = if skip_state.is_skipping() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn cleanup_skip_indicator(mut commands: Commands, indicator: Query<Entity, With<SkipIndicator>>) {
+    for entity in indicator.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+// 设置菜单中的快进选项：点击切换，已开启的选项高亮
+fn handle_skip_option_buttons(
+    mut skip_settings: ResMut<SkipSettings>,
+    mut config: ResMut<MainConfig>,
+    mut prefs: ResMut<UserPrefs>,
+    pressed_query: Query<(&Interaction, &SkipOptionButton), Changed<Interaction>>,
+    mut button_query: Query<(&Interaction, &SkipOptionButton, &mut BackgroundColor)>,
+) {
+    for (interaction, option_button) in &pressed_query {
+        if *interaction == Interaction::Pressed {
+            match option_button.0 {
+                SkipOption::Unread => {
+                    let skip_unread = !config.settings.skip_unread;
+                    config.settings.skip_unread = skip_unread;
+                    prefs.skip_unread = Some(skip_unread);
+                }
+                SkipOption::AfterChoices => {
+                    skip_settings.after_choices = !skip_settings.after_choices
+                }
+                SkipOption::Transitions => {
+                    skip_settings.transitions = !skip_settings.transitions
+                }
+            }
+        }
+    }
+
+    for (interaction, option_button, mut color) in button_query.iter_mut() {
+        let enabled = match option_button.0 {
+            SkipOption::Unread => config.settings.skip_unread,
+            SkipOption::AfterChoices => skip_settings.after_choices,
+            SkipOption::Transitions => skip_settings.transitions,
+        };
+        *color = BackgroundColor(if enabled {
+            SELECTED_OPTION_COLOR
+        } else if *interaction == Interaction::Hovered {
+            HOVERED_OPTION_COLOR
+        } else {
+            Color::srgba(0.0, 0.0, 0.0, 0.0)
+        });
+    }
+}
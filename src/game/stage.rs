@@ -0,0 +1,387 @@
+// src/game/stage.rs
+// 多角色同屏：按 show / hide 维护画面上的角色，每个角色一个精灵，直到 hide 为止
+use std::collections::HashSet;
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use super::PortraitAssets;
+use crate::{
+    GameScene,
+    config::MainConfig,
+    motion::{Motion, MotionPlayer, play_motion},
+    script::dialogue::{ShowCharacter, StagePosition},
+    transition::{SpriteTransition, Transition, start_transition},
+};
+
+/// 角色精灵所在的 z 层：背景（-10）之上、说话人立绘（0）之下
+const STAGE_BASE_Z: f32 = -5.0;
+
+/// 角色精灵的 y 坐标，与 spritebox 一致
+const STAGE_Y: f32 = 10.0;
+
+/// 当前在画面上的角色（位置与 z 均已确定）
+#[derive(Resource, Default, Debug, Clone)]
+pub struct Stage {
+    pub characters: Vec<ShowCharacter>,
+    /// 这一次登场 / 退场的转场，None 为直接显示
+    pub transition: Option<Transition>,
+    /// 这一次要播放变换动画的角色与动画的写法（不保存在 characters 中，回退、读档时不重播）
+    pub transforms: Vec<(String, String)>,
+    /// 回退、读档后停止角色精灵上的变换动画，回到原来的样子
+    pub reset_motions: bool,
+}
+
+impl Stage {
+    /// 执行一行的 hide 和 show（先 hide，再 show）
+    pub fn apply_directions(&mut self, show: Option<&[ShowCharacter]>, hide: Option<&[String]>) {
+        self.transforms.clear();
+        for name in hide.into_iter().flatten() {
+            if name == "all" {
+                self.characters.clear();
+            } else {
+                self.characters.retain(|shown| shown.character != *name);
+            }
+        }
+
+        for show in show.into_iter().flatten() {
+            if let Some(transform) = &show.transform {
+                self.transforms
+                    .push((show.character.clone(), transform.clone()));
+            }
+            let next_z = self
+                .characters
+                .iter()
+                .filter_map(|shown| shown.z)
+                .max()
+                .map_or(0, |z| z + 1);
+            match self
+                .characters
+                .iter_mut()
+                .find(|shown| shown.character == show.character)
+            {
+                // 已在画面上：只更新写了的字段
+                Some(shown) => {
+                    if show.at.is_some() {
+                        shown.at = show.at.clone();
+                    }
+                    if show.z.is_some() {
+                        shown.z = show.z;
+                    }
+                    if show.expression.is_some() {
+                        shown.expression = show.expression.clone();
+                    }
+                }
+                None => self.characters.push(ShowCharacter {
+                    character: show.character.clone(),
+                    expression: show.expression.clone(),
+                    at: Some(show.at.clone().unwrap_or_default()),
+                    z: Some(show.z.unwrap_or(next_z)),
+                    transform: None,
+                }),
+            }
+        }
+    }
+}
+
+/// 角色精灵，内容为角色键名
+#[derive(Component)]
+struct StageSprite(String);
+
+pub struct StagePlugin;
+
+impl Plugin for StagePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Stage>()
+            .add_systems(
+                Update,
+                (
+                    sync_stage_sprites.after(super::enter_current_line),
+                    relayout_stage_sprites.after(sync_stage_sprites),
+                )
+                    .run_if(in_state(GameScene::Game)),
+            )
+            .add_systems(OnExit(GameScene::Game), clear_stage);
+    }
+}
+
+// 让角色精灵与 Stage 保持一致：新增的生成，退场的移除，其余更新位置与层级
+fn sync_stage_sprites(
+    mut commands: Commands,
+    mut stage: ResMut<Stage>,
+    config: Res<MainConfig>,
+    portraits: Option<Res<PortraitAssets>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut sprites: Query<(
+        Entity,
+        &StageSprite,
+        &mut Sprite,
+        &mut Transform,
+        Option<&mut MotionPlayer>,
+    )>,
+    mut warned: Local<HashSet<(String, String)>>,
+) {
+    if !stage.is_changed() {
+        return;
+    }
+    let Some(portraits) = portraits else {
+        return;
+    };
+    let width = window.single().map_or(1400.0, |window| window.width());
+    let reset_motions = stage.reset_motions;
+    if reset_motions {
+        stage.bypass_change_detection().reset_motions = false;
+        for (entity, _, mut sprite, mut transform, player) in sprites.iter_mut() {
+            if let Some(player) = player {
+                player.reset(&mut sprite, &mut transform);
+                commands.entity(entity).remove::<MotionPlayer>();
+            }
+        }
+    }
+
+    for (entity, sprite, ..) in sprites.iter() {
+        if stage.characters.iter().any(|shown| shown.character == sprite.0) {
+            continue;
+        }
+        match &stage.transition {
+            // 退场的精灵不再属于 Stage，按转场消失后移除
+            Some(transition) => {
+                commands.entity(entity).remove::<StageSprite>();
+                start_transition(&mut commands, transition.clone(), Some(entity), None);
+            }
+            None => commands.entity(entity).despawn(),
+        }
+    }
+
+    for shown in &stage.characters {
+        let expression = shown.expression.as_deref().or_else(|| {
+            config
+                .get_character(&shown.character)
+                .and_then(|entry| entry.expression.as_deref())
+        });
+        let Some(handle) = portraits.get(&shown.character, expression) else {
+            eprintln!("找不到角色立绘: {}", shown.character);
+            continue;
+        };
+        let warn_key = (shown.character.clone(), expression.unwrap_or_default().to_string());
+        if !portraits.has_expression(&shown.character, expression) && warned.insert(warn_key) {
+            eprintln!(
+                "找不到角色 {} 的表情 {}，使用默认立绘",
+                shown.character,
+                expression.unwrap_or_default()
+            );
+        }
+        let at = shown.at.clone().unwrap_or_default();
+        if at.x_fraction().is_none() {
+            eprintln!(
+                "未知的角色位置: {}（可用: {}），按 center 处理",
+                at,
+                StagePosition::NAMES.join(" / ")
+            );
+        }
+        let translation = stage_translation(shown, width);
+
+        let motion = stage
+            .transforms
+            .iter()
+            .rev()
+            .find(|(character, _)| *character == shown.character)
+            .and_then(|(_, source)| Motion::parse_or_warn(source, &config.transforms));
+
+        let entity = match sprites
+            .iter_mut()
+            .find(|(_, sprite, ..)| sprite.0 == shown.character)
+        {
+            Some((entity, _, mut sprite, mut transform, player)) => {
+                sprite.image = handle.clone();
+                // 播放过变换动画的精灵相对 base 计算位置
+                match player {
+                    Some(mut player) if !reset_motions => {
+                        player.base.translation = translation
+                    }
+                    _ => transform.translation = translation,
+                }
+                entity
+            }
+            None => {
+                let entity = commands
+                    .spawn((
+                        Name::new(format!("stage_{}", shown.character)),
+                        StageSprite(shown.character.clone()),
+                        Sprite {
+                            image: handle.clone(),
+                            ..default()
+                        },
+                        Transform::from_translation(translation),
+                        Visibility::Visible,
+                    ))
+                    .id();
+                if let Some(transition) = &stage.transition {
+                    start_transition(&mut commands, transition.clone(), None, Some(entity));
+                }
+                entity
+            }
+        };
+        if let Some(motion) = motion {
+            play_motion(&mut commands, entity, motion);
+        }
+    }
+}
+
+// 角色精灵的位置：x 按画面宽度的比例计算，未知的位置按 center 处理
+fn stage_translation(shown: &ShowCharacter, width: f32) -> Vec3 {
+    let fraction = shown
+        .at
+        .clone()
+        .unwrap_or_default()
+        .x_fraction()
+        .or(StagePosition::default().x_fraction())
+        .unwrap_or(0.5);
+    Vec3::new(
+        (fraction - 0.5) * width,
+        STAGE_Y,
+        STAGE_BASE_Z + shown.z.unwrap_or(0) as f32 * 0.01,
+    )
+}
+
+// 窗口大小改变（切换分辨率、全屏）后按新的宽度重新摆放角色
+fn relayout_stage_sprites(
+    mut resize_events: MessageReader<WindowResized>,
+    primary_window: Query<(), With<PrimaryWindow>>,
+    stage: Res<Stage>,
+    mut sprites: Query<(
+        &StageSprite,
+        &mut Transform,
+        Option<&mut MotionPlayer>,
+        Option<&mut SpriteTransition>,
+    )>,
+) {
+    let Some(width) = resize_events
+        .read()
+        .filter(|event| primary_window.contains(event.window))
+        .last()
+        .map(|event| event.width)
+    else {
+        return;
+    };
+    for (sprite, mut transform, player, transition) in sprites.iter_mut() {
+        let Some(shown) = stage
+            .characters
+            .iter()
+            .find(|shown| shown.character == sprite.0)
+        else {
+            continue;
+        };
+        let translation = stage_translation(shown, width);
+        // 转场、变换动画中的精灵修改它们所基于的位置，由它们写入 Transform
+        let mut direct = true;
+        if let Some(mut transition) = transition {
+            transition.origin.translation = translation;
+            direct = false;
+        }
+        if let Some(mut player) = player {
+            player.base.translation = translation;
+            direct = false;
+        }
+        if direct {
+            transform.translation = translation;
+        }
+    }
+}
+
+fn clear_stage(mut stage: ResMut<Stage>) {
+    stage.characters.clear();
+    stage.transition = None;
+    stage.transforms.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn show(character: &str) -> ShowCharacter {
+        ShowCharacter {
+            character: character.to_string(),
+            expression: None,
+            at: None,
+            z: None,
+            transform: None,
+        }
+    }
+
+    fn names(stage: &Stage) -> Vec<(&str, Option<i32>)> {
+        stage
+            .characters
+            .iter()
+            .map(|shown| (shown.character.as_str(), shown.z))
+            .collect()
+    }
+
+    #[test]
+    fn new_characters_are_centered_and_stacked() {
+        let mut stage = Stage::default();
+        stage.apply_directions(Some(&[show("ayla"), show("captain")]), None);
+        assert_eq!(names(&stage), [("ayla", Some(0)), ("captain", Some(1))]);
+        assert_eq!(stage.characters[0].at, Some(StagePosition::default()));
+    }
+
+    #[test]
+    fn showing_again_only_updates_written_fields() {
+        let mut stage = Stage::default();
+        let mut ayla = show("ayla");
+        ayla.at = Some(StagePosition::Named("left".to_string()));
+        ayla.expression = Some("smile".to_string());
+        stage.apply_directions(Some(&[ayla, show("captain")]), None);
+
+        let mut update = show("ayla");
+        update.z = Some(5);
+        stage.apply_directions(Some(&[update]), None);
+        let ayla = &stage.characters[0];
+        assert_eq!(ayla.at, Some(StagePosition::Named("left".to_string())));
+        assert_eq!(ayla.expression.as_deref(), Some("smile"));
+        assert_eq!(ayla.z, Some(5));
+        assert_eq!(stage.characters.len(), 2);
+    }
+
+    #[test]
+    fn hide_runs_before_show() {
+        let mut stage = Stage::default();
+        stage.apply_directions(Some(&[show("ayla"), show("captain")]), None);
+        // 同一行先 hide 再 show：重新登场的角色排在最前
+        stage.apply_directions(Some(&[show("ayla")]), Some(&["ayla".to_string()]));
+        assert_eq!(names(&stage), [("captain", Some(1)), ("ayla", Some(2))]);
+
+        stage.apply_directions(None, Some(&["all".to_string()]));
+        assert!(stage.characters.is_empty());
+    }
+
+    #[test]
+    fn transforms_only_last_for_one_line() {
+        let mut stage = Stage::default();
+        let mut ayla = show("ayla");
+        ayla.transform = Some("hop".to_string());
+        stage.apply_directions(Some(&[ayla]), None);
+        assert_eq!(stage.transforms, [("ayla".to_string(), "hop".to_string())]);
+        // 变换动画不保存在角色上，回退、读档时不重播
+        assert_eq!(stage.characters[0].transform, None);
+
+        stage.apply_directions(None, None);
+        assert!(stage.transforms.is_empty());
+    }
+
+    #[test]
+    fn translation_follows_window_width() {
+        let mut ayla = show("ayla");
+        ayla.at = Some(StagePosition::Named("right".to_string()));
+        ayla.z = Some(2);
+        let translation = stage_translation(&ayla, 1600.0);
+        assert_eq!(translation.x, 400.0);
+        assert_eq!(translation.z, STAGE_BASE_Z + 0.02);
+        assert_eq!(stage_translation(&ayla, 800.0).x, 200.0);
+
+        ayla.at = Some(StagePosition::Named("nowhere".to_string()));
+        assert_eq!(stage_translation(&ayla, 1600.0).x, 0.0);
+    }
+}
@@ -0,0 +1,193 @@
+// src/game/voice.rs
+// 语音：每行的 voice 在独立的语音通道播放，进入下一行时停止；播放期间降低 BGM 音量
+use bevy::prelude::*;
+
+use super::{CurrentAudio, GameState, rollback::RollbackHistory, skip::SkipState};
+use crate::{
+    GameScene,
+    audio::{AudioChannel, MixerSound},
+    config::MainConfig,
+    script::{voice_path, voice_tag},
+};
+
+/// 自动播放时，语音结束后等待多久进入下一行（秒）
+pub const VOICE_AUTO_ADVANCE_DELAY: f32 = 0.5;
+
+/// 语音通道上播放的声音
+#[derive(Component)]
+pub struct VoiceSound;
+
+/// 重新播放一段语音（历史记录中的语音按钮）
+#[derive(Message, Debug, Clone)]
+pub struct PlayVoiceMessage {
+    /// 相对 assets 目录的路径
+    pub path: String,
+    /// 语音标签，用于查找角色的语音音量
+    pub tag: String,
+}
+
+#[derive(Resource, Default)]
+pub struct VoiceState {
+    // 已经处理过语音的（行号, 回退历史长度）
+    played: Option<(usize, usize)>,
+    /// 当前行是否有语音
+    pub line_has_voice: bool,
+    /// 语音是否仍在播放
+    pub playing: bool,
+}
+
+pub struct VoicePlugin;
+
+impl Plugin for VoicePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VoiceState>()
+            .add_message::<PlayVoiceMessage>()
+            .add_systems(
+                Update,
+                (play_line_voice, replay_voice, track_voice, duck_bgm)
+                    .chain()
+                    .after(super::enter_current_line)
+                    .run_if(in_state(GameScene::Game)),
+            )
+            .add_systems(OnExit(GameScene::Game), stop_voice_on_exit);
+    }
+}
+
+// 进入新的一行时停止上一段语音，并播放这一行的语音
+fn play_line_voice(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<MainConfig>,
+    game_state: Res<GameState>,
+    history: Res<RollbackHistory>,
+    skip_state: Res<SkipState>,
+    mut voice_state: ResMut<VoiceState>,
+    voices: Query<Entity, With<VoiceSound>>,
+) {
+    let current_line = game_state.current_line;
+    let key = (current_line, history.entries.len());
+    if game_state.entered_line != Some(current_line) || voice_state.played == Some(key) {
+        return;
+    }
+    voice_state.played = Some(key);
+    stop_voices(&mut commands, &voices);
+
+    let dialogue = game_state.dialogues.get(current_line);
+    let voice = dialogue.and_then(|dialogue| {
+        dialogue
+            .voice
+            .as_deref()
+            .map(|voice| (dialogue.character.as_str(), voice))
+    });
+    voice_state.line_has_voice = voice.is_some();
+
+    // 快进时不播放
+    let Some((character, voice)) = voice.filter(|_| !skip_state.is_skipping()) else {
+        return;
+    };
+    let path = voice_path(&config, character, voice);
+    spawn_voice(&mut commands, &asset_server, &config, path, voice_tag(&config, character));
+    voice_state.playing = true;
+}
+
+fn replay_voice(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<MainConfig>,
+    mut play_voice: MessageReader<PlayVoiceMessage>,
+    mut voice_state: ResMut<VoiceState>,
+    voices: Query<Entity, With<VoiceSound>>,
+) {
+    let Some(message) = play_voice.read().last() else {
+        return;
+    };
+    stop_voices(&mut commands, &voices);
+    spawn_voice(&mut commands, &asset_server, &config, message.path.clone(), &message.tag);
+    voice_state.playing = true;
+}
+
+// 语音播放完毕后实体会被移除；加载失败的语音直接移除，避免自动播放一直等待
+fn track_voice(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    skip_state: Res<SkipState>,
+    mut voice_state: ResMut<VoiceState>,
+    voices: Query<(Entity, &AudioPlayer), With<VoiceSound>>,
+) {
+    for (entity, player) in voices.iter() {
+        if skip_state.is_skipping() || asset_server.load_state(&player.0).is_failed() {
+            commands.entity(entity).despawn();
+        }
+    }
+    if skip_state.is_skipping() {
+        if voice_state.playing {
+            voice_state.playing = false;
+        }
+        return;
+    }
+    // 刚生成的语音实体下一帧才能查询到
+    if voices.is_empty() && voice_state.playing && !voice_state.is_changed() {
+        voice_state.playing = false;
+    }
+}
+
+// 播放语音时降低 BGM 音量（与混音器的背景音乐音量相乘）
+fn duck_bgm(
+    config: Res<MainConfig>,
+    current_audio: Res<CurrentAudio>,
+    voice_state: Res<VoiceState>,
+    mut sounds: Query<&mut MixerSound>,
+) {
+    let Some(mut sound) = current_audio
+        .current_entity
+        .and_then(|entity| sounds.get_mut(entity).ok())
+    else {
+        return;
+    };
+    let target = if voice_state.playing {
+        config.settings.voice_ducking.clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    if (sound.volume - target).abs() > f32::EPSILON {
+        sound.volume = target;
+    }
+}
+
+fn stop_voice_on_exit(
+    mut commands: Commands,
+    mut voice_state: ResMut<VoiceState>,
+    voices: Query<Entity, With<VoiceSound>>,
+) {
+    stop_voices(&mut commands, &voices);
+    *voice_state = VoiceState::default();
+}
+
+fn stop_voices(commands: &mut Commands, voices: &Query<Entity, With<VoiceSound>>) {
+    for entity in voices.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn spawn_voice(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    config: &MainConfig,
+    path: String,
+    tag: &str,
+) {
+    let volume = config
+        .settings
+        .voice_volumes
+        .get(tag)
+        .copied()
+        .unwrap_or(1.0)
+        .clamp(0.0, 1.0);
+    println!("播放语音: {}（{}，音量 {}）", path, tag, volume);
+    commands.spawn((
+        VoiceSound,
+        AudioPlayer::new(asset_server.load::<AudioSource>(path)),
+        PlaybackSettings::DESPAWN,
+        MixerSound::new(AudioChannel::Voice).with_volume(volume),
+    ));
+}
@@ -3,10 +3,19 @@ pub mod dissolve;
 pub mod menu;
 pub use menu::MenuPlugin;
 pub mod audio;
+pub mod check;
 pub mod config;
+pub mod display;
+pub mod effect;
+pub mod easing;
+pub mod locale;
+pub mod motion;
 pub mod position;
+pub mod save;
+pub mod script;
 pub mod style;
 pub mod toolbar;
+pub mod transition;
 pub mod typewriter;
 pub mod url;
 
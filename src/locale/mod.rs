@@ -0,0 +1,438 @@
+// src/locale/mod.rs
+// 本地化：界面文字表与对话翻译，运行时切换语言，缺少的翻译回退到原文
+//
+// 翻译位于 assets/locales/<语言>/：
+// - ui.yaml：界面文字，键为文字 id（见 SOURCE_UI_STRINGS），`character.<角色 id>` 为角色的显示名称
+// - 其余 yaml：对话翻译，文件名与脚本文件相同（同一命名空间），
+//   键为标签或行标识（见 `script::line_key`），值为译文，或 `{text, choices}`
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    config::{MainConfig, VariableValue, prefs::UserPrefs},
+    script::{Dialogue, character_name, interpolate, line_key, loader::qualify_label},
+};
+
+/// 翻译所在目录（相对 assets 目录）
+pub const LOCALES_DIR: &str = "locales";
+
+/// 界面文字表的文件名
+pub const UI_STRINGS_FILE: &str = "ui.yaml";
+
+/// 角色显示名称在界面文字表中的前缀
+const CHARACTER_PREFIX: &str = "character.";
+
+/// 引擎界面文字的原文，可以在 locales/<原文语言>/ui.yaml 中覆盖
+const SOURCE_UI_STRINGS: &[(&str, &str)] = &[
+    ("menu.start", "开始游戏"),
+    ("menu.load", "载入游戏"),
+    ("menu.about", "关于"),
+    ("menu.help", "帮助"),
+    ("menu.exit", "退出"),
+    ("common.back", "返回"),
+    ("common.on", "开启"),
+    ("common.off", "关闭"),
+    ("speed.slow", "慢"),
+    ("speed.normal", "中"),
+    ("speed.fast", "快"),
+    ("settings.title", "设置"),
+    ("settings.game_title", "游戏设置"),
+    ("settings.text_speed", "文字显示速度"),
+    ("settings.auto_play", "自动播放速度"),
+    ("settings.fullscreen", "全屏模式"),
+    ("settings.skip_unread", "跳过未读文本"),
+    ("settings.display", "显示"),
+    ("settings.window", "窗口"),
+    ("settings.full", "全屏"),
+    ("settings.skip", "快进"),
+    ("settings.skip_unread_option", "未读文本"),
+    ("settings.skip_after_choices", "选项后继续"),
+    ("settings.skip_transitions", "忽略转场"),
+    ("settings.volume", "音量"),
+    ("settings.language", "语言"),
+    ("volume.bgm", "背景音乐音量"),
+    ("volume.sfx", "音效音量"),
+    ("volume.voice", "语音音量"),
+    ("volume.ui", "界面音效音量"),
+    ("game_menu.history", "历史"),
+    ("game_menu.save", "保存"),
+    ("game_menu.load", "读取游戏"),
+    ("game_menu.settings", "设置"),
+    ("game_menu.title", "标题界面"),
+    ("game_menu.about", "关于"),
+    ("toolbar.rollback", "回退"),
+    ("toolbar.history", "历史"),
+    ("toolbar.skip", "快进"),
+    ("toolbar.auto", "自动"),
+    ("toolbar.save", "存档"),
+    ("toolbar.load", "读档"),
+    ("toolbar.settings", "设置"),
+    ("backlog.title", "历史"),
+    ("backlog.empty", "暂无记录"),
+    ("backlog.voice", "▶ 语音"),
+    ("skip.indicator", "快进中 ▶▶"),
+    ("mixer.mute", "静音"),
+    ("mixer.unmute", "取消静音"),
+    ("help.title", "游戏操作"),
+    ("help.rollback", "回退上一句:"),
+    ("help.next", "进入下一句:"),
+    ("help.exit", "退出主界面:"),
+    ("help.auto", "自动播放:"),
+    ("help.tip", "提示：游戏支持鼠标点击操作，你也可以使用键盘快捷键来提升游戏体验。按ESC键可以随时返回主菜单。"),
+    ("slots.save_title", "保存游戏"),
+    ("slots.load_title", "读取游戏"),
+    ("slots.slot", "存档槽"),
+    ("slots.empty", "空"),
+    ("game.end", "感谢体验，按下ESC退出"),
+];
+
+#[derive(Debug, Error)]
+pub enum LocaleError {
+    #[error("无法读取翻译文件 {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("翻译文件 {path:?} 格式错误: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+}
+
+/// 一行对话的翻译：只写字符串时为对话文字
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LineTranslation {
+    Text(String),
+    Line {
+        #[serde(default)]
+        text: Option<String>,
+        // 按脚本中的顺序对应 choices，缺少的选项显示原文
+        #[serde(default)]
+        choices: Vec<String>,
+    },
+}
+
+impl LineTranslation {
+    fn text(&self) -> Option<&str> {
+        match self {
+            LineTranslation::Text(text) => Some(text),
+            LineTranslation::Line { text, .. } => text.as_deref(),
+        }
+    }
+
+    fn choice(&self, index: usize) -> Option<&str> {
+        match self {
+            LineTranslation::Text(_) => None,
+            LineTranslation::Line { choices, .. } => choices.get(index).map(String::as_str),
+        }
+    }
+}
+
+/// 当前语言的界面文字与对话翻译
+#[derive(Resource)]
+pub struct Locale {
+    // 当前语言，None 表示脚本原文的语言
+    language: Option<String>,
+    ui: HashMap<String, String>,
+    source_ui: HashMap<String, String>,
+    lines: HashMap<String, LineTranslation>,
+}
+
+impl FromWorld for Locale {
+    fn from_world(world: &mut World) -> Self {
+        world
+            .get_resource::<MainConfig>()
+            .map(Locale::load)
+            .unwrap_or_else(|| Locale::load(&MainConfig::default()))
+    }
+}
+
+impl Locale {
+    /// 按 settings.language 加载 assets 目录下的翻译
+    pub fn load(config: &MainConfig) -> Self {
+        let exe_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+        Self::load_from(config, &exe_dir.join("assets"))
+    }
+
+    pub fn load_from(config: &MainConfig, assets_dir: &Path) -> Self {
+        let locales_dir = assets_dir.join(LOCALES_DIR);
+        let mut source_ui: HashMap<String, String> = SOURCE_UI_STRINGS
+            .iter()
+            .map(|(id, text)| (id.to_string(), text.to_string()))
+            .collect();
+        if let Some(source) = config.source_language() {
+            source_ui.extend(read_ui_strings(&locales_dir.join(&source.id)));
+        }
+
+        let language = active_language(config);
+        let (ui, lines) = match &language {
+            Some(language) => {
+                let dir = locales_dir.join(language);
+                if !dir.is_dir() {
+                    eprintln!("找不到语言 '{}' 的翻译目录 {:?}，使用原文", language, dir);
+                }
+                (read_ui_strings(&dir), read_line_translations(&dir))
+            }
+            None => (HashMap::new(), HashMap::new()),
+        };
+        println!(
+            "当前语言: {}（界面文字 {} 条，对话翻译 {} 条）",
+            language.as_deref().unwrap_or("原文"),
+            ui.len(),
+            lines.len()
+        );
+
+        Self {
+            language,
+            ui,
+            source_ui,
+            lines,
+        }
+    }
+
+    /// 当前语言，None 表示脚本原文的语言
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// 界面文字：当前语言 -> 原文 -> id 本身
+    pub fn ui<'a>(&'a self, id: &'a str) -> &'a str {
+        self.ui
+            .get(id)
+            .or_else(|| self.source_ui.get(id))
+            .map_or(id, String::as_str)
+    }
+
+    /// 界面文字组件，切换语言时自动刷新
+    pub fn ui_text(&self, id: &'static str) -> (Text, LocalizedText) {
+        (Text::new(self.ui(id)), LocalizedText(id))
+    }
+
+    /// 对话文字（未替换变量和标签）
+    pub fn dialogue_text<'a>(&'a self, dialogue: &'a Dialogue) -> &'a str {
+        self.line(dialogue)
+            .and_then(LineTranslation::text)
+            .unwrap_or(&dialogue.text)
+    }
+
+    /// 第 index 个选项的文字（index 为在 choices 中的原始下标）
+    pub fn choice_text<'a>(&'a self, dialogue: &'a Dialogue, index: usize) -> &'a str {
+        let source = dialogue
+            .choices
+            .as_ref()
+            .and_then(|choices| choices.get(index))
+            .map_or("", |choice| choice.text.as_str());
+        self.line(dialogue)
+            .and_then(|line| line.choice(index))
+            .unwrap_or(source)
+    }
+
+    /// 角色的显示名称：界面文字表中的 `character.<角色 id>`，未翻译时使用角色表
+    pub fn character_name(
+        &self,
+        config: &MainConfig,
+        character: &str,
+        variables: &HashMap<String, VariableValue>,
+    ) -> String {
+        match self.ui.get(&format!("{}{}", CHARACTER_PREFIX, character)) {
+            Some(name) => interpolate(name, variables),
+            None => character_name(config, character, variables),
+        }
+    }
+
+    // 先按标签查找，再按行标识查找
+    fn line(&self, dialogue: &Dialogue) -> Option<&LineTranslation> {
+        if self.lines.is_empty() {
+            return None;
+        }
+        dialogue
+            .label
+            .as_ref()
+            .and_then(|label| self.lines.get(label))
+            .or_else(|| {
+                self.lines
+                    .get(&line_key(&dialogue.character, &dialogue.text))
+            })
+    }
+}
+
+/// 使用界面文字表的文字，切换语言时刷新
+#[derive(Component)]
+pub struct LocalizedText(pub &'static str);
+
+/// 语言选项按钮
+#[derive(Component)]
+pub struct LanguageButton(pub String);
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Locale>().add_systems(
+            Update,
+            (
+                handle_language_buttons,
+                reload_locale,
+                refresh_localized_text,
+                update_language_buttons,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// settings.language 对应的翻译语言；未设置或为原文语言时为 None
+pub fn active_language(config: &MainConfig) -> Option<String> {
+    let language = config.settings.language.as_ref()?;
+    let is_source = config
+        .source_language()
+        .is_some_and(|source| &source.id == language);
+    (!is_source).then(|| language.clone())
+}
+
+// 点击语言按钮：修改 settings 并记录到偏好
+fn handle_language_buttons(
+    mut config: ResMut<MainConfig>,
+    prefs: Option<ResMut<UserPrefs>>,
+    buttons: Query<(&Interaction, &LanguageButton), Changed<Interaction>>,
+) {
+    let Some((_, button)) = buttons
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+    else {
+        return;
+    };
+    if config.settings.language.as_ref() == Some(&button.0) {
+        return;
+    }
+    println!("切换语言: {}", button.0);
+    config.settings.language = Some(button.0.clone());
+    if let Some(mut prefs) = prefs {
+        prefs.language = Some(button.0.clone());
+    }
+}
+
+// settings.language 变化后重新加载翻译
+fn reload_locale(config: Res<MainConfig>, mut locale: ResMut<Locale>) {
+    if !config.is_changed() {
+        return;
+    }
+    if active_language(&config).as_deref() != locale.language() {
+        *locale = Locale::load(&config);
+    }
+}
+
+fn refresh_localized_text(locale: Res<Locale>, mut texts: Query<(&LocalizedText, &mut Text)>) {
+    if !locale.is_changed() {
+        return;
+    }
+    for (localized, mut text) in texts.iter_mut() {
+        let new_text = locale.ui(localized.0);
+        if text.0 != new_text {
+            text.0 = new_text.to_string();
+        }
+    }
+}
+
+// 当前语言的按钮文字高亮显示
+fn update_language_buttons(
+    config: Res<MainConfig>,
+    buttons: Query<(&LanguageButton, &Children)>,
+    mut colors: Query<&mut TextColor>,
+) {
+    for (button, children) in buttons.iter() {
+        let selected = config.current_language() == Some(button.0.as_str());
+        let color = language_color(selected);
+        for child in children.iter() {
+            if let Ok(mut text_color) = colors.get_mut(child) {
+                if text_color.0 != color {
+                    text_color.0 = color;
+                }
+            }
+        }
+    }
+}
+
+/// 语言按钮的文字颜色
+pub fn language_color(selected: bool) -> Color {
+    if selected {
+        Color::srgb(1.0, 0.6, 0.2)
+    } else {
+        Color::WHITE
+    }
+}
+
+fn read_yaml<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T, LocaleError> {
+    let yaml = fs::read_to_string(path).map_err(|source| LocaleError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    serde_yaml::from_str(&yaml).map_err(|source| LocaleError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn read_ui_strings(dir: &Path) -> HashMap<String, String> {
+    let path = dir.join(UI_STRINGS_FILE);
+    if !path.exists() {
+        return HashMap::new();
+    }
+    read_yaml(&path).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        HashMap::new()
+    })
+}
+
+// 目录中除 ui.yaml 以外的全部 yaml，标签按文件名加上命名空间
+fn read_line_translations(dir: &Path) -> HashMap<String, LineTranslation> {
+    let mut lines = HashMap::new();
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return lines;
+    };
+    let mut paths: Vec<PathBuf> = read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "yaml" || ext == "yml")
+                && path.file_name().is_some_and(|name| name != UI_STRINGS_FILE)
+        })
+        .collect();
+    paths.sort();
+    for path in paths {
+        let namespace = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let entries: HashMap<String, LineTranslation> = match read_yaml(&path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        for (key, line) in entries {
+            let key = if is_line_key(&key) {
+                key
+            } else {
+                qualify_label(&namespace, &key)
+            };
+            lines.insert(key, line);
+        }
+    }
+    lines
+}
+
+// 行标识为 16 位十六进制数
+fn is_line_key(key: &str) -> bool {
+    key.len() == 16 && key.chars().all(|c| c.is_ascii_hexdigit())
+}
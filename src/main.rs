@@ -1,7 +1,15 @@
 mod audio;
 mod config;
+mod display;
+mod dissolve;
+mod easing;
+mod effect;
 mod game;
+mod locale;
 mod menu;
+mod motion;
+mod save;
+mod script;
 mod style;
 mod toolbar;
 mod transition; // 添加模块
@@ -11,6 +19,10 @@ use crate::game::GamePlugin;
 use crate::{audio::AudioPlugin, url::UrlPlugin};
 use bevy::prelude::*;
 use config::{MainConfig, load_main_config};
+use display::{DisplayPlugin, window_mode, window_size};
+use effect::EffectPlugin;
+use motion::MotionPlugin;
+use locale::LocalePlugin;
 use menu::MenuPlugin;
 // use crate::audio::{play_audio, play_audio_with_volume, play_audio_loop};
 use crate::transition::{TransitionPlugin, fade_in, fade_out}; //
@@ -37,10 +49,7 @@ fn menu_exit_system(mut commands: Commands) {
 
 fn main() {
     let main_config = load_main_config();
-    let (width, height) = (
-        main_config.settings.resolution[0] as f32,
-        main_config.settings.resolution[1] as f32,
-    );
+    let [width, height] = window_size(&main_config);
 
     App::new()
         .insert_resource(ClearColor(Color::BLACK))
@@ -53,16 +62,21 @@ fn main() {
                     maximize: main_config.is_maximizable(), 
                     ..Default::default()
                 },
-                resolution: (width as u32, height as u32).into(),
+                resolution: (width, height).into(),
+                mode: window_mode(main_config.settings.fullscreen),
                 ..default()
             }),
             ..default()
         }))
         .insert_resource(main_config)
+        .add_plugins(LocalePlugin) // 本地化（需要在 MainConfig 之后）
+        .add_plugins(DisplayPlugin) // 窗口 / 全屏与窗口大小
         .add_plugins(menu::MenuPlugin) // 主菜单界面
         .init_state::<GameScene>()
         .add_plugins(StylePlugin)
         .add_plugins(TransitionPlugin)
+        .add_plugins(EffectPlugin) // 镜头效果
+        .add_plugins(MotionPlugin) // 变换动画
         .add_plugins(GamePlugin)
         .add_plugins(AudioPlugin)
         .add_plugins(UrlPlugin) // 新增的url插件
@@ -0,0 +1,218 @@
+// src/menu/mixer.rs
+// 设置界面的音量滑块：按住拖动调节通道音量，右侧按钮切换静音，修改立即作用于正在播放的声音
+use bevy::{
+    prelude::*,
+    ui::{FocusPolicy, RelativeCursorPosition},
+};
+
+use crate::{
+    GameScene,
+    audio::{AudioChannel, AudioMixer},
+    locale::{Locale, LocalizedText},
+};
+
+/// 滑块的宽度（像素）
+const SLIDER_WIDTH: f32 = 200.0;
+
+/// 滑块的高度（像素）
+const SLIDER_HEIGHT: f32 = 25.0;
+
+/// 滑块轨道，按住并拖动时设置音量
+#[derive(Component)]
+struct VolumeSlider(AudioChannel);
+
+/// 滑块中表示当前音量的填充部分
+#[derive(Component)]
+struct VolumeFill {
+    channel: AudioChannel,
+    accent: Color,
+}
+
+/// 音量百分比文字
+#[derive(Component)]
+struct VolumeValueText(AudioChannel);
+
+/// 静音按钮
+#[derive(Component)]
+struct MuteButton(AudioChannel);
+
+/// 静音按钮上的文字
+#[derive(Component)]
+struct MuteText(AudioChannel);
+
+pub struct MixerSettingsPlugin;
+
+impl Plugin for MixerSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (drag_volume_sliders, toggle_mute_buttons, update_volume_controls)
+                .chain()
+                .run_if(in_state(GameScene::Settings).or(in_state(GameScene::GameSettings))),
+        );
+    }
+}
+
+/// 生成一个通道的音量控件：滑块、百分比与静音按钮（标题文字由调用方生成）
+pub fn spawn_volume_control(
+    parent: &mut ChildSpawnerCommands,
+    font: Handle<Font>,
+    locale: &Locale,
+    mixer: &AudioMixer,
+    channel: AudioChannel,
+    accent: Color,
+) {
+    let mix = mixer.channel(channel);
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(12.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Button,
+                    VolumeSlider(channel),
+                    RelativeCursorPosition::default(),
+                    Node {
+                        width: Val::Px(SLIDER_WIDTH),
+                        height: Val::Px(SLIDER_HEIGHT),
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.3)),
+                    BorderColor::all(accent),
+                ))
+                .with_children(|parent| {
+                    // 填充部分不拦截点击，让滑块收到按下事件
+                    parent.spawn((
+                        VolumeFill { channel, accent },
+                        FocusPolicy::Pass,
+                        Node {
+                            width: Val::Percent(mix.volume * 100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(fill_color(accent, mix.muted)),
+                    ));
+                });
+
+            parent.spawn((
+                VolumeValueText(channel),
+                Text::new(volume_label(mix.volume)),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    width: Val::Px(45.0),
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn((
+                    Button,
+                    MuteButton(channel),
+                    Node {
+                        padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                        border: UiRect::all(Val::Px(1.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
+                    BorderColor::all(accent),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        MuteText(channel),
+                        locale.ui_text(mute_label(mix.muted)),
+                        TextFont {
+                            font,
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+        });
+}
+
+// 按住滑块时按光标的横向位置设置音量
+fn drag_volume_sliders(
+    mut mixer: ResMut<AudioMixer>,
+    sliders: Query<(&Interaction, &RelativeCursorPosition, &VolumeSlider)>,
+) {
+    for (interaction, cursor, slider) in sliders.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // normalized 以节点中心为原点，取值 -0.5 ~ 0.5
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+        let volume = ((position.x + 0.5) * 100.0).round() / 100.0;
+        if (mixer.channel(slider.0).volume - volume.clamp(0.0, 1.0)).abs() > f32::EPSILON {
+            mixer.set_volume(slider.0, volume);
+        }
+    }
+}
+
+fn toggle_mute_buttons(
+    mut mixer: ResMut<AudioMixer>,
+    buttons: Query<(&Interaction, &MuteButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            mixer.toggle_mute(button.0);
+            println!("{} 静音: {}", button.0.label(), mixer.channel(button.0).muted);
+        }
+    }
+}
+
+// 混音器变化时刷新滑块、百分比与静音按钮
+fn update_volume_controls(
+    mixer: Res<AudioMixer>,
+    locale: Res<Locale>,
+    mut fills: Query<(&VolumeFill, &mut Node, &mut BackgroundColor)>,
+    mut values: Query<(&VolumeValueText, &mut Text), Without<MuteText>>,
+    mut mute_texts: Query<(&MuteText, &mut LocalizedText, &mut Text), Without<VolumeValueText>>,
+) {
+    if !mixer.is_changed() {
+        return;
+    }
+    for (fill, mut node, mut color) in fills.iter_mut() {
+        let mix = mixer.channel(fill.channel);
+        node.width = Val::Percent(mix.volume * 100.0);
+        color.0 = fill_color(fill.accent, mix.muted);
+    }
+    for (value, mut text) in values.iter_mut() {
+        text.0 = volume_label(mixer.channel(value.0).volume);
+    }
+    for (mute, mut localized, mut text) in mute_texts.iter_mut() {
+        localized.0 = mute_label(mixer.channel(mute.0).muted);
+        text.0 = locale.ui(localized.0).to_string();
+    }
+}
+
+fn fill_color(accent: Color, muted: bool) -> Color {
+    if muted {
+        Color::srgb(0.4, 0.4, 0.4)
+    } else {
+        accent
+    }
+}
+
+fn volume_label(volume: f32) -> String {
+    format!("{}%", (volume * 100.0).round() as i32)
+}
+
+// 按钮上显示的是点击后的操作（界面文字 id）
+fn mute_label(muted: bool) -> &'static str {
+    if muted { "mixer.unmute" } else { "mixer.mute" }
+}
@@ -1,12 +1,22 @@
 use bevy::{input_focus::InputFocus, prelude::*,window::{WindowResized,Window,PrimaryWindow},text::TextColor};
 
 use crate::GameScene;
-use crate::audio::{AudioManager,stop_all_audio,stop_all_audio_system};
+use crate::audio::{AudioChannel,AudioManager,AudioMixer,stop_all_audio,stop_all_audio_system};
 use crate::style::{UiStyleSheet, load_styles}; 
 use crate::config::MainConfig;
+use crate::display::{DisplayButton, DisplayPlugin, display_color, resolution_label, resolution_presets};
+use crate::locale::{LanguageButton, Locale, LocalePlugin, language_color};
 use crate::url::{UrlButton,open_url};
 use crate::style::ElementId;
 
+mod mixer;
+mod prefs;
+use mixer::{MixerSettingsPlugin, spawn_volume_control};
+use prefs::{
+    AUTO_PLAY_PRESETS, PrefsOption, PrefsPlugin, TEXT_SPEED_PRESETS, spawn_prefs_option,
+    toggle_label,
+};
+
 #[derive(Component)]
 pub struct BackButton;
 
@@ -27,12 +37,21 @@ struct MainMenuBackground;
 pub struct MenuPlugin;
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<LocalePlugin>() {
+            app.add_plugins(LocalePlugin);
+        }
+        if !app.is_plugin_added::<DisplayPlugin>() {
+            app.add_plugins(DisplayPlugin);
+        }
         app
             .init_resource::<InputFocus>()
             .init_resource::<UiStyleSheet>()
+            .add_plugins(MixerSettingsPlugin)
+            .add_plugins(PrefsPlugin)
             // .init_state::<GameScene>()
             .add_systems(Startup, setup)
             .add_systems(Update, button_system.run_if(in_state(GameScene::LoadButton)))
+            .add_systems(Update, load_slot_button_system.run_if(in_state(GameScene::LoadButton)))
             .add_systems(Update, button_system.run_if(in_state(GameScene::Settings)))
             .add_systems(Update, button_system.run_if(in_state(GameScene::Menu)))
 
@@ -103,6 +122,10 @@ pub struct LoadUI;
 #[derive(Component)]
 pub struct BackToMenuButton;
 
+// 载入界面中的存档槽按钮（槽位从 1 开始）
+#[derive(Component)]
+pub struct LoadSlotButton(pub usize);
+
 
 
 // 按钮颜色常量
@@ -195,6 +218,28 @@ fn button_system(
     }
 }
 
+// 点击存档槽：记录待读取的存档并进入游戏
+fn load_slot_button_system(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameScene>>,
+    interaction_query: Query<(&Interaction, &LoadSlotButton), Changed<Interaction>>,
+) {
+    for (interaction, slot_button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match crate::save::read_slot(slot_button.0) {
+            Ok(Some(data)) => {
+                println!("从存档槽 {} 载入游戏", slot_button.0);
+                commands.insert_resource(crate::save::PendingLoad(data));
+                next_state.set(GameScene::Game);
+            }
+            Ok(None) => println!("存档槽 {} 为空", slot_button.0),
+            Err(e) => eprintln!("读取存档槽 {} 失败: {}", slot_button.0, e),
+        }
+    }
+}
+
 fn setup(mut commands: Commands) {
     // UI 摄像机
     commands.spawn((Camera2d, MenuCamera)); // 添加标记组件
@@ -206,6 +251,7 @@ fn setup_menu_scene(
     assets: Res<AssetServer>,
     style_sheet: Res<UiStyleSheet>,
     config: Res<MainConfig>,
+    locale: Res<Locale>,
     scene_query: Query<Entity, With<SceneEntity>>,
 ) {
     // 检查是否已经创建了场景实体
@@ -324,10 +370,11 @@ fn setup_menu_scene(
                         GlobalZIndex(110), // 比菜单容器更高
                     ),
                     // 菜单按钮
-                    create_button(&assets, "开始游戏", StartGameButton),
-                    create_button(&assets, "关于", AboutButton),
-                    create_button(&assets, "帮助", HelpButton),
-                    create_button(&assets, "退出", ExitGameButton),
+                    create_button(&assets, &locale, "menu.start", StartGameButton),
+                    create_button(&assets, &locale, "menu.load", LoadGameButton),
+                    create_button(&assets, &locale, "menu.about", AboutButton),
+                    create_button(&assets, &locale, "menu.help", HelpButton),
+                    create_button(&assets, &locale, "menu.exit", ExitGameButton),
                 ],
             ),
             // 右侧透明区域（用于保持布局但不遮挡背景）
@@ -342,7 +389,7 @@ fn setup_menu_scene(
         ],
     ));
 }
-fn create_button(asset_server: &AssetServer, text: &str, button_type: impl Component) -> impl Bundle {
+fn create_button(asset_server: &AssetServer, locale: &Locale, text: &'static str, button_type: impl Component) -> impl Bundle {
     
     (
         button_type,
@@ -366,7 +413,7 @@ fn create_button(asset_server: &AssetServer, text: &str, button_type: impl Compo
         BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)), // 完全透明
         GlobalZIndex(55),
         children![(
-            Text::new(text),
+            locale.ui_text(text),
             TextFont {
                 font: asset_server.load("fonts/SarasaFixedHC-Light.ttf"),
                 font_size: 26.0,
@@ -502,6 +549,7 @@ fn cleanup_settings_overlay(
 fn setup_about_scene(
     mut commands: Commands, 
     asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
     camera_query: Query<Entity, With<MenuCamera>>,
     mut overlay_query: Query<&mut Visibility, With<GameMenuOverlay>>,
     mut main_menu_query: Query<&mut Visibility, (With<MainMenuBackground>, Without<GameMenuOverlay>)>,
@@ -533,7 +581,7 @@ fn setup_about_scene(
         AboutUI, // 用于清理
     )).with_children(|title_parent| {
         title_parent.spawn((
-            Text::new("关于"),
+            locale.ui_text("menu.about"),
             TextFont {
                 font: asset_server.load("fonts/SarasaFixedHC-Light.ttf"),
                 font_size: 45.0,
@@ -605,7 +653,7 @@ fn setup_about_scene(
                     ..default()
                 }).with_children(|row_parent| {
                     row_parent.spawn((
-                        Text::new(format!("{}:", label)),
+                        locale.ui_text(label),
                         TextFont {
                             font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                             font_size: 18.0,
@@ -664,7 +712,7 @@ fn setup_about_scene(
         )).with_children(|button_parent| {
             button_parent.spawn((
                 
-                Text::new("返回"),
+                locale.ui_text("common.back"),
                 TextFont {
                     font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                     font_size: 30.0,
@@ -708,6 +756,7 @@ about_parent.spawn((
 fn setup_help_scene(
     mut commands: Commands, 
     asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
     camera_query: Query<Entity, With<MenuCamera>>,
     mut overlay_query: Query<&mut Visibility, With<GameMenuOverlay>>,
     mut main_menu_query: Query<&mut Visibility, (With<MainMenuBackground>, Without<GameMenuOverlay>)>,
@@ -735,7 +784,7 @@ fn setup_help_scene(
         AboutUI, // 用于清理
     )).with_children(|title_parent| {
         title_parent.spawn((
-            Text::new("帮助"),
+            locale.ui_text("menu.help"),
             TextFont {
                 font: asset_server.load("fonts/SarasaFixedHC-Light.ttf"),
                 font_size: 45.0,
@@ -766,7 +815,7 @@ fn setup_help_scene(
     )).with_children(|help_parent| {
         // 游戏标题
         help_parent.spawn((
-            Text::new("游戏操作"),
+            locale.ui_text("help.title"),
             TextFont {
                 font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                 font_size: 28.0,
@@ -789,10 +838,10 @@ fn setup_help_scene(
             ..default()
         }).with_children(|info_parent| {
             let help_items = [
-                ("回退上一句", "←"),
-                ("进入下一句", "Enter"),
-                ("退出主界面", "ESC"),
-                ("自动播放", "Space"),
+                ("help.rollback", "←"),
+                ("help.next", "Enter"),
+                ("help.exit", "ESC"),
+                ("help.auto", "Space"),
             ];
 
             for (label, key) in help_items {
@@ -828,7 +877,7 @@ fn setup_help_scene(
 
             // 提示信息
             info_parent.spawn((
-                Text::new("提示：游戏支持鼠标点击操作，你也可以使用键盘快捷键来提升游戏体验。按ESC键可以随时返回主菜单。"),
+                locale.ui_text("help.tip"),
                 TextFont {
                     font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                     font_size: 16.0,
@@ -859,7 +908,7 @@ fn setup_help_scene(
             BackButton,
         )).with_children(|button_parent| {
             button_parent.spawn((
-                Text::new("返回"),
+                locale.ui_text("common.back"),
                 TextFont {
                     font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                     font_size: 30.0,
@@ -872,7 +921,7 @@ fn setup_help_scene(
 }
 
 
-fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>, camera_query: Query<Entity, With<MenuCamera>>) {
+fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>, config: Res<MainConfig>, locale: Res<Locale>, mixer: Res<AudioMixer>, camera_query: Query<Entity, With<MenuCamera>>) {
     println!("执行设置界面");
     
     if camera_query.is_empty() {
@@ -898,7 +947,7 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                 .spawn((
                     Node {
                         width: Val::Px(650.0),
-                        height: Val::Px(580.0),
+                        height: Val::Px(680.0),
                         flex_direction: FlexDirection::Column,
                         justify_content: JustifyContent::SpaceBetween,
                         align_items: AlignItems::Center,
@@ -912,7 +961,7 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                 .with_children(|parent| {
                     // 标题
                     parent.spawn((
-                        Text::new("游戏设置"),
+                        locale.ui_text("settings.game_title"),
                         TextFont {
                             font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                             font_size: 28.0,
@@ -926,99 +975,50 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                         .spawn(Node {
                             flex_direction: FlexDirection::Column,
                             align_items: AlignItems::Stretch,
-                            row_gap: Val::Px(20.0),
+                            row_gap: Val::Px(10.0),
                             width: Val::Percent(100.0),
                             ..default()
                         })
                         .with_children(|parent| {
-                            // 音效音量
-                            parent
-                                .spawn(Node {
-                                    flex_direction: FlexDirection::Row,
-                                    align_items: AlignItems::Center,
-                                    justify_content: JustifyContent::SpaceBetween,
-                                    width: Val::Percent(100.0),
-                                    padding: UiRect::all(Val::Px(10.0)),
-                                    border: UiRect::all(Val::Px(1.0)),
-                                    ..default()
-                                })
-                                .with_children(|parent| {
-                                    parent.spawn((
-                                        Text::new("音效音量"),
-                                        TextFont {
-                                            font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                            font_size: 18.0,
-                                            ..default()
-                                        },
-                                        TextColor(Color::WHITE),
-                                    ));
-                                    
-                              // 音量滑块容器
-                                    parent
-                                        .spawn(Node {
-                                            width: Val::Px(200.0),
-                                            height: Val::Px(25.0),
-                                            justify_content: JustifyContent::Center,
-                                            align_items: AlignItems::Center,
-                                            border: UiRect::all(Val::Px(1.0)),
-                                            ..default()
-                                        })
-                                        .with_children(|parent| {
-                                            parent.spawn((
-                                                Text::new("████████░░"),
-                                                TextFont {
-                                                    font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                    font_size: 14.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::srgb(0.7, 0.7, 0.9)),
-                                            ));
-                                        });
-                                });
+                            // 各通道音量（音效、背景音乐、语音、界面音效）
+                            for channel in [
+                                AudioChannel::Sfx,
+                                AudioChannel::Bgm,
+                                AudioChannel::Voice,
+                                AudioChannel::Ui,
+                            ] {
+                                parent
+                                    .spawn(Node {
+                                        flex_direction: FlexDirection::Row,
+                                        align_items: AlignItems::Center,
+                                        justify_content: JustifyContent::SpaceBetween,
+                                        width: Val::Percent(100.0),
+                                        padding: UiRect::all(Val::Px(10.0)),
+                                        border: UiRect::all(Val::Px(1.0)),
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        parent.spawn((
+                                            locale.ui_text(channel.label_id()),
+                                            TextFont {
+                                                font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
+                                                font_size: 18.0,
+                                                ..default()
+                                            },
+                                            TextColor(Color::WHITE),
+                                        ));
 
-                            // 背景音乐音量
-                            parent
-                                .spawn(Node {
-                                    flex_direction: FlexDirection::Row,
-                                    align_items: AlignItems::Center,
-                                    justify_content: JustifyContent::SpaceBetween,
-                                    width: Val::Percent(100.0),
-                                    padding: UiRect::all(Val::Px(10.0)),
-                                    border: UiRect::all(Val::Px(1.0)),
-                                    ..default()
-                                })
-                                .with_children(|parent| {
-                                    parent.spawn((
-                                        Text::new("背景音乐音量"),
-                                        TextFont {
-                                            font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                            font_size: 18.0,
-                                            ..default()
-                                        },
-                                        TextColor(Color::WHITE),
-                                    ));
-                                    
-                                    parent
-                                        .spawn(Node {
-                                            width: Val::Px(200.0),
-                                            height: Val::Px(25.0),
-                                            justify_content: JustifyContent::Center,
-                                            align_items: AlignItems::Center,
-                                            border: UiRect::all(Val::Px(1.0)),
-                                            ..default()
-                                        })
-                                        .with_children(|parent| {
-                                            parent.spawn((
-                                                Text::new("██████░░░░"),
-                                                TextFont {
-                                                    font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                    font_size: 14.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::srgb(0.7, 0.7, 0.9)),
-                                            ));
-                                        });
-                                });
+                                        // 音量滑块
+                                        spawn_volume_control(
+                                            parent,
+                                            asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
+                                            &locale,
+                                            &mixer,
+                                            channel,
+                                            Color::srgb(0.7, 0.7, 0.9),
+                                        );
+                                    });
+                            }
 
                             // 文字显示速度
                             parent
@@ -1033,7 +1033,7 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                                 })
                                 .with_children(|parent| {
                                     parent.spawn((
-                                        Text::new("文字显示速度"),
+                                        locale.ui_text("settings.text_speed"),
                                         TextFont {
                                             font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                                             font_size: 18.0,
@@ -1049,86 +1049,17 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                                             ..default()
                                         })
                                         .with_children(|parent| {
-                                            // 慢速按钮
-                                            parent
-                                                .spawn((
-                                                    Button,
-                                                    Node {
-                                                        width: Val::Px(50.0),
-                                                        height: Val::Px(30.0),
-                                                        justify_content: JustifyContent::Center,
-                                                        align_items: AlignItems::Center,
-                                                        border: UiRect::all(Val::Px(1.0)),
-                                                        ..default()
-                                                    },
-                                                    BackgroundColor(Color::srgb(0.3, 0.3, 0.5)),
-                                                    BorderColor::all(Color::srgb(0.5, 0.5, 0.7)),
-                                                ))
-                                                .with_children(|parent| {
-                                                    parent.spawn((
-                                                        Text::new("慢"),
-                                                        TextFont {
-                                                            font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                            font_size: 14.0,
-                                                            ..default()
-                                                        },
-                                                        TextColor(Color::WHITE),
-                                                    ));
-                                                });
-                                            
-                                            // 中速按钮
-                                            parent
-                                                .spawn((
-                                                    Button,
-                                                    Node {
-                                                        width:Val::Px(50.0),
-                                                        height: Val::Px(30.0),
-                                                        justify_content: JustifyContent::Center,
-                                                        align_items: AlignItems::Center,
-                                                        border: UiRect::all(Val::Px(2.0)),
-                                                        ..default()
-                                                    },
-                                                    BackgroundColor(Color::srgb(0.4, 0.4, 0.6)),
-                                                    BorderColor::all(Color::srgb(0.8, 0.8, 1.0)),
-                                                ))
-                                                .with_children(|parent| {
-                                                    parent.spawn((
-                                                        Text::new("中"),
-                                                        TextFont {
-                                                            font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                            font_size: 14.0,
-                                                            ..default()
-                                                        },
-                                                        TextColor(Color::WHITE),
-                                                    ));
-                                                });
-                                            
-                                            // 快速按钮
-                                            parent
-                                                .spawn((
-                                                    Button,
-                                                    Node {
-                                                        width: Val::Px(50.0),
-                                                        height: Val::Px(30.0),
-                                                        justify_content: JustifyContent::Center,
-                                                        align_items: AlignItems::Center,
-                                                        border: UiRect::all(Val::Px(1.0)),
-                                                        ..default()
-                                                    },
-                                                    BackgroundColor(Color::srgb(0.3, 0.3, 0.5)),
-                                                    BorderColor::all(Color::srgb(0.5, 0.5, 0.7)),
-                                                ))
-                                                .with_children(|parent| {
-                                                    parent.spawn((
-                                                        Text::new("快"),
-                                                        TextFont {
-                                                            font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                            font_size: 14.0,
-                                                            ..default()
-                                                        },
-                                                        TextColor(Color::WHITE),
-                                                    ));
-                                                });
+                                            for (label, speed) in TEXT_SPEED_PRESETS {
+                                                spawn_prefs_option(
+                                                    parent,
+                                                    asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
+                                                    &config,
+                                                    &locale,
+                                                    PrefsOption::TextSpeed(speed),
+                                                    label,
+                                                    50.0,
+                                                );
+                                            }
                                         });
                                 });
 
@@ -1145,7 +1076,7 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                                 })
                                 .with_children(|parent| {
                                     parent.spawn((
-                                        Text::new("自动播放速度"),
+                                        locale.ui_text("settings.auto_play"),
                                         TextFont {
                                             font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                                             font_size: 18.0,
@@ -1156,23 +1087,22 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                                     
                                     parent
                                         .spawn(Node {
-                                            width: Val::Px(200.0),
-                                            height: Val::Px(25.0),
-                                            justify_content: JustifyContent::Center,
-                                            align_items: AlignItems::Center,
-                                            border: UiRect::all(Val::Px(1.0)),
+                                            flex_direction: FlexDirection::Row,
+                                            column_gap: Val::Px(10.0),
                                             ..default()
                                         })
                                         .with_children(|parent| {
-                                            parent.spawn((
-                                                Text::new("█████░░░░░"),
-                                                TextFont {
-                                                    font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                    font_size: 14.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::srgb(0.7, 0.7, 0.9)),
-                                            ));
+                                            for (label, interval) in AUTO_PLAY_PRESETS {
+                                                spawn_prefs_option(
+                                                    parent,
+                                                    asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
+                                                    &config,
+                                                    &locale,
+                                                    PrefsOption::AutoPlay(interval),
+                                                    label,
+                                                    50.0,
+                                                );
+                                            }
                                         });
                                 });
 
@@ -1189,7 +1119,7 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                                 })
                                 .with_children(|parent| {
                                     parent.spawn((
-                                        Text::new("全屏模式"),
+                                        locale.ui_text("settings.fullscreen"),
                                         TextFont {
                                             font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                                             font_size: 18.0,
@@ -1198,9 +1128,11 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                                         TextColor(Color::WHITE),
                                     ));
                                     
+                                    let fullscreen = config.settings.fullscreen;
                                     parent
                                         .spawn((
                                             Button,
+                                            DisplayButton::ToggleFullscreen,
                                             Node {
                                                 width: Val::Px(80.0),
                                                 height: Val::Px(35.0),
@@ -1214,13 +1146,13 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                                         ))
                                         .with_children(|parent| {
                                             parent.spawn((
-                                                Text::new("关闭"),
+                                                locale.ui_text(toggle_label(fullscreen)),
                                                 TextFont {
                                                     font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                                                     font_size: 14.0,
                                                     ..default()
                                                 },
-                                                TextColor(Color::WHITE),
+                                                TextColor(display_color(fullscreen)),
                                             ));
                                         });
                                 });
@@ -1238,7 +1170,7 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                                 })
                                 .with_children(|parent| {
                                     parent.spawn((
-                                        Text::new("跳过已读文本"),
+                                        locale.ui_text("settings.skip_unread"),
                                         TextFont {
                                             font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                                             font_size: 18.0,
@@ -1247,31 +1179,15 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                                         TextColor(Color::WHITE),
                                     ));
                                     
-                                    parent
-                                        .spawn((
-                                            Button,
-                                            Node {
-                                                width: Val::Px(80.0),
-                                                height: Val::Px(35.0),
-                                                justify_content: JustifyContent::Center,
-                                                align_items: AlignItems::Center,
-                                                border: UiRect::all(Val::Px(2.0)),
-                                                ..default()
-                                            },
-                                            BackgroundColor(Color::srgb(0.4, 0.4, 0.6)),
-                                            BorderColor::all(Color::srgb(0.8, 0.8, 1.0)),
-                                        ))
-                                        .with_children(|parent| {
-                                            parent.spawn((
-                                                Text::new("开启"),
-                                                TextFont {
-                                                    font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                    font_size: 14.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::WHITE),
-                                            ));
-                                        });
+                                    spawn_prefs_option(
+                                        parent,
+                                        asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
+                                        &config,
+                                        &locale,
+                                        PrefsOption::SkipUnread,
+                                        toggle_label(config.settings.skip_unread),
+                                        80.0,
+                                    );
                                 });
                         });
 
@@ -1293,7 +1209,7 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                         ))
                         .with_children(|parent| {
                             parent.spawn((
-                                Text::new("返回"),
+                                locale.ui_text("common.back"),
                                 TextFont {
                                     font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                                     font_size: 16.0,
@@ -1305,7 +1221,7 @@ fn setup_settings_overlay(mut commands: Commands, asset_server: Res<AssetServer>
                 });
         });
 }
-fn setup_load_scene(mut commands: Commands, asset_server: Res<AssetServer>, camera_query: Query<Entity, With<MenuCamera>>) {
+fn setup_load_scene(mut commands: Commands, asset_server: Res<AssetServer>, locale: Res<Locale>, camera_query: Query<Entity, With<MenuCamera>>) {
     println!("执行载入界面");
     
     if camera_query.is_empty() {
@@ -1345,7 +1261,7 @@ fn setup_load_scene(mut commands: Commands, asset_server: Res<AssetServer>, came
                 .with_children(|parent| {
                     // 标题
                     parent.spawn((
-                        Text::new("载入游戏"),
+                        locale.ui_text("menu.load"),
                         TextFont {
                             font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                             font_size: 28.0,
@@ -1359,51 +1275,61 @@ fn setup_load_scene(mut commands: Commands, asset_server: Res<AssetServer>, came
                         .spawn(Node {
                             flex_direction: FlexDirection::Column,
                             align_items: AlignItems::Stretch,
-                            row_gap: Val::Px(15.0),
+                            row_gap: Val::Px(10.0),
                             width: Val::Percent(100.0),
                             height: Val::Px(420.0),
                             overflow: Overflow::clip_y(),
                             ..default()
                         })
                         .with_children(|parent| {
-                            // 存档槽1
-                            parent
-                                .spawn((
-                                    Button,
+                            for (slot, data) in crate::save::list_slots() {
+                                let (heading_color, detail, time) = match &data {
+                                    Some(data) => (Color::WHITE, data.summary(), data.formatted_time()),
+                                    None => (Color::srgb(0.6, 0.6, 0.7), locale.ui("slots.empty").to_string(), String::new()),
+                                };
+                                let mut row = parent.spawn((
                                     Node {
                                         flex_direction: FlexDirection::Row,
                                         align_items: AlignItems::Center,
                                         justify_content: JustifyContent::SpaceBetween,
                                         width: Val::Percent(100.0),
-                                        height: Val::Px(80.0),
-                                        padding: UiRect::all(Val::Px(15.0)),
+                                        height: Val::Px(60.0),
+                                        padding: UiRect::axes(Val::Px(15.0), Val::Px(5.0)),
                                         border: UiRect::all(Val::Px(1.0)),
                                         ..default()
                                     },
-                                    BackgroundColor(Color::srgb(0.3, 0.3, 0.4)),
+                                    BackgroundColor(if data.is_some() {
+                                        Color::srgb(0.3, 0.3, 0.4)
+                                    } else {
+                                        Color::srgb(0.25, 0.25, 0.3)
+                                    }),
                                     BorderColor::all(Color::srgb(0.5, 0.5, 0.6)),
-                                ))
-                                .with_children(|parent| {
+                                ));
+                                // 只有已有存档的槽位可以点击
+                                if data.is_some() {
+                                    row.insert((Button, LoadSlotButton(slot)));
+                                }
+                                row.with_children(|parent| {
                                     // 存档信息
                                     parent
                                         .spawn(Node {
                                             flex_direction: FlexDirection::Column,
                                             align_items: AlignItems::Start,
-                                            row_gap: Val::Px(5.0),
+                                            row_gap: Val::Px(2.0),
                                             ..default()
                                         })
                                         .with_children(|parent| {
                                             parent.spawn((
-                                                Text::new("存档槽 1"),
+                                                Text::new(format!("{} {}", locale.ui("slots.slot"), slot)),
                                                 TextFont {
                                                     font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                                                     font_size: 16.0,
                                                     ..default()
                                                 },
-                                                TextColor(Color::WHITE),
+                                                TextColor(heading_color),
                                             ));
                                             parent.spawn((
-                                                Text::new("第一章 - 你好"),
+                                                Text::new(detail),
                                                 TextFont {
                                                     font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                                                     font_size: 14.0,
@@ -1411,151 +1337,19 @@ fn setup_load_scene(mut commands: Commands, asset_server: Res<AssetServer>, came
                                                 },
                                                 TextColor(Color::srgb(0.8, 0.8, 0.9)),
                                             ));
-                                            parent.spawn((
-                                                Text::new("2025/07/27 14:30"),
-                                                TextFont {
-                                                    font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                    font_size: 12.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::srgb(0.7, 0.7, 0.8)),
-                                            ));
                                         });
-                                    
-                                    // 预览图占位符
-                                    parent.spawn((
-                                        Node {
-                                            width: Val::Px(80.0),
-                                            height: Val::Px(50.0),
-                                            border: UiRect::all(Val::Px(1.0)),
-                                            justify_content: JustifyContent::Center,
-                                            align_items: AlignItems::Center,
-                                            ..default()
-                                        },
-                                        BackgroundColor(Color::srgb(0.4, 0.4, 0.5)),
-                                        BorderColor::all(Color::srgb(0.6, 0.6, 0.7)),
-                                    ));
-                                });
 
-                            // 存档槽2
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        flex_direction: FlexDirection::Row,
-                                        align_items: AlignItems::Center,
-                                        justify_content: JustifyContent::SpaceBetween,
-                                        width: Val::Percent(100.0),
-                                        height: Val::Px(80.0),
-                                        padding: UiRect::all(Val::Px(15.0)),
-                                        border: UiRect::all(Val::Px(1.0)),
-                                        ..default()
-                                    },
-                                    BackgroundColor(Color::srgb(0.3, 0.3, 0.4)),
-                                    BorderColor::all(Color::srgb(0.5, 0.5, 0.6)),
-                                ))
-                                .with_children(|parent| {
-                                    parent
-                                        .spawn(Node {
-                                            flex_direction: FlexDirection::Column,
-                                            align_items: AlignItems::Start,
-                                            row_gap: Val::Px(5.0),
-                                            ..default()
-                                        })
-                                        .with_children(|parent| {
-                                            parent.spawn((
-                                                Text::new("存档槽 2"),
-                                                TextFont {
-                                                    font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                    font_size: 16.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::WHITE),
-                                            ));
-                                            parent.spawn((
-                                                Text::new("第二章 - 转折"),
-                                                TextFont {
-                                                    font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                    font_size: 14.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::srgb(0.8, 0.8, 0.9)),
-                                            ));
-                                            parent.spawn((
-                                                Text::new("2025/07/30 20:15"),
-                                                TextFont {
-                                                    font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                    font_size: 12.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::srgb(0.7, 0.7, 0.8)),
-                                            ));
-                                        });
-                                    
+                                    // 存档时间
                                     parent.spawn((
-                                        Node {
-                                            width: Val::Px(80.0),
-                                            height: Val::Px(50.0),
-                                            border: UiRect::all(Val::Px(1.0)),
-                                            justify_content: JustifyContent::Center,
-                                            align_items: AlignItems::Center,
+                                        Text::new(time),
+                                        TextFont {
+                                            font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
+                                            font_size: 12.0,
                                             ..default()
                                         },
-                                        BackgroundColor(Color::srgb(0.4, 0.4, 0.5)),
-                                        BorderColor::all(Color::srgb(0.6, 0.6, 0.7)),
+                                        TextColor(Color::srgb(0.7, 0.7, 0.8)),
                                     ));
                                 });
-
-                            // 空存档槽
-                            for i in 3..=4 {
-                                parent
-                                    .spawn((
-                                        Node {
-                                            flex_direction: FlexDirection::Row,
-                                            align_items: AlignItems::Center,
-                                            justify_content: JustifyContent::SpaceBetween,
-                                            width: Val::Percent(100.0),
-                                            height: Val::Px(80.0),
-                                            padding: UiRect::all(Val::Px(15.0)),
-                                            border: UiRect::all(Val::Px(1.0)),
-                                            ..default()
-                                        },
-                                        BackgroundColor(Color::srgb(0.25, 0.25, 0.3)),
-                                        BorderColor::all(Color::srgb(0.4, 0.4, 0.5)),
-                                    ))
-                                    .with_children(|parent| {
-                                        parent
-                                            .spawn(Node {
-                                                flex_direction: FlexDirection::Column,
-                                                align_items: AlignItems::Start,
-                                                row_gap: Val::Px(5.0),
-                                                ..default()
-                                            })
-                                            .with_children(|parent| {
-                                                parent.spawn((
-                                                    Text::new(&format!("存档槽 {}", i)),
-                                                    TextFont {
-                                                        font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
-                                                        font_size: 16.0,
-                                                        ..default()
-                                                    },
-                                                    TextColor(Color::srgb(0.6, 0.6, 0.7)),
-                                                ));
-                                            });
-                                        
-                                        parent.spawn((
-                                            Node {
-                                                width: Val::Px(80.0),
-                                                height: Val::Px(50.0),
-                                                border: UiRect::all(Val::Px(1.0)),
-                                                justify_content: JustifyContent::Center,
-                                                align_items: AlignItems::Center,
-                                                ..default()
-                                            },
-                                            BackgroundColor(Color::srgb(0.3, 0.3, 0.35)),
-                                            BorderColor::all(Color::srgb(0.5, 0.5, 0.6)),
-                                        ));
-                                    });
                             }
                         });
 
@@ -1577,7 +1371,7 @@ fn setup_load_scene(mut commands: Commands, asset_server: Res<AssetServer>, came
                         ))
                         .with_children(|parent| {
                             parent.spawn((
-                                Text::new("返回"),
+                                locale.ui_text("common.back"),
                                 TextFont {
                                     font: asset_server.load("fonts/GenSenMaruGothicTW-Bold.ttf"),
                                     font_size: 16.0,
@@ -1654,6 +1448,9 @@ fn cleanup_cameras(
 fn setup_game_settings_overlay(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    config: Res<MainConfig>,
+    locale: Res<Locale>,
+    mixer: Res<AudioMixer>,
     camera_query: Query<Entity, With<MenuCamera>>,
     mut overlay_query: Query<&mut Visibility, With<GameMenuOverlay>>,
 ) {
@@ -1680,7 +1477,7 @@ fn setup_game_settings_overlay(
         SettingsEntity, // 用于清理
     )).with_children(|title_parent| {
         title_parent.spawn((
-            Text::new("设置"),
+            locale.ui_text("settings.title"),
             TextFont {
                 font: asset_server.load("fonts/SarasaFixedHC-Light.ttf"),
                 font_size: 45.0,
@@ -1799,7 +1596,7 @@ fn setup_game_settings_overlay(
                 )).with_children(|display_parent| {
                     // 显示标题
                     display_parent.spawn((
-                        Text::new("显示"),
+                        locale.ui_text("settings.display"),
                         TextFont {
                             font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                             font_size: 24.0,
@@ -1821,54 +1618,131 @@ fn setup_game_settings_overlay(
                             ..default()
                         },
                     )).with_children(|row_parent| {
-                        // 窗口按钮
-                        row_parent.spawn((
-                            Button,
-                            Node {
-                                padding: UiRect::all(Val::Px(12.0)),
-                                border: UiRect::all(Val::Px(2.0)),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                ..default()
-                            },
-                            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
-                            BorderColor::all(Color::srgb(1.0, 0.6, 0.2)),
-                        )).with_children(|button_parent| {
-                            button_parent.spawn((
-                                Text::new("窗口"),
-                                TextFont {
-                                    font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
-                                    font_size: 18.0,
+                        for (button, label) in [
+                            (DisplayButton::Windowed, "settings.window"),
+                            (DisplayButton::Fullscreen, "settings.full"),
+                        ] {
+                            row_parent.spawn((
+                                Button,
+                                button,
+                                Node {
+                                    padding: UiRect::all(Val::Px(12.0)),
+                                    border: UiRect::all(Val::Px(1.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
                                     ..default()
                                 },
-                                TextColor(Color::srgb(1.0, 0.6, 0.2)),
-                            ));
-                        });
+                                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
+                                BorderColor::all(Color::srgb(0.6, 0.6, 0.6)),
+                            )).with_children(|button_parent| {
+                                button_parent.spawn((
+                                    locale.ui_text(label),
+                                    TextFont {
+                                        font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
+                                        font_size: 18.0,
+                                        ..default()
+                                    },
+                                    TextColor(display_color(button.is_selected(&config))),
+                                ));
+                            });
+                        }
+                    });
 
-                        // 全屏按钮
-                        row_parent.spawn((
-                            Button,
+                    // 窗口大小（窗口模式下）
+                    display_parent.spawn((
+                        Node {
+                            flex_direction: FlexDirection::Row,
+                            flex_wrap: FlexWrap::Wrap,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(20.0),
+                            row_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                    )).with_children(|row_parent| {
+                        for size in resolution_presets(&config) {
+                            let button = DisplayButton::Resolution(size);
+                            row_parent.spawn((
+                                Button,
+                                button,
+                                Node {
+                                    padding: UiRect::all(Val::Px(8.0)),
+                                    border: UiRect::all(Val::Px(1.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
+                                BorderColor::all(Color::srgb(0.6, 0.6, 0.6)),
+                            )).with_children(|button_parent| {
+                                button_parent.spawn((
+                                    Text::new(resolution_label(size)),
+                                    TextFont {
+                                        font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
+                                        font_size: 16.0,
+                                        ..default()
+                                    },
+                                    TextColor(display_color(button.is_selected(&config))),
+                                ));
+                            });
+                        }
+                    });
+                });
+
+                // 音量设置组
+                settings_parent.spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(12.0),
+                        ..default()
+                    },
+                )).with_children(|volume_parent| {
+                    // 音量标题
+                    volume_parent.spawn((
+                        locale.ui_text("settings.volume"),
+                        TextFont {
+                            font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(1.0, 0.6, 0.2)),
+                        Node {
+                            margin: UiRect::bottom(Val::Px(15.0)),
+                            ..default()
+                        },
+                    ));
+
+                    for channel in AudioChannel::ALL {
+                        volume_parent.spawn((
                             Node {
-                                padding: UiRect::all(Val::Px(12.0)),
-                                border: UiRect::all(Val::Px(1.0)),
-                                justify_content: JustifyContent::Center,
+                                flex_direction: FlexDirection::Row,
                                 align_items: AlignItems::Center,
+                                column_gap: Val::Px(30.0),
                                 ..default()
                             },
-                            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
-                            BorderColor::all(Color::srgb(0.6, 0.6, 0.6)),
-                        )).with_children(|button_parent| {
-                            button_parent.spawn((
-                                Text::new("全屏"),
+                        )).with_children(|row_parent| {
+                            row_parent.spawn((
+                                locale.ui_text(channel.label_id()),
                                 TextFont {
                                     font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                                     font_size: 18.0,
                                     ..default()
                                 },
                                 TextColor(Color::WHITE),
+                                Node {
+                                    width: Val::Px(140.0),
+                                    ..default()
+                                },
                             ));
+                            spawn_volume_control(
+                                row_parent,
+                                asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
+                                &locale,
+                                &mixer,
+                                channel,
+                                Color::srgb(1.0, 0.6, 0.2),
+                            );
                         });
-                    });
+                    }
                 });
 
                 // 语言设置组
@@ -1881,7 +1755,7 @@ fn setup_game_settings_overlay(
                 )).with_children(|language_parent| {
                     // 语言标题
                     language_parent.spawn((
-                        Text::new("语言"),
+                        locale.ui_text("settings.language"),
                         TextFont {
                             font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                             font_size: 24.0,
@@ -1907,32 +1781,13 @@ fn setup_game_settings_overlay(
                             ..default()
                         },
                     )).with_children(|grid_parent| {
-                        let languages = [
-                            ("English", false),
-                            ("Español", false),
-                            ("Česky", false),
-                            ("Українська", false),
-                            ("Dansk", false),
-                            ("日本語", false),
-                            ("Français", false),
-                            ("한국어", false),
-                            ("Italiano", false),
-                            ("简体中文", true), // 选中状态
-                            ("Bahasa Melayu", false),
-                            ("繁體中文", false),
-                            ("Русский", false),
-                        ];
-
-                        for (lang, is_selected) in languages {
+                        for language in &config.languages {
+                            let is_selected = config.current_language() == Some(language.id.as_str());
                             grid_parent.spawn((
                                 Button,
+                                LanguageButton(language.id.clone()),
                                 Node {
                                     padding: UiRect::all(Val::Px(10.0)),
-                                    border: if is_selected { 
-                                        UiRect::all(Val::Px(2.0)) 
-                                    } else { 
-                                        UiRect::ZERO 
-                                    },
                                     justify_content: JustifyContent::Center,
                                     align_items: AlignItems::Center,
                                     ..default()
@@ -1941,17 +1796,13 @@ fn setup_game_settings_overlay(
                                 BorderColor::all(Color::srgb(1.0, 0.6, 0.2)),
                             )).with_children(|lang_parent| {
                                 lang_parent.spawn((
-                                    Text::new(lang),
+                                    Text::new(language.name.clone()),
                                     TextFont {
                                         font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                                         font_size: 16.0,
                                         ..default()
                                     },
-                                    TextColor(if is_selected { 
-                                        Color::srgb(1.0, 0.6, 0.2) 
-                                    } else { 
-                                        Color::WHITE 
-                                    }),
+                                    TextColor(language_color(is_selected)),
                                 ));
                             });
                         }
@@ -1979,7 +1830,7 @@ fn setup_game_settings_overlay(
         SettingsEntity,
     )).with_children(|button_parent| {
         button_parent.spawn((
-            Text::new("返回"),
+            locale.ui_text("common.back"),
             TextFont {
                 font: asset_server.load("fonts/SarasaFixedHC-Regular.ttf"),
                 font_size: 30.0,
@@ -0,0 +1,236 @@
+// src/menu/prefs.rs
+// 玩家偏好：设置界面的文字速度 / 自动播放速度 / 快进未读选项，修改后记录到 UserPrefs 并写入偏好文件
+use bevy::prelude::*;
+
+use crate::{
+    GameScene,
+    audio::{AudioChannel, AudioMixer},
+    config::{
+        MainConfig,
+        prefs::{UserPrefs, read_user_prefs, write_user_prefs},
+    },
+    locale::{Locale, LocalizedText},
+};
+
+/// 文字速度的选项（界面文字 id，每秒字数）
+pub const TEXT_SPEED_PRESETS: [(&str, u32); 3] =
+    [("speed.slow", 20), ("speed.normal", 50), ("speed.fast", 100)];
+
+/// 自动播放速度的选项（界面文字 id，每行停留秒数）
+pub const AUTO_PLAY_PRESETS: [(&str, f32); 3] =
+    [("speed.slow", 3.0), ("speed.normal", 2.0), ("speed.fast", 1.0)];
+
+/// 修改后等待多久写入文件（秒），拖动音量滑块时避免每帧写入
+const PREFS_SAVE_DELAY: f32 = 0.5;
+
+const OPTION_COLOR: Color = Color::srgb(0.3, 0.3, 0.5);
+const SELECTED_OPTION_COLOR: Color = Color::srgb(0.5, 0.5, 0.8);
+
+/// 设置界面中的偏好选项按钮
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum PrefsOption {
+    TextSpeed(u32),
+    AutoPlay(f32),
+    SkipUnread,
+}
+
+impl PrefsOption {
+    fn is_selected(self, config: &MainConfig) -> bool {
+        match self {
+            PrefsOption::TextSpeed(speed) => config.settings.text_speed == speed,
+            PrefsOption::AutoPlay(interval) => {
+                (config.settings.auto_play_interval - interval).abs() < f32::EPSILON
+            }
+            PrefsOption::SkipUnread => config.settings.skip_unread,
+        }
+    }
+}
+
+/// 开关类选项按钮上的文字
+#[derive(Component)]
+struct PrefsToggleText(PrefsOption);
+
+// 等待写入的偏好，值为剩余秒数
+#[derive(Resource, Default)]
+struct PrefsSaveTimer(Option<f32>);
+
+pub struct PrefsPlugin;
+
+impl Plugin for PrefsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UserPrefs>()
+            .init_resource::<PrefsSaveTimer>()
+            .add_systems(Startup, init_prefs)
+            .add_systems(
+                Update,
+                (
+                    handle_prefs_options.run_if(
+                        in_state(GameScene::Settings).or(in_state(GameScene::GameSettings)),
+                    ),
+                    update_prefs_options,
+                    record_mixer_prefs,
+                    save_prefs,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// 生成一个偏好选项按钮（样式与设置界面的其他按钮一致），label 为界面文字 id
+pub fn spawn_prefs_option(
+    parent: &mut ChildSpawnerCommands,
+    font: Handle<Font>,
+    config: &MainConfig,
+    locale: &Locale,
+    option: PrefsOption,
+    label: &'static str,
+    width: f32,
+) {
+    let selected = option.is_selected(config);
+    parent
+        .spawn((
+            Button,
+            option,
+            Node {
+                width: Val::Px(width),
+                height: Val::Px(30.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(option_color(selected)),
+            BorderColor::all(Color::srgb(0.5, 0.5, 0.7)),
+        ))
+        .with_children(|parent| {
+            let mut text = parent.spawn((
+                locale.ui_text(label),
+                TextFont {
+                    font,
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            if option == PrefsOption::SkipUnread {
+                text.insert(PrefsToggleText(option));
+            }
+        });
+}
+
+/// 开关类选项显示的文字（界面文字 id）
+pub fn toggle_label(enabled: bool) -> &'static str {
+    if enabled { "common.on" } else { "common.off" }
+}
+
+fn init_prefs(mut commands: Commands, config: Res<MainConfig>, mut mixer: ResMut<AudioMixer>) {
+    commands.insert_resource(read_user_prefs(&config.title));
+    *mixer = AudioMixer::from_settings(&config.settings);
+}
+
+// 点击选项：修改 settings（立即生效）并记录到偏好
+fn handle_prefs_options(
+    mut config: ResMut<MainConfig>,
+    mut prefs: ResMut<UserPrefs>,
+    options: Query<(&Interaction, &PrefsOption), Changed<Interaction>>,
+) {
+    for (interaction, option) in options.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *option {
+            PrefsOption::TextSpeed(speed) => {
+                config.settings.text_speed = speed;
+                prefs.text_speed = Some(speed);
+            }
+            PrefsOption::AutoPlay(interval) => {
+                config.settings.auto_play_interval = interval;
+                prefs.auto_play_interval = Some(interval);
+            }
+            PrefsOption::SkipUnread => {
+                let skip_unread = !config.settings.skip_unread;
+                config.settings.skip_unread = skip_unread;
+                prefs.skip_unread = Some(skip_unread);
+            }
+        }
+    }
+}
+
+// settings 变化时刷新选项按钮的高亮与文字
+fn update_prefs_options(
+    config: Res<MainConfig>,
+    locale: Res<Locale>,
+    mut options: Query<(&PrefsOption, &mut BackgroundColor)>,
+    mut toggle_texts: Query<(&PrefsToggleText, &mut LocalizedText, &mut Text)>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+    for (option, mut color) in options.iter_mut() {
+        color.0 = option_color(option.is_selected(&config));
+    }
+    for (toggle, mut localized, mut text) in toggle_texts.iter_mut() {
+        localized.0 = toggle_label(toggle.0.is_selected(&config));
+        text.0 = locale.ui(localized.0).to_string();
+    }
+}
+
+// 混音器与 settings 不一致时（玩家调节了音量或静音）记录到偏好
+fn record_mixer_prefs(
+    mixer: Res<AudioMixer>,
+    mut config: ResMut<MainConfig>,
+    mut prefs: ResMut<UserPrefs>,
+) {
+    if !mixer.is_changed() {
+        return;
+    }
+    for channel in AudioChannel::ALL {
+        let volume = mixer.channel(channel).volume;
+        let saved = config.settings.volumes.get(channel.key()).copied().unwrap_or(1.0);
+        if (saved - volume).abs() > f32::EPSILON {
+            config.settings.volumes.insert(channel.key().to_string(), volume);
+            prefs.volumes.insert(channel.key().to_string(), volume);
+        }
+    }
+    let muted: Vec<String> = AudioChannel::ALL
+        .into_iter()
+        .filter(|channel| mixer.channel(*channel).muted)
+        .map(|channel| channel.key().to_string())
+        .collect();
+    if config.settings.muted_channels != muted {
+        config.settings.muted_channels = muted.clone();
+        prefs.muted_channels = Some(muted);
+    }
+}
+
+// 偏好变化后稍等片刻写入文件
+fn save_prefs(
+    time: Res<Time>,
+    config: Res<MainConfig>,
+    prefs: Res<UserPrefs>,
+    mut timer: ResMut<PrefsSaveTimer>,
+) {
+    if prefs.is_changed() && !prefs.is_added() {
+        timer.0 = Some(PREFS_SAVE_DELAY);
+    }
+    let Some(remaining) = timer.0.as_mut() else {
+        return;
+    };
+    *remaining -= time.delta_secs();
+    if *remaining > 0.0 {
+        return;
+    }
+    timer.0 = None;
+    match write_user_prefs(&config.title, &prefs) {
+        Ok(()) => println!("玩家偏好已保存"),
+        Err(e) => eprintln!("保存玩家偏好失败: {}", e),
+    }
+}
+
+fn option_color(selected: bool) -> Color {
+    if selected {
+        SELECTED_OPTION_COLOR
+    } else {
+        OPTION_COLOR
+    }
+}
@@ -0,0 +1,23 @@
+// src/motion/mod.rs
+// 变换动画（类似 Ren'Py 的 ATL）：立绘、头像等精灵按脚本移动、缩放、旋转、渐隐
+// 可以在 main.yaml 的 transforms 中命名，在台词、show 指令中按名称或直接写出使用
+mod parse;
+mod player;
+
+pub use parse::*;
+pub use player::*;
+
+use bevy::prelude::*;
+
+use crate::transition::TransitionPlugin;
+
+pub struct MotionPlugin;
+
+impl Plugin for MotionPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TransitionPlugin>() {
+            app.add_plugins(TransitionPlugin);
+        }
+        app.add_systems(Update, update_motions);
+    }
+}
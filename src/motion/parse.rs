@@ -0,0 +1,418 @@
+// src/motion/parse.rs
+// 变换动画的写法：语句之间用 `;` 或换行分隔，`repeat [次数] { ... }` 重复执行，`parallel { ... }` 中的语句同时执行
+//
+//   move to [x, y] [over] 秒数 [缓动]     位移到相对原位置的 [x, y]（像素，y 轴向上）
+//   move by [dx, dy] [over] 秒数 [缓动]   在当前位置上位移
+//   slide in from left|right|up|down [秒数] [缓动]   从画面外移入原位置
+//   slide out to left|right|up|down [秒数] [缓动]    移出画面
+//   alpha 不透明度 [秒数] [缓动]          省略秒数时立即生效，scale / rotate 相同
+//   scale 倍数 [秒数] [缓动]
+//   rotate 角度 [秒数] [缓动]             顺时针为正
+//   wait 秒数
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    easing::{Easing, split_words_with},
+    effect::{parse_point, parse_seconds},
+    transition::Direction,
+};
+
+/// 省略秒数时 slide 的时长
+const DEFAULT_SLIDE_DURATION: f32 = 0.5;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum MotionError {
+    #[error("无法识别的语句 '{0}'")]
+    UnknownStatement(String),
+    #[error("语句 '{0}' 的参数无效")]
+    InvalidArguments(String),
+    #[error("缺少 '}}'")]
+    UnclosedBlock,
+    #[error("多余的 '}}'")]
+    UnexpectedClose,
+}
+
+/// 位移的目标
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveTarget {
+    /// 相对原位置
+    To(Vec2),
+    /// 相对当前位置
+    By(Vec2),
+    /// 从画面外移入原位置
+    SlideIn(Direction),
+    /// 移出画面
+    SlideOut(Direction),
+}
+
+/// 一条语句
+#[derive(Debug, Clone, PartialEq)]
+pub enum MotionStep {
+    Move {
+        target: MoveTarget,
+        duration: f32,
+        easing: Easing,
+    },
+    Alpha {
+        value: f32,
+        duration: f32,
+        easing: Easing,
+    },
+    Scale {
+        value: f32,
+        duration: f32,
+        easing: Easing,
+    },
+    Rotate {
+        degrees: f32,
+        duration: f32,
+        easing: Easing,
+    },
+    Wait(f32),
+    /// count 为 None 时一直重复
+    Repeat {
+        count: Option<u32>,
+        body: Vec<MotionStep>,
+    },
+    /// 同时执行，全部完成后继续
+    Parallel(Vec<MotionStep>),
+}
+
+impl MotionStep {
+    fn is_finite(&self) -> bool {
+        match self {
+            MotionStep::Repeat { count: None, .. } => false,
+            MotionStep::Repeat { body, .. } | MotionStep::Parallel(body) => {
+                body.iter().all(MotionStep::is_finite)
+            }
+            _ => true,
+        }
+    }
+
+    fn uses_alpha(&self) -> bool {
+        match self {
+            MotionStep::Alpha { .. } => true,
+            MotionStep::Repeat { body, .. } | MotionStep::Parallel(body) => {
+                body.iter().any(MotionStep::uses_alpha)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// 一段变换动画
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Motion {
+    pub steps: Vec<MotionStep>,
+}
+
+impl Motion {
+    /// 解析变换动画的写法
+    pub fn parse(source: &str) -> Result<Self, MotionError> {
+        let tokens = tokenize(source);
+        let mut position = 0;
+        let steps = parse_block(&tokens, &mut position, false)?;
+        Ok(Motion { steps })
+    }
+
+    /// source 为 main.yaml 中 transforms 的名称时使用其内容，否则按写法解析
+    pub fn parse_named(source: &str, named: &HashMap<String, String>) -> Result<Self, MotionError> {
+        Self::parse(named.get(source.trim()).map_or(source, String::as_str))
+    }
+
+    /// 解析脚本中写的变换动画，写法无效时提示并返回 None
+    pub fn parse_or_warn(source: &str, named: &HashMap<String, String>) -> Option<Self> {
+        Self::parse_named(source, named)
+            .inspect_err(|e| eprintln!("无效的变换动画 '{}': {}", source, e))
+            .ok()
+    }
+
+    /// 没有无限重复的部分，会在有限时间内结束
+    pub fn is_finite(&self) -> bool {
+        self.steps.iter().all(MotionStep::is_finite)
+    }
+
+    /// 会改变不透明度（没有 alpha 语句时不修改精灵的颜色）
+    pub fn uses_alpha(&self) -> bool {
+        self.steps.iter().any(MotionStep::uses_alpha)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Open,
+    Close,
+    End,
+}
+
+// 括号内的空白、分号不分开，与转场、镜头效果的分词相同
+fn tokenize(source: &str) -> Vec<Token> {
+    split_words_with(source, &['{', '}', ';', '\n'])
+        .into_iter()
+        .map(|word| match word.as_str() {
+            "{" => Token::Open,
+            "}" => Token::Close,
+            ";" | "\n" => Token::End,
+            _ => Token::Word(word),
+        })
+        .collect()
+}
+
+fn parse_block(
+    tokens: &[Token],
+    position: &mut usize,
+    nested: bool,
+) -> Result<Vec<MotionStep>, MotionError> {
+    let mut steps = Vec::new();
+    let mut words: Vec<&str> = Vec::new();
+    while let Some(token) = tokens.get(*position) {
+        *position += 1;
+        match token {
+            Token::Word(word) => words.push(word),
+            Token::End => {
+                if !words.is_empty() {
+                    steps.push(parse_statement(&words)?);
+                    words.clear();
+                }
+            }
+            Token::Open => {
+                let body = parse_block(tokens, position, true)?;
+                steps.push(parse_block_statement(&words, body)?);
+                words.clear();
+            }
+            Token::Close => {
+                if !nested {
+                    return Err(MotionError::UnexpectedClose);
+                }
+                if !words.is_empty() {
+                    steps.push(parse_statement(&words)?);
+                }
+                return Ok(steps);
+            }
+        }
+    }
+    if nested {
+        return Err(MotionError::UnclosedBlock);
+    }
+    if !words.is_empty() {
+        steps.push(parse_statement(&words)?);
+    }
+    Ok(steps)
+}
+
+fn parse_block_statement(words: &[&str], body: Vec<MotionStep>) -> Result<MotionStep, MotionError> {
+    let invalid = || MotionError::InvalidArguments(words.join(" "));
+    match words {
+        ["repeat"] => Ok(MotionStep::Repeat { count: None, body }),
+        ["repeat", count] => Ok(MotionStep::Repeat {
+            count: Some(count.parse().map_err(|_| invalid())?),
+            body,
+        }),
+        ["parallel"] => Ok(MotionStep::Parallel(body)),
+        _ => Err(MotionError::UnknownStatement(words.join(" "))),
+    }
+}
+
+fn parse_statement(words: &[&str]) -> Result<MotionStep, MotionError> {
+    let invalid = || MotionError::InvalidArguments(words.join(" "));
+    let number = |word: &str| {
+        word.parse::<f32>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .ok_or_else(invalid)
+    };
+    match words {
+        ["move", mode @ ("to" | "by"), point, rest @ ..] => {
+            let point = parse_point(point).ok_or_else(invalid)?;
+            let (duration, easing) = parse_timing(rest).ok_or_else(invalid)?;
+            let target = if *mode == "to" {
+                MoveTarget::To(point)
+            } else {
+                MoveTarget::By(point)
+            };
+            Ok(MotionStep::Move {
+                target,
+                duration: duration.unwrap_or(0.0),
+                easing,
+            })
+        }
+        ["slide", mode @ ("in" | "out"), preposition, direction, rest @ ..] => {
+            let direction = Direction::parse(direction).ok_or_else(invalid)?;
+            let target = match (*mode, *preposition) {
+                ("in", "from") => MoveTarget::SlideIn(direction),
+                ("out", "to") => MoveTarget::SlideOut(direction),
+                _ => return Err(invalid()),
+            };
+            let (duration, easing) = parse_timing(rest).ok_or_else(invalid)?;
+            Ok(MotionStep::Move {
+                target,
+                duration: duration.unwrap_or(DEFAULT_SLIDE_DURATION),
+                easing,
+            })
+        }
+        [name @ ("alpha" | "scale" | "rotate"), value, rest @ ..] => {
+            let value = number(value)?;
+            let (duration, easing) = parse_timing(rest).ok_or_else(invalid)?;
+            let duration = duration.unwrap_or(0.0);
+            Ok(match *name {
+                "alpha" => MotionStep::Alpha {
+                    value: value.clamp(0.0, 1.0),
+                    duration,
+                    easing,
+                },
+                "scale" => MotionStep::Scale {
+                    value,
+                    duration,
+                    easing,
+                },
+                _ => MotionStep::Rotate {
+                    degrees: value,
+                    duration,
+                    easing,
+                },
+            })
+        }
+        ["wait", seconds] => match parse_seconds(seconds) {
+            Some(seconds) if seconds >= 0.0 => Ok(MotionStep::Wait(seconds)),
+            _ => Err(invalid()),
+        },
+        _ => Err(MotionError::UnknownStatement(words.join(" "))),
+    }
+}
+
+// `[over] 秒数 [缓动]`，缓动省略时为线性
+fn parse_timing(words: &[&str]) -> Option<(Option<f32>, Easing)> {
+    let mut duration = None;
+    let mut easing = None;
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        let seconds = if *word == "over" {
+            Some(parse_seconds(words.next()?)?)
+        } else {
+            parse_seconds(word)
+        };
+        match seconds {
+            Some(seconds) if seconds < 0.0 => return None,
+            Some(seconds) if duration.is_none() => duration = Some(seconds),
+            Some(_) => return None,
+            None if easing.is_none() => easing = Some(Easing::parse(word)?),
+            None => return None,
+        }
+    }
+    Some((duration, easing.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::easing::{Curve, EaseMode};
+
+    #[test]
+    fn statements_split_on_semicolons_and_newlines() {
+        let bounce = Motion::parse(
+            "move by [0, 24] 0.12 ease_out_quad; move by [0, -24] over 0.12\nwait 1s",
+        )
+        .unwrap();
+        assert_eq!(
+            bounce.steps,
+            vec![
+                MotionStep::Move {
+                    target: MoveTarget::By(Vec2::new(0.0, 24.0)),
+                    duration: 0.12,
+                    easing: Easing::Curve(Curve::Quad, EaseMode::Out),
+                },
+                MotionStep::Move {
+                    target: MoveTarget::By(Vec2::new(0.0, -24.0)),
+                    duration: 0.12,
+                    easing: Easing::Linear,
+                },
+                MotionStep::Wait(1.0),
+            ]
+        );
+        assert_eq!(Motion::parse(";\n;"), Ok(Motion::default()));
+    }
+
+    #[test]
+    fn slide_and_instant_properties() {
+        let motion = Motion::parse("slide out to right; alpha 1.5; rotate -10").unwrap();
+        assert_eq!(
+            motion.steps,
+            vec![
+                MotionStep::Move {
+                    target: MoveTarget::SlideOut(Direction::Right),
+                    duration: DEFAULT_SLIDE_DURATION,
+                    easing: Easing::Linear,
+                },
+                // 不透明度限制在 0 ~ 1，省略秒数时立即生效
+                MotionStep::Alpha {
+                    value: 1.0,
+                    duration: 0.0,
+                    easing: Easing::Linear,
+                },
+                MotionStep::Rotate {
+                    degrees: -10.0,
+                    duration: 0.0,
+                    easing: Easing::Linear,
+                },
+            ]
+        );
+        assert!(motion.uses_alpha());
+    }
+
+    #[test]
+    fn nested_blocks() {
+        let breathe = Motion::parse(
+            "repeat {\n    scale 1.02 1.5 ease_in_out_sine\n    scale 1.0 1.5 ease_in_out_sine\n}",
+        )
+        .unwrap();
+        match breathe.steps.as_slice() {
+            [MotionStep::Repeat { count: None, body }] => assert_eq!(body.len(), 2),
+            steps => panic!("{:?}", steps),
+        }
+        assert!(!breathe.is_finite());
+
+        let pulse = Motion::parse("repeat 3 { parallel { alpha 0 0.2; scale 2 0.2 } }").unwrap();
+        assert!(pulse.is_finite());
+        assert!(pulse.uses_alpha());
+        match pulse.steps.as_slice() {
+            [MotionStep::Repeat { count: Some(3), body }] => {
+                assert!(matches!(body.as_slice(), [MotionStep::Parallel(steps)] if steps.len() == 2));
+            }
+            steps => panic!("{:?}", steps),
+        }
+    }
+
+    #[test]
+    fn unbalanced_braces() {
+        assert_eq!(Motion::parse("repeat { scale 2 1"), Err(MotionError::UnclosedBlock));
+        assert_eq!(Motion::parse("scale 2 1 }"), Err(MotionError::UnexpectedClose));
+        assert_eq!(
+            Motion::parse("loop { wait 1 }"),
+            Err(MotionError::UnknownStatement("loop".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        for source in [
+            "repeat twice { wait 1 }",
+            "wait -1",
+            "wait nan",
+            "scale 2 inf",
+            "rotate nan 1",
+            "move to [inf, 0] 1",
+            "slide in to left",
+            "scale 2 1 2",
+        ] {
+            assert!(
+                matches!(Motion::parse(source), Err(MotionError::InvalidArguments(_))),
+                "{}",
+                source
+            );
+        }
+    }
+}
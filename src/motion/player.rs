@@ -0,0 +1,352 @@
+// src/motion/player.rs
+// 按时间执行变换动画：位移、缩放、旋转相对精灵的原位置，结束后保持最后的状态
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use super::parse::{Motion, MotionStep, MoveTarget};
+use crate::{
+    easing::Easing,
+    transition::{Direction, SpriteTransition, TransitionSettings},
+};
+
+/// 取不到窗口时按这个大小计算移出画面的距离
+const FALLBACK_SCREEN_SIZE: Vec2 = Vec2::new(1400.0, 770.0);
+
+/// 动画当前的状态（相对原位置）
+#[derive(Debug, Clone, Copy)]
+struct MotionState {
+    offset: Vec2,
+    alpha: f32,
+    scale: f32,
+    /// 角度，顺时针为正
+    rotation: f32,
+}
+
+impl Default for MotionState {
+    fn default() -> Self {
+        MotionState {
+            offset: Vec2::ZERO,
+            alpha: 1.0,
+            scale: 1.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Property {
+    Offset(Vec2, Vec2),
+    Alpha(f32, f32),
+    Scale(f32, f32),
+    Rotation(f32, f32),
+}
+
+impl Property {
+    fn set(self, state: &mut MotionState, t: f32) {
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+        match self {
+            Property::Offset(from, to) => state.offset = from.lerp(to, t),
+            Property::Alpha(from, to) => state.alpha = lerp(from, to).clamp(0.0, 1.0),
+            Property::Scale(from, to) => state.scale = lerp(from, to),
+            Property::Rotation(from, to) => state.rotation = lerp(from, to),
+        }
+    }
+}
+
+// 正在执行的语句
+enum Active {
+    Tween {
+        property: Property,
+        elapsed: f32,
+        duration: f32,
+        easing: Easing,
+    },
+    Wait {
+        elapsed: f32,
+        duration: f32,
+    },
+    Repeat {
+        remaining: Option<u32>,
+        body: Vec<MotionStep>,
+        run: Box<Sequence>,
+    },
+    Parallel(Vec<Option<Sequence>>),
+}
+
+impl Active {
+    fn start(step: &MotionStep, state: &MotionState, screen: Vec2) -> Self {
+        let tween = |property, duration, easing| Active::Tween {
+            property,
+            elapsed: 0.0,
+            duration,
+            easing,
+        };
+        match step {
+            MotionStep::Move {
+                target,
+                duration,
+                easing,
+            } => {
+                let offscreen = |direction: Direction| {
+                    Vec2::from(direction.vector()) * screen
+                };
+                let (from, to) = match *target {
+                    MoveTarget::To(point) => (state.offset, point),
+                    MoveTarget::By(delta) => (state.offset, state.offset + delta),
+                    MoveTarget::SlideIn(direction) => (offscreen(direction), Vec2::ZERO),
+                    MoveTarget::SlideOut(direction) => {
+                        (state.offset, state.offset + offscreen(direction))
+                    }
+                };
+                tween(Property::Offset(from, to), *duration, *easing)
+            }
+            MotionStep::Alpha {
+                value,
+                duration,
+                easing,
+            } => tween(Property::Alpha(state.alpha, *value), *duration, *easing),
+            MotionStep::Scale {
+                value,
+                duration,
+                easing,
+            } => tween(Property::Scale(state.scale, *value), *duration, *easing),
+            MotionStep::Rotate {
+                degrees,
+                duration,
+                easing,
+            } => tween(Property::Rotation(state.rotation, *degrees), *duration, *easing),
+            MotionStep::Wait(duration) => Active::Wait {
+                elapsed: 0.0,
+                duration: *duration,
+            },
+            MotionStep::Repeat { count, body } => Active::Repeat {
+                remaining: *count,
+                body: body.clone(),
+                run: Box::new(Sequence::new(body.clone())),
+            },
+            MotionStep::Parallel(steps) => Active::Parallel(
+                steps
+                    .iter()
+                    .map(|step| Some(Sequence::new(vec![step.clone()])))
+                    .collect(),
+            ),
+        }
+    }
+
+    // 推进 delta 秒；结束时返回剩余的时间
+    fn advance(&mut self, delta: f32, state: &mut MotionState, screen: Vec2) -> Option<f32> {
+        match self {
+            Active::Tween {
+                property,
+                elapsed,
+                duration,
+                easing,
+            } => {
+                *elapsed += delta;
+                let progress = if *duration > 0.0 {
+                    *elapsed / *duration
+                } else {
+                    1.0
+                };
+                property.set(state, easing.apply(progress));
+                (*elapsed >= *duration).then_some(*elapsed - *duration)
+            }
+            Active::Wait { elapsed, duration } => {
+                *elapsed += delta;
+                (*elapsed >= *duration).then_some(*elapsed - *duration)
+            }
+            Active::Repeat {
+                remaining,
+                body,
+                run,
+            } => {
+                let mut delta = delta;
+                loop {
+                    if *remaining == Some(0) {
+                        return Some(delta);
+                    }
+                    let left = run.advance(delta, state, screen)?;
+                    // 一轮没有用掉时间（内容都是立即完成的）时，一直重复会卡住，留到下一帧
+                    if remaining.is_none() && left >= delta {
+                        **run = Sequence::new(body.clone());
+                        return None;
+                    }
+                    if let Some(count) = remaining {
+                        *count -= 1;
+                    }
+                    **run = Sequence::new(body.clone());
+                    delta = left;
+                }
+            }
+            Active::Parallel(runs) => {
+                let mut left = delta;
+                for slot in runs.iter_mut() {
+                    if let Some(run_left) = slot
+                        .as_mut()
+                        .and_then(|run| run.advance(delta, state, screen))
+                    {
+                        left = left.min(run_left);
+                        *slot = None;
+                    }
+                }
+                runs.iter().all(Option::is_none).then_some(left)
+            }
+        }
+    }
+}
+
+// 依次执行的语句
+struct Sequence {
+    steps: Vec<MotionStep>,
+    index: usize,
+    current: Option<Active>,
+}
+
+impl Sequence {
+    fn new(steps: Vec<MotionStep>) -> Self {
+        Sequence {
+            steps,
+            index: 0,
+            current: None,
+        }
+    }
+
+    fn advance(&mut self, mut delta: f32, state: &mut MotionState, screen: Vec2) -> Option<f32> {
+        loop {
+            if self.current.is_none() {
+                let Some(step) = self.steps.get(self.index) else {
+                    return Some(delta);
+                };
+                self.current = Some(Active::start(step, state, screen));
+                self.index += 1;
+            }
+            let active = self.current.as_mut()?;
+            delta = active.advance(delta, state, screen)?;
+            self.current = None;
+        }
+    }
+}
+
+/// 播放变换动画的精灵
+#[derive(Component)]
+pub struct MotionPlayer {
+    run: Sequence,
+    state: MotionState,
+    finite: bool,
+    uses_alpha: bool,
+    done: bool,
+    /// 精灵原来的位置、缩放与旋转，动画相对它计算；调整精灵位置时应修改这里
+    pub base: Transform,
+    /// 精灵原来的不透明度
+    base_alpha: f32,
+    screen: Vec2,
+}
+
+impl MotionPlayer {
+    fn new(motion: Motion, base: Transform, base_alpha: f32) -> Self {
+        MotionPlayer {
+            finite: motion.is_finite(),
+            uses_alpha: motion.uses_alpha(),
+            done: false,
+            run: Sequence::new(motion.steps),
+            state: MotionState::default(),
+            base,
+            base_alpha,
+            screen: FALLBACK_SCREEN_SIZE,
+        }
+    }
+
+    /// 动画会结束（没有无限重复），点击或快进时可以直接完成
+    pub fn is_finite(&self) -> bool {
+        self.finite
+    }
+
+    /// 还在播放（结束后组件保留，精灵停在最后的状态）
+    pub fn is_playing(&self) -> bool {
+        !self.done
+    }
+
+    /// 立即完成（无限重复的动画不受影响），下一帧写入最终状态
+    pub fn finish(&mut self) {
+        if self.finite && !self.done {
+            self.advance(f32::INFINITY);
+        }
+    }
+
+    fn advance(&mut self, delta: f32) {
+        self.done = self.run.advance(delta, &mut self.state, self.screen).is_some();
+    }
+
+    /// 写回原来的位置、缩放、旋转与不透明度（移除动画前调用）
+    pub fn reset(&self, sprite: &mut Sprite, transform: &mut Transform) {
+        *transform = self.base;
+        if self.uses_alpha {
+            sprite.color = sprite.color.with_alpha(self.base_alpha);
+        }
+    }
+
+    fn apply(&self, sprite: &mut Sprite, transform: &mut Transform) {
+        transform.translation = self.base.translation + self.state.offset.extend(0.0);
+        transform.scale = self.base.scale * Vec3::new(self.state.scale, self.state.scale, 1.0);
+        transform.rotation =
+            self.base.rotation * Quat::from_rotation_z(-self.state.rotation.to_radians());
+        if self.uses_alpha {
+            sprite.color = sprite.color.with_alpha(self.base_alpha * self.state.alpha);
+        }
+    }
+}
+
+/// 在精灵上播放变换动画；精灵上已有动画时替换它，从原来的位置重新开始
+///
+/// 精灵正在转场（推移、缩放等）时，动画等转场结束后再开始
+pub fn play_motion(commands: &mut Commands, entity: Entity, motion: Motion) {
+    commands.queue(move |world: &mut World| {
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+        let (base, base_alpha) = match entity.take::<MotionPlayer>() {
+            Some(player) => (player.base, player.base_alpha),
+            // 转场中的位置与颜色是临时的，以转场结束后恢复的为准
+            None => match entity.get::<SpriteTransition>() {
+                Some(transition) => (transition.origin, transition.color.alpha()),
+                None => (
+                    entity.get::<Transform>().copied().unwrap_or_default(),
+                    entity.get::<Sprite>().map_or(1.0, |sprite| sprite.color.alpha()),
+                ),
+            },
+        };
+        entity.insert(MotionPlayer::new(motion, base, base_alpha));
+    });
+}
+
+// 结束后只在 base 被修改（例如立绘换了位置）时重新写入
+//
+// 转场中的精灵由转场控制位置与缩放，动画暂停，转场结束后再继续
+pub fn update_motions(
+    time: Res<Time>,
+    settings: Res<TransitionSettings>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut players: Query<
+        (&mut MotionPlayer, &mut Sprite, &mut Transform),
+        Without<SpriteTransition>,
+    >,
+) {
+    let screen = window
+        .single()
+        .map_or(FALLBACK_SCREEN_SIZE, |window| window.size());
+    for (mut player, mut sprite, mut transform) in players.iter_mut() {
+        if player.done && !player.is_changed() {
+            continue;
+        }
+        if !player.done {
+            player.screen = screen;
+            // 快进时会结束的动画直接完成
+            let delta = if settings.instant && player.finite {
+                f32::INFINITY
+            } else {
+                time.delta_secs()
+            };
+            player.advance(delta);
+        }
+        player.apply(&mut sprite, &mut transform);
+    }
+}
@@ -3,10 +3,20 @@ use bevy::prelude::*;
 use bevy::ui::ContentSize;
 use bevy::window::{WindowResolution, WindowResized};
 use crate::raven::script::Script;
+use crate::raven::character::Character;
+use crate::config::MainConfig;
 use crate::raven::config; 
 use bevy::app::AppExit; 
 use std::collections::HashMap;
 use crate::raven::scene::SceneCommand;
+use crate::script::markup::parse_color;
+use crate::audio::{crossfade_music, fade_out_audio};
+use crate::effect::{CameraEffect, CameraEffects, EffectPlugin};
+use crate::motion::{Motion, MotionPlayer, MotionPlugin, play_motion};
+use crate::transition::{Direction, finish_transition, start_transition, Transition, TransitionPlugin};
+
+/// DSL 中 play music / stop music 没有写 fade 时的淡入淡出秒数
+const DEFAULT_MUSIC_FADE: f32 = 1.0;
 
 
 //  打字机组件
@@ -135,6 +145,14 @@ pub struct RavenStory {
     pub waiting_for_input: bool,
     pub waiting_for_asset_load: bool,
     pub waiting_for_typewriter: bool, 
+    pub waiting_for_motion: bool,
+}
+
+/// 正在播放的音乐
+#[derive(Resource, Default)]
+pub struct RavenMusic {
+    pub file: Option<String>,
+    pub entity: Option<Entity>,
 }
 
 #[derive(Component)]
@@ -187,9 +205,22 @@ pub struct RavenPlugin;
 
 impl Plugin for RavenPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<crate::audio::AudioPlugin>() {
+            app.add_plugins(crate::audio::AudioPlugin);
+        }
+        if !app.is_plugin_added::<TransitionPlugin>() {
+            app.add_plugins(TransitionPlugin);
+        }
+        if !app.is_plugin_added::<EffectPlugin>() {
+            app.add_plugins(EffectPlugin);
+        }
+        if !app.is_plugin_added::<MotionPlugin>() {
+            app.add_plugins(MotionPlugin);
+        }
         app
             .init_state::<GameState>()
             .init_resource::<AssetCache>()
+            .init_resource::<RavenMusic>()
             // 添加虚拟屏幕资源
             .insert_resource(CanvasConfig {
                 width: 1920.0,  // 视觉小说常用分辨率
@@ -413,11 +444,25 @@ fn execute_simple_command(
     dialogue_ui_query: &Query<Entity, With<DialogueUI>>,
     exit: &mut EventWriter<AppExit>,
     asset_cache: &Res<AssetCache>,
+    music: &mut RavenMusic,
+    camera: &mut CameraEffects,
 ) -> bool {
     match command {
-        SceneCommand::ShowBackground { background } => {
+        SceneCommand::ShowBackground { background, transition } => {
+            let transition = parse_transition(transition);
+            // 有转场时保留一张旧背景放在新背景下面，转场结束后移除
+            let mut old_background = None;
             for entity in background_query.iter() {
-                commands.entity(entity).despawn();
+                if transition.is_some() && old_background.is_none() {
+                    finish_transition(commands, entity);
+                    commands
+                        .entity(entity)
+                        .remove::<BackgroundSprite>()
+                        .insert(Transform::from_translation(Vec3::new(0.0, 0.0, -10.5)));
+                    old_background = Some(entity);
+                } else {
+                    commands.entity(entity).despawn();
+                }
             }
 
             if let Some(bg) = raven_story.story.get_background(background) {
@@ -427,7 +472,7 @@ fn execute_simple_command(
                     asset_server.load(&bg.image)
                 };
                 
-                commands.spawn((
+                let entity = commands.spawn((
                     Sprite {
                         custom_size: Some(Vec2::new(1920.0, 1080.0)), // 匹配虚拟分辨率
                         ..Sprite::from_image(handle)
@@ -435,50 +480,89 @@ fn execute_simple_command(
                     Transform::from_translation(Vec3::new(0.0, 0.0, -10.0)),
                     BackgroundSprite,
                     ScalableUI::new().with_sprite_size(Vec2::new(1920.0, 1080.0)),
-                ));
+                )).id();
+                if let Some(transition) = transition {
+                    start_transition(commands, transition, old_background, Some(entity));
+                }
                 println!("显示背景: {}", background);
+            } else if let Some(entity) = old_background {
+                commands.entity(entity).despawn();
             }
             false
         },
-        SceneCommand::ShowCharacter { character, emotion } => {
-            let mut character_exists = false;
-            for (_, char_comp) in character_query.iter() {
+        SceneCommand::ShowCharacter { character, emotion, transition, transform } => {
+            let mut shown_entity = None;
+            for (entity, char_comp) in character_query.iter() {
                 if char_comp.character_id == *character {
-                    character_exists = true;
+                    shown_entity = Some(entity);
+                    // 已在场的角色切换表情
+                    if let (Some(char), Some(_)) = (raven_story.story.get_character(character), emotion) {
+                        warn_missing_expression(char, emotion);
+                        let sprite = char.sprite_for(emotion.as_deref());
+                        commands.entity(entity).insert(Sprite::from_image(asset_server.load(&sprite)));
+                        println!("切换表情: {} -> {}", char.name, sprite);
+                    }
                     break;
                 }
             }
 
-            if !character_exists {
+            if shown_entity.is_none() {
                 if let Some(char) = raven_story.story.get_character(character) {
-                    commands.spawn((
-                        Sprite::from_image(asset_server.load(&char.sprite)),
+                    warn_missing_expression(char, emotion);
+                    let entity = commands.spawn((
+                        Sprite::from_image(asset_server.load(char.sprite_for(emotion.as_deref()))),
                         Transform::from_translation(Vec3::new(0.0, -200.0, 0.0))
                             .with_scale(Vec3::splat(1.0)), // 基于虚拟分辨率调整
                         CharacterSprite {
                             character_id: character.clone(),
                         },
                         ScalableUI::new(), // 角色也支持缩放
-                    ));
+                    )).id();
+                    if let Some(transition) = parse_transition(transition) {
+                        start_transition(commands, transition, None, Some(entity));
+                    }
+                    shown_entity = Some(entity);
 
                     let emotion_text = emotion.as_ref().map(|e| format!(" [{}]", e)).unwrap_or_default();
                     println!("显示角色: {}{}", char.name, emotion_text);
                 }
             }
+
+            let motion = transform
+                .as_deref()
+                .and_then(|source| Motion::parse_or_warn(source, &raven_story.story.transforms));
+            if let (Some(entity), Some(motion)) = (shown_entity, motion) {
+                play_motion(commands, entity, motion);
+            }
             false
         },
         // ... 其他命令保持不变
         _ => {
             // 复制原有的其他命令处理逻辑
             match command {
-                SceneCommand::PlayMusic { file } => {
-                    println!("播放音乐: {}", file);
+                SceneCommand::PlayMusic { file, fade } => {
+                    // 正在播放同一首时继续播放
+                    if music.file.as_deref() != Some(file.as_str()) {
+                        let fade = fade.unwrap_or(DEFAULT_MUSIC_FADE);
+                        let entity = crossfade_music(commands, asset_server, music.entity.take(), file, fade);
+                        music.file = Some(file.clone());
+                        music.entity = Some(entity);
+                        println!("播放音乐: {}（淡入淡出 {} 秒）", file, fade);
+                    }
+                    false
+                },
+                SceneCommand::StopMusic { fade } => {
+                    if let Some(entity) = music.entity.take() {
+                        fade_out_audio(commands, entity, fade.unwrap_or(DEFAULT_MUSIC_FADE));
+                    }
+                    music.file = None;
+                    println!("停止音乐");
                     false
                 },
-                SceneCommand::HideCharacter { character } => {
+                SceneCommand::HideCharacter { character, transition } => {
                     for (entity, char_comp) in character_query.iter() {
                         if char_comp.character_id == *character {
-                            commands.entity(entity).despawn();
+                            hide_sprite::<CharacterSprite>(commands, entity, transition);
                             println!("隐藏角色: {}", character);
                             break;
                         }
@@ -516,9 +600,9 @@ fn execute_simple_command(
                     exit.write(AppExit::Success);
                     false
                 },
-                SceneCommand::HideBackground => {
+                SceneCommand::HideBackground { transition } => {
                     for entity in background_query.iter() {
-                        commands.entity(entity).despawn();
+                        hide_sprite::<BackgroundSprite>(commands, entity, transition);
                     }
                     println!("隐藏背景");
                     false
@@ -530,12 +614,106 @@ fn execute_simple_command(
                     println!("隐藏对话框");
                     false
                 },
+                SceneCommand::Shake { .. }
+                | SceneCommand::Zoom { .. }
+                | SceneCommand::Pan { .. }
+                | SceneCommand::Flash { .. }
+                | SceneCommand::ResetCamera { .. } => {
+                    if let Some(effect) = camera_effect(command) {
+                        camera.play(commands, &effect);
+                        println!("镜头效果: {:?}", effect);
+                    }
+                    false
+                },
+                SceneCommand::WaitMotion => {
+                    raven_story.waiting_for_motion = true;
+                    false
+                },
                 _ => false,
             }
         }
     }
 }
 
+/// 把镜头效果命令转换为 CameraEffect，省略的参数使用默认值
+fn camera_effect(command: &SceneCommand) -> Option<CameraEffect> {
+    let effect = match command {
+        SceneCommand::Shake { duration, strength } => CameraEffect::Shake {
+            duration: duration.unwrap_or(CameraEffect::DEFAULT_SHAKE_DURATION),
+            strength: strength.unwrap_or(CameraEffect::DEFAULT_SHAKE_STRENGTH),
+        },
+        SceneCommand::Zoom { scale, focus, duration } => CameraEffect::Zoom {
+            scale: *scale,
+            focus: focus.map(Vec2::from),
+            duration: duration.unwrap_or(CameraEffect::DEFAULT_MOTION_DURATION),
+            easing: CameraEffect::DEFAULT_EASING,
+        },
+        SceneCommand::Pan { direction, duration } => {
+            let Some(direction) = Direction::parse(direction) else {
+                eprintln!("无效的平移方向 '{}'（可用: left / right / up / down）", direction);
+                return None;
+            };
+            CameraEffect::Pan {
+                direction,
+                distance: CameraEffect::DEFAULT_PAN_DISTANCE,
+                duration: duration.unwrap_or(CameraEffect::DEFAULT_MOTION_DURATION),
+                easing: CameraEffect::DEFAULT_EASING,
+            }
+        },
+        SceneCommand::Flash { color } => {
+            let color = match color {
+                Some(name) => {
+                    let Some(color) = CameraEffect::parse_color(name) else {
+                        eprintln!("无效的闪光颜色 '{}'", name);
+                        return None;
+                    };
+                    color
+                }
+                None => Color::WHITE,
+            };
+            CameraEffect::Flash {
+                color,
+                duration: CameraEffect::DEFAULT_FLASH_DURATION,
+            }
+        },
+        SceneCommand::ResetCamera { duration } => CameraEffect::Reset {
+            duration: duration.unwrap_or(CameraEffect::DEFAULT_MOTION_DURATION),
+            easing: CameraEffect::DEFAULT_EASING,
+        },
+        _ => return None,
+    };
+    Some(effect)
+}
+
+/// 解析命令中写的转场，没有写时为 None（直接切换）
+fn warn_missing_expression(character: &Character, emotion: &Option<String>) {
+    if !character.has_expression(emotion.as_deref()) {
+        eprintln!(
+            "找不到角色 {} 的表情 {}，使用默认立绘",
+            character.name,
+            emotion.as_deref().unwrap_or_default()
+        );
+    }
+}
+
+fn parse_transition(transition: &Option<String>) -> Option<Transition> {
+    transition.as_deref().map(Transition::parse_or_default)
+}
+
+/// 移除背景或角色；有转场时先去掉标记组件，转场结束后再移除
+fn hide_sprite<T: Component>(commands: &mut Commands, entity: Entity, transition: &Option<String>) {
+    match parse_transition(transition) {
+        Some(transition) => {
+            finish_transition(commands, entity);
+            commands.entity(entity).remove::<T>();
+            start_transition(commands, transition, Some(entity), None);
+        }
+        None => {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
 // === 其余函数保持不变 ===
 fn handle_input(keys: Res<ButtonInput<KeyCode>>, mouse: Res<ButtonInput<MouseButton>>, mut raven_story: ResMut<RavenStory>) {
     if raven_story.waiting_for_input {
@@ -553,11 +731,21 @@ fn handle_scene_progress(
     character_query: Query<(Entity, &CharacterSprite)>,
     dialogue_ui_query: Query<Entity, With<DialogueUI>>,
     mut exit: EventWriter<AppExit>, 
-    asset_cache: Res<AssetCache>
+    asset_cache: Res<AssetCache>,
+    mut music: ResMut<RavenMusic>,
+    mut camera: ResMut<CameraEffects>,
+    motions: Query<&MotionPlayer>,
 ) {
     if raven_story.waiting_for_input || raven_story.waiting_for_asset_load { 
         return;
     }
+    // wait motion：等会结束的变换动画播放完
+    if raven_story.waiting_for_motion {
+        if motions.iter().any(|player| player.is_finite() && player.is_playing()) {
+            return;
+        }
+        raven_story.waiting_for_motion = false;
+    }
 
     let current_scene_id = match &raven_story.current_scene {
         Some(id) => id.clone(),
@@ -569,9 +757,12 @@ fn handle_scene_progress(
         None => return,
     };
 
-    while raven_story.scene_index < scene_commands.len() && !raven_story.waiting_for_input {
+    while raven_story.scene_index < scene_commands.len()
+        && !raven_story.waiting_for_input
+        && !raven_story.waiting_for_motion
+    {
         let command = scene_commands[raven_story.scene_index].clone();
-        let should_pause = execute_simple_command(&command, &mut commands, &asset_server, &mut raven_story, &background_query, &character_query, &dialogue_ui_query, &mut exit, &asset_cache);
+        let should_pause = execute_simple_command(&command, &mut commands, &asset_server, &mut raven_story, &background_query, &character_query, &dialogue_ui_query, &mut exit, &asset_cache, &mut music, &mut camera);
 
         raven_story.scene_index += 1;
 
@@ -582,7 +773,7 @@ fn handle_scene_progress(
     }
 }
 
-fn update_dialogue_display(raven_story: Res<RavenStory>, mut speaker_query: Query<&mut Text, (With<SpeakerNameText>, Without<DialogueText>)>, mut dialogue_query: Query<&mut Text, (With<DialogueText>, Without<SpeakerNameText>)>) {
+fn update_dialogue_display(raven_story: Res<RavenStory>, mut speaker_query: Query<(&mut Text, &mut TextColor), (With<SpeakerNameText>, Without<DialogueText>)>, mut dialogue_query: Query<&mut Text, (With<DialogueText>, Without<SpeakerNameText>)>) {
     if let Some(scene_id) = &raven_story.current_scene {
         if let Some(scene) = raven_story.story.get_scene(scene_id) {
             if raven_story.scene_index > 0 && raven_story.scene_index <= scene.commands.len() {
@@ -590,11 +781,18 @@ fn update_dialogue_display(raven_story: Res<RavenStory>, mut speaker_query: Quer
 
                 match command {
                     SceneCommand::Dialogue { speaker, text } => {
-                        if let Ok(mut speaker_text) = speaker_query.single_mut() {
+                        if let Ok((mut speaker_text, mut speaker_color)) = speaker_query.single_mut() {
                             if let Some(character) = raven_story.story.get_character(speaker) {
                                 **speaker_text = character.name.clone();
+                                // 角色的 color 用作名字颜色，与 main.yaml 角色表的规则相同
+                                speaker_color.0 = character
+                                    .color
+                                    .as_deref()
+                                    .and_then(parse_color)
+                                    .map_or(Color::WHITE, |[r, g, b, a]| Color::srgba(r, g, b, a));
                             } else {
                                 **speaker_text = speaker.clone();
+                                speaker_color.0 = Color::WHITE;
                             }
                         }
 
@@ -603,7 +801,8 @@ fn update_dialogue_display(raven_story: Res<RavenStory>, mut speaker_query: Quer
                         }
                     },
                     SceneCommand::PlayerThinks { text } => {
-                        if let Ok(mut speaker_text) = speaker_query.single_mut() {
+                        if let Ok((mut speaker_text, mut speaker_color)) = speaker_query.single_mut() {
+                            speaker_color.0 = Color::WHITE;
                             **speaker_text = "内心想法".to_string();
                         }
                         if let Ok(mut dialogue_text) = dialogue_query.single_mut() {
@@ -611,7 +810,8 @@ fn update_dialogue_display(raven_story: Res<RavenStory>, mut speaker_query: Quer
                         }
                     },
                     SceneCommand::PlayerSays { text } => {
-                        if let Ok(mut speaker_text) = speaker_query.single_mut() {
+                        if let Ok((mut speaker_text, mut speaker_color)) = speaker_query.single_mut() {
+                            speaker_color.0 = Color::WHITE;
                             **speaker_text = "玩家".to_string();
                         }
                         if let Ok(mut dialogue_text) = dialogue_query.single_mut() {
@@ -619,7 +819,8 @@ fn update_dialogue_display(raven_story: Res<RavenStory>, mut speaker_query: Quer
                         }
                     },
                     _ => {
-                        if let Ok(mut speaker_text) = speaker_query.single_mut() {
+                        if let Ok((mut speaker_text, mut speaker_color)) = speaker_query.single_mut() {
+                            speaker_color.0 = Color::WHITE;
                             **speaker_text = "".to_string();
                         }
                         if let Ok(mut dialogue_text) = dialogue_query.single_mut() {
@@ -633,7 +834,10 @@ fn update_dialogue_display(raven_story: Res<RavenStory>, mut speaker_query: Quer
 }
 
 pub fn run_raven_game(story_option: Option<Script>) {
-    if let Some(story) = story_option {
+    if let Some(mut story) = story_option {
+        // 表情与 YAML 脚本共用 main.yaml 和角色目录的规则
+        let exe_dir = std::env::current_exe().unwrap().parent().unwrap().to_path_buf();
+        story.resolve_expressions(&MainConfig::load(), &exe_dir.join("assets"));
         App::new()
             .add_plugins(
                 DefaultPlugins.set(WindowPlugin {
@@ -649,6 +853,7 @@ pub fn run_raven_game(story_option: Option<Script>) {
                 waiting_for_input: false,
                 waiting_for_asset_load: true,
                 waiting_for_typewriter: true,
+                waiting_for_motion: false,
             })
             .insert_state(GameState::Playing)
             .run();
@@ -683,13 +888,16 @@ fn preload_all_assets(
     }
     
     for (_, character) in &raven_story.story.characters {
-        if !asset_cache.cached_characters.contains_key(&character.sprite) {
-            let handle = asset_server.load(&character.sprite);
-            asset_cache.cached_characters.insert(character.sprite.clone(), handle.clone());
-            commands.spawn(LoadingAsset {
-                asset_handle: handle,
-                asset_path: character.sprite.clone(),
-            });
+        // 默认立绘和声明的表情一起预加载
+        for sprite in std::iter::once(&character.sprite).chain(character.expressions.values()) {
+            if !asset_cache.cached_characters.contains_key(sprite) {
+                let handle = asset_server.load(sprite);
+                asset_cache.cached_characters.insert(sprite.clone(), handle.clone());
+                commands.spawn(LoadingAsset {
+                    asset_handle: handle,
+                    asset_path: sprite.clone(),
+                });
+            }
         }
     }
 }
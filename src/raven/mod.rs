@@ -4,6 +4,8 @@ pub mod config;
 pub mod menu;
 pub mod script {  
     use std::collections::HashMap;
+    use std::path::Path;
+    use crate::config::MainConfig;
     use crate::raven::character::Character;
     use crate::raven::scene::Scene;
     use crate::raven::background::Background;
@@ -13,6 +15,8 @@ pub mod script {
         pub characters: HashMap<String, Character>,
         pub scenes: HashMap<String, Scene>,
         pub backgrounds: HashMap<String, Background>,
+        /// 命名的变换动画（与 main.yaml 的 transforms 相同）
+        pub transforms: HashMap<String, String>,
         pub start_scene: Option<String>,
     }
 
@@ -22,6 +26,7 @@ pub mod script {
                 characters: HashMap::new(),
                 scenes: HashMap::new(),
                 backgrounds: HashMap::new(),
+                transforms: HashMap::new(),
                 start_scene: None,
             }
         }
@@ -41,10 +46,22 @@ pub mod script {
             self.backgrounds.insert(id, background);
         }
 
+        pub fn add_transform(&mut self, id: String, source: String) {
+            self.transforms.insert(id, source);
+        }
+
         pub fn get_character(&self, id: &str) -> Option<&Character> {
             self.characters.get(id)
         }
 
+        /// 按 assets 目录中的图片和 main.yaml 的表情补全每个角色的表情
+        pub fn resolve_expressions(&mut self, config: &MainConfig, assets_dir: &Path) {
+            let shared = config.character_expressions(assets_dir);
+            for (id, character) in &mut self.characters {
+                character.resolve_expressions(assets_dir, shared.get(id));
+            }
+        }
+
         pub fn get_scene(&self, id: &str) -> Option<&Scene> {
             self.scenes.get(id)
         }
@@ -56,11 +73,17 @@ pub mod script {
 }
 
 pub mod character {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use crate::config::scan_expressions;
+
     #[derive(Debug, Clone)]
     pub struct Character {
         pub name: String,
         pub sprite: String,
         pub color: Option<String>,
+        pub expressions: HashMap<String, String>,
     }
 
     impl Character {
@@ -69,6 +92,7 @@ pub mod character {
                 name,
                 sprite,
                 color: None,
+                expressions: HashMap::new(),
             }
         }
 
@@ -76,6 +100,71 @@ pub mod character {
             self.color = Some(color);
             self
         }
+
+        pub fn with_expression(mut self, expression: String, sprite: String) -> Self {
+            self.expressions.insert(expression, sprite);
+            self
+        }
+
+        // 表情对应的图片，没有这个表情时使用默认立绘
+        pub fn sprite_for(&self, emotion: Option<&str>) -> String {
+            emotion
+                .and_then(|emotion| self.expressions.get(emotion))
+                .unwrap_or(&self.sprite)
+                .clone()
+        }
+
+        pub fn has_expression(&self, emotion: Option<&str>) -> bool {
+            emotion.is_none_or(|emotion| self.expressions.contains_key(emotion))
+        }
+
+        // 补全表情（与 YAML 脚本的规则相同）：默认立绘同目录下的图片，
+        // `images/sylvie green normal.png` 这样的立绘只取 `sylvie green *.png`；
+        // 再加上 main.yaml 中同 id 角色的表情，脚本中声明的表情优先
+        pub fn resolve_expressions(&mut self, assets_dir: &Path, shared: Option<&HashMap<String, String>>) {
+            let sprite = Path::new(&self.sprite);
+            let dir = sprite
+                .parent()
+                .map(|dir| dir.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            let stem = sprite.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+            let prefix = stem.rfind(' ').map(|index| &stem[..=index]).unwrap_or("");
+            let mut expressions = scan_expressions(assets_dir, &dir, prefix);
+            if let Some(shared) = shared {
+                expressions.extend(shared.iter().map(|(name, path)| (name.clone(), path.clone())));
+            }
+            expressions.extend(self.expressions.drain());
+            self.expressions = expressions;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::fs;
+
+        #[test]
+        fn expressions_follow_the_sprite_name() {
+            let assets_dir = std::env::temp_dir().join(format!("raven_character_{}", std::process::id()));
+            let _ = fs::remove_dir_all(&assets_dir);
+            fs::create_dir_all(assets_dir.join("images")).unwrap();
+            for file in ["sylvie green normal.png", "sylvie green smile.png", "sylvie blue smile.png", "notes.txt"] {
+                fs::write(assets_dir.join("images").join(file), "").unwrap();
+            }
+            let shared = HashMap::from([("wink".to_string(), "portraits/wink.png".to_string())]);
+            let mut sylvie = Character::new("希尔薇".to_string(), "images/sylvie green normal.png".to_string())
+                .with_expression("wink".to_string(), "images/sylvie blue smile.png".to_string());
+            sylvie.resolve_expressions(&assets_dir, Some(&shared));
+            let _ = fs::remove_dir_all(&assets_dir);
+
+            assert_eq!(sylvie.sprite_for(Some("smile")), "images/sylvie green smile.png");
+            assert_eq!(sylvie.sprite_for(Some("normal")), "images/sylvie green normal.png");
+            // 脚本中声明的表情优先于 main.yaml
+            assert_eq!(sylvie.sprite_for(Some("wink")), "images/sylvie blue smile.png");
+            assert!(!sylvie.has_expression(Some("surprised")));
+            assert_eq!(sylvie.sprite_for(Some("surprised")), "images/sylvie green normal.png");
+            assert_eq!(sylvie.expressions.len(), 3);
+        }
     }
 }
 
@@ -87,19 +176,33 @@ pub mod scene {
 
     #[derive(Debug, Clone)]
     pub enum SceneCommand {
+        /// 播放音乐，fade 为与上一首交叉淡化的秒数（None 时使用默认值）
         PlayMusic {
             file: String,
+            fade: Option<f32>,
+        },
+        /// 淡出并停止音乐
+        StopMusic {
+            fade: Option<f32>,
         },
+        /// transition 为转场的写法（与 dialogues.yaml 相同，例如 `"wipe left 1.0"`），None 时直接切换
         ShowBackground {
             background: String,
+            transition: Option<String>,
         },
-        HideBackground,
+        HideBackground {
+            transition: Option<String>,
+        },
+        /// transform 为变换动画的写法或 transform 声明的名称，角色已在场时也会播放
         ShowCharacter {
             character: String,
             emotion: Option<String>,
+            transition: Option<String>,
+            transform: Option<String>,
         },
         HideCharacter {
             character: String,
+            transition: Option<String>,
         },
         Dialogue {
             speaker: String,
@@ -122,6 +225,28 @@ pub mod scene {
         },
         ExitGame, 
         HideDialogueBox,
+        /// 镜头效果，省略的参数使用 dialogues.yaml 中 effect 的默认值
+        Shake {
+            duration: Option<f32>,
+            strength: Option<f32>,
+        },
+        Zoom {
+            scale: f32,
+            focus: Option<[f32; 2]>,
+            duration: Option<f32>,
+        },
+        Pan {
+            direction: String,
+            duration: Option<f32>,
+        },
+        Flash {
+            color: Option<String>,
+        },
+        ResetCamera {
+            duration: Option<f32>,
+        },
+        /// 等角色的变换动画播放完（一直重复的动画不等待）
+        WaitMotion,
     }
 
     #[derive(Debug, Clone)]
@@ -241,21 +366,27 @@ pub mod game {
         use crate::raven::scene::SceneCommand;
         
         match command {
-            SceneCommand::PlayMusic { file } => {
+            SceneCommand::PlayMusic { file, .. } => {
                 println!("🎵 播放音乐: {}", file);
             },
-            SceneCommand::ShowBackground { background } => {
+            SceneCommand::StopMusic { .. } => {
+                println!("🎵 停止音乐");
+            },
+            SceneCommand::ShowBackground { background, .. } => {
                 if let Some(bg) = script.get_background(background) {
                     println!("🖼️ 显示背景: {} ({})", background, bg.image);
                 }
             },
-            SceneCommand::ShowCharacter { character, emotion } => {
+            SceneCommand::ShowCharacter { character, emotion, transform, .. } => {
                 if let Some(char) = script.get_character(character) {
                     let emotion_text = emotion.as_ref().map(|e| format!(" [{}]", e)).unwrap_or_default();
-                    println!(" 显示角色: {}{} ({})", char.name, emotion_text, char.sprite);
+                    println!(" 显示角色: {}{} ({})", char.name, emotion_text, char.sprite_for(emotion.as_deref()));
+                    if let Some(transform) = transform {
+                        println!(" 变换动画: {}", transform);
+                    }
                 }
             },
-            SceneCommand::HideCharacter { character } => {
+            SceneCommand::HideCharacter { character, .. } => {
                 println!(" 隐藏角色: {}", character);
             },
             SceneCommand::Dialogue { speaker, text } => {
@@ -288,12 +419,30 @@ pub mod game {
                 println!(" 退出游戏");
                 end_raven_game();
             },
-            SceneCommand::HideBackground => {
+            SceneCommand::HideBackground { .. } => {
                 println!(" 隐藏背景");
             },
             SceneCommand::HideDialogueBox => {
                 println!(" 隐藏对话框");
             },
+            SceneCommand::Shake { .. } => {
+                println!(" 镜头震动");
+            },
+            SceneCommand::Zoom { scale, .. } => {
+                println!(" 镜头缩放: {}", scale);
+            },
+            SceneCommand::Pan { direction, .. } => {
+                println!(" 镜头平移: {}", direction);
+            },
+            SceneCommand::Flash { .. } => {
+                println!(" 闪光");
+            },
+            SceneCommand::ResetCamera { .. } => {
+                println!(" 镜头复位");
+            },
+            SceneCommand::WaitMotion => {
+                println!(" 等待变换动画");
+            },
         }
     }
 
@@ -355,6 +504,11 @@ macro_rules! parse_story_items {
         $crate::parse_story_items!($script, $($rest)*);
     };
     
+    ($script:ident, transform $transform_id:ident $source:literal $($rest:tt)*) => {
+        $script.add_transform(stringify!($transform_id).to_string(), $source.to_string());
+        $crate::parse_story_items!($script, $($rest)*);
+    };
+    
     ($script:ident, scene $scene_id:ident { $($scene_content:tt)* } $($rest:tt)*) => {
         let scene = $crate::parse_scene!($($scene_content)*);
         $script.add_scene(stringify!($scene_id).to_string(), scene);
@@ -368,33 +522,42 @@ macro_rules! parse_character {
         let mut name = String::new();
         let mut sprite = String::new();
         let mut color: Option<String> = None;
-        $crate::parse_character_fields!(name, sprite, color, $($content)*);
+        let mut expressions: Vec<(String, String)> = Vec::new();
+        $crate::parse_character_fields!(name, sprite, color, expressions, $($content)*);
         
         let mut character = $crate::raven::character::Character::new(name, sprite);
         if let Some(c) = color {
             character = character.with_color(c);
         }
+        for (expression, sprite) in expressions {
+            character = character.with_expression(expression, sprite);
+        }
         character
     }};
 }
 
 #[macro_export]
 macro_rules! parse_character_fields {
-    ($name:ident, $sprite:ident, $color:ident,) => {};
+    ($name:ident, $sprite:ident, $color:ident, $expressions:ident,) => {};
     
-    ($name:ident, $sprite:ident, $color:ident, name = $value:expr; $($rest:tt)*) => {
+    ($name:ident, $sprite:ident, $color:ident, $expressions:ident, name = $value:expr; $($rest:tt)*) => {
         $name = $value.to_string();
-        $crate::parse_character_fields!($name, $sprite, $color, $($rest)*);
+        $crate::parse_character_fields!($name, $sprite, $color, $expressions, $($rest)*);
     };
     
-    ($name:ident, $sprite:ident, $color:ident, sprite = $value:expr; $($rest:tt)*) => {
+    ($name:ident, $sprite:ident, $color:ident, $expressions:ident, sprite = $value:expr; $($rest:tt)*) => {
         $sprite = $value.to_string();
-        $crate::parse_character_fields!($name, $sprite, $color, $($rest)*);
+        $crate::parse_character_fields!($name, $sprite, $color, $expressions, $($rest)*);
     };
     
-    ($name:ident, $sprite:ident, $color:ident, color = $value:expr; $($rest:tt)*) => {
+    ($name:ident, $sprite:ident, $color:ident, $expressions:ident, color = $value:expr; $($rest:tt)*) => {
         $color = Some($value.to_string());
-        $crate::parse_character_fields!($name, $sprite, $color, $($rest)*);
+        $crate::parse_character_fields!($name, $sprite, $color, $expressions, $($rest)*);
+    };
+    
+    ($name:ident, $sprite:ident, $color:ident, $expressions:ident, expression $expression:literal = $value:expr; $($rest:tt)*) => {
+        $expressions.push(($expression.to_string(), $value.to_string()));
+        $crate::parse_character_fields!($name, $sprite, $color, $expressions, $($rest)*);
     };
 }
 
@@ -430,32 +593,135 @@ macro_rules! parse_scene {
 macro_rules! parse_scene_commands {
     ($scene:ident,) => {};
     
+    // 带淡入淡出时间的写法需要放在前面
+    ($scene:ident, play music $file:literal fade $seconds:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::PlayMusic {
+            file: $file.to_string(),
+            fade: Some($seconds as f32),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+
     ($scene:ident, play music $file:literal $($rest:tt)*) => {
         $scene.add_command($crate::raven::scene::SceneCommand::PlayMusic {
             file: $file.to_string(),
+            fade: None,
         });
         $crate::parse_scene_commands!($scene, $($rest)*);
     };
+
+    ($scene:ident, stop music fade $seconds:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::StopMusic {
+            fade: Some($seconds as f32),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+
+    ($scene:ident, stop music $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::StopMusic { fade: None });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
     
+    // 带转场的写法（with "dissolve 0.8"）需要放在前面
+    ($scene:ident, show background $bg:ident with $transition:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::ShowBackground {
+            background: stringify!($bg).to_string(),
+            transition: Some($transition.to_string()),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+
     ($scene:ident, show background $bg:ident $($rest:tt)*) => {
         $scene.add_command($crate::raven::scene::SceneCommand::ShowBackground {
             background: stringify!($bg).to_string(),
+            transition: None,
         });
         $crate::parse_scene_commands!($scene, $($rest)*);
     };
     
-    ($scene:ident, show character $char:ident $($rest:tt)*) => {
+    // 带表情的写法需要放在前面，否则会被不带表情的规则先匹配
+    ($scene:ident, show character $char:ident as $emotion:literal transform $transform:literal $($rest:tt)*) => {
         $scene.add_command($crate::raven::scene::SceneCommand::ShowCharacter {
             character: stringify!($char).to_string(),
-            emotion: None,
+            emotion: Some($emotion.to_string()),
+            transition: None,
+            transform: Some($transform.to_string()),
         });
         $crate::parse_scene_commands!($scene, $($rest)*);
     };
-    
+
+    ($scene:ident, show character $char:ident as $emotion:literal with $transition:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::ShowCharacter {
+            character: stringify!($char).to_string(),
+            emotion: Some($emotion.to_string()),
+            transition: Some($transition.to_string()),
+            transform: None,
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+
     ($scene:ident, show character $char:ident as $emotion:literal $($rest:tt)*) => {
         $scene.add_command($crate::raven::scene::SceneCommand::ShowCharacter {
             character: stringify!($char).to_string(),
             emotion: Some($emotion.to_string()),
+            transition: None,
+            transform: None,
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+
+    ($scene:ident, show character $char:ident with $transition:literal transform $transform:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::ShowCharacter {
+            character: stringify!($char).to_string(),
+            emotion: None,
+            transition: Some($transition.to_string()),
+            transform: Some($transform.to_string()),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+
+    ($scene:ident, show character $char:ident with $transition:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::ShowCharacter {
+            character: stringify!($char).to_string(),
+            emotion: None,
+            transition: Some($transition.to_string()),
+            transform: None,
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    
+    ($scene:ident, show character $char:ident transform $transform:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::ShowCharacter {
+            character: stringify!($char).to_string(),
+            emotion: None,
+            transition: None,
+            transform: Some($transform.to_string()),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+
+    ($scene:ident, show character $char:ident $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::ShowCharacter {
+            character: stringify!($char).to_string(),
+            emotion: None,
+            transition: None,
+            transform: None,
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+
+    ($scene:ident, hide character $char:ident with $transition:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::HideCharacter {
+            character: stringify!($char).to_string(),
+            transition: Some($transition.to_string()),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+
+    ($scene:ident, hide character $char:ident $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::HideCharacter {
+            character: stringify!($char).to_string(),
+            transition: None,
         });
         $crate::parse_scene_commands!($scene, $($rest)*);
     };
@@ -506,14 +772,113 @@ macro_rules! parse_scene_commands {
         $scene.add_command($crate::raven::scene::SceneCommand::ExitGame);
         $crate::parse_scene_commands!($scene, $($rest)*);
     };
+    ($scene:ident, hide background with $transition:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::HideBackground {
+            transition: Some($transition.to_string()),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
     ($scene:ident, hide background $($rest:tt)*) => {
-        $scene.add_command($crate::raven::scene::SceneCommand::HideBackground);
+        $scene.add_command($crate::raven::scene::SceneCommand::HideBackground { transition: None });
         $crate::parse_scene_commands!($scene, $($rest)*);
     };
     ($scene:ident, hide dialogue box $($rest:tt)*) => {
         $scene.add_command($crate::raven::scene::SceneCommand::HideDialogueBox);
         $crate::parse_scene_commands!($scene, $($rest)*);
     };
+
+    // 镜头效果：参数多的写法需要放在前面
+    ($scene:ident, shake $seconds:literal strength $strength:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Shake {
+            duration: Some($seconds as f32),
+            strength: Some($strength as f32),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, shake $seconds:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Shake {
+            duration: Some($seconds as f32),
+            strength: None,
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, shake $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Shake {
+            duration: None,
+            strength: None,
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, zoom $scale:literal to [$x:literal, $y:literal] over $seconds:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Zoom {
+            scale: $scale as f32,
+            focus: Some([$x as f32, $y as f32]),
+            duration: Some($seconds as f32),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, zoom $scale:literal to [$x:literal, $y:literal] $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Zoom {
+            scale: $scale as f32,
+            focus: Some([$x as f32, $y as f32]),
+            duration: None,
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, zoom $scale:literal over $seconds:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Zoom {
+            scale: $scale as f32,
+            focus: None,
+            duration: Some($seconds as f32),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, zoom $scale:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Zoom {
+            scale: $scale as f32,
+            focus: None,
+            duration: None,
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, pan $direction:ident $seconds:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Pan {
+            direction: stringify!($direction).to_string(),
+            duration: Some($seconds as f32),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, pan $direction:ident $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Pan {
+            direction: stringify!($direction).to_string(),
+            duration: None,
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, flash $color:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Flash {
+            color: Some($color.to_string()),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, flash $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::Flash { color: None });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, reset camera $seconds:literal $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::ResetCamera {
+            duration: Some($seconds as f32),
+        });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, reset camera $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::ResetCamera { duration: None });
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
+    ($scene:ident, wait motion $($rest:tt)*) => {
+        $scene.add_command($crate::raven::scene::SceneCommand::WaitMotion);
+        $crate::parse_scene_commands!($scene, $($rest)*);
+    };
 }
 
 #[macro_export]
@@ -0,0 +1,191 @@
+// src/save/mod.rs
+use bevy::prelude::*;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    env, fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+use crate::{config::VariableValue, effect::CameraView, script::dialogue::ShowCharacter};
+
+/// 存档槽数量
+pub const SAVE_SLOT_COUNT: usize = 6;
+
+/// 存档摘要的最大字符数
+const EXCERPT_MAX_CHARS: usize = 24;
+
+/// 一个存档槽保存的游戏快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveData {
+    /// 当前对话行
+    pub current_line: usize,
+    /// 当前显示的背景名称（对应 assets.backgrounds 的键）
+    #[serde(default)]
+    pub background: Option<String>,
+    /// 当前播放的BGM名称（对应 assets.audio.bgm 的键）
+    #[serde(default)]
+    pub bgm: Option<String>,
+    /// 当前显示的SWF名称（对应 assets.swf 的键）
+    #[serde(default)]
+    pub swf: Option<String>,
+    /// 画面上的角色（进入当前行之前）
+    #[serde(default)]
+    pub characters: Vec<ShowCharacter>,
+    /// 镜头的缩放与位置（进入当前行之前）
+    #[serde(default)]
+    pub camera: CameraView,
+    /// 脚本变量
+    #[serde(default)]
+    pub variables: HashMap<String, VariableValue>,
+    /// 存档时的说话角色
+    #[serde(default)]
+    pub character: String,
+    /// 存档时的对话摘要
+    #[serde(default)]
+    pub excerpt: String,
+    /// 存档时间（UNIX 秒）
+    pub saved_at: u64,
+}
+
+impl SaveData {
+    /// 生成存档列表中显示的摘要文本
+    pub fn make_excerpt(text: &str) -> String {
+        let mut excerpt: String = text.chars().take(EXCERPT_MAX_CHARS).collect();
+        if text.chars().count() > EXCERPT_MAX_CHARS {
+            excerpt.push('…');
+        }
+        excerpt
+    }
+
+    /// 存档列表中显示的标题行，例如 "艾拉：舰长，我们收到了…"
+    pub fn summary(&self) -> String {
+        if self.character.is_empty() || self.character == "none" {
+            self.excerpt.clone()
+        } else {
+            format!("{}：{}", self.character, self.excerpt)
+        }
+    }
+
+    /// 格式化后的存档时间
+    pub fn formatted_time(&self) -> String {
+        format_timestamp(self.saved_at)
+    }
+}
+
+/// 跨存档保存的数据（已读文本等），与存档槽无关
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersistentData {
+    /// 已读过的对话行（见 `script::line_key`）
+    #[serde(default)]
+    pub seen_lines: BTreeSet<String>,
+}
+
+/// 等待进入游戏后应用的存档（从标题界面或游戏内读档时插入）
+#[derive(Resource)]
+pub struct PendingLoad(pub SaveData);
+
+#[derive(Debug, Error)]
+pub enum SaveError {
+    #[error("存档槽 {0} 不存在")]
+    InvalidSlot(usize),
+    #[error("读写存档文件失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("存档格式错误: {0}")]
+    Format(#[from] serde_yaml::Error),
+}
+
+/// 存档目录，与 assets 同级
+pub fn save_dir() -> PathBuf {
+    let exe_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    exe_dir.join("saves")
+}
+
+fn slot_path(slot: usize) -> PathBuf {
+    save_dir().join(format!("slot_{}.yaml", slot))
+}
+
+/// 写入存档槽（槽位从 1 开始）
+pub fn write_slot(slot: usize, data: &SaveData) -> Result<(), SaveError> {
+    if slot == 0 || slot > SAVE_SLOT_COUNT {
+        return Err(SaveError::InvalidSlot(slot));
+    }
+    fs::create_dir_all(save_dir())?;
+    let yaml = serde_yaml::to_string(data)?;
+    fs::write(slot_path(slot), yaml)?;
+    Ok(())
+}
+
+/// 读取存档槽，空槽返回 `Ok(None)`
+pub fn read_slot(slot: usize) -> Result<Option<SaveData>, SaveError> {
+    if slot == 0 || slot > SAVE_SLOT_COUNT {
+        return Err(SaveError::InvalidSlot(slot));
+    }
+    let path = slot_path(slot);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let yaml = fs::read_to_string(path)?;
+    Ok(Some(serde_yaml::from_str(&yaml)?))
+}
+
+/// 列出所有存档槽，损坏的存档按空槽处理
+pub fn list_slots() -> Vec<(usize, Option<SaveData>)> {
+    (1..=SAVE_SLOT_COUNT)
+        .map(|slot| match read_slot(slot) {
+            Ok(data) => (slot, data),
+            Err(e) => {
+                eprintln!("读取存档槽 {} 失败: {}", slot, e);
+                (slot, None)
+            }
+        })
+        .collect()
+}
+
+fn persistent_path() -> PathBuf {
+    save_dir().join("persistent.yaml")
+}
+
+/// 读取持久化数据，文件不存在或损坏时返回空数据
+pub fn read_persistent() -> PersistentData {
+    let path = persistent_path();
+    if !path.exists() {
+        return PersistentData::default();
+    }
+    let result = fs::read_to_string(&path)
+        .map_err(SaveError::from)
+        .and_then(|yaml| serde_yaml::from_str(&yaml).map_err(SaveError::from));
+    match result {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("读取持久化数据失败: {}", e);
+            PersistentData::default()
+        }
+    }
+}
+
+/// 写入持久化数据
+pub fn write_persistent(data: &PersistentData) -> Result<(), SaveError> {
+    fs::create_dir_all(save_dir())?;
+    let yaml = serde_yaml::to_string(data)?;
+    fs::write(persistent_path(), yaml)?;
+    Ok(())
+}
+
+/// 当前时间（UNIX 秒）
+pub fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 把 UNIX 秒格式化为本地时间 "2025/07/27 14:30"
+pub fn format_timestamp(secs: u64) -> String {
+    DateTime::from_timestamp(secs as i64, 0)
+        .map(|time| time.with_timezone(&Local).format("%Y/%m/%d %H:%M").to_string())
+        .unwrap_or_default()
+}
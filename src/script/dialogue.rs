@@ -0,0 +1,366 @@
+// src/script/dialogue.rs
+// 对话脚本的数据结构（dialogues.yaml 中的每一行）
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::VariableValue, effect::CameraEffect, transition::Transition};
+
+#[derive(Debug, Deserialize)]
+pub struct Choice {
+    pub text: String,
+    pub goto: ChoiceTarget,
+
+    // 显示条件，例如 `if: "affection_points >= 3"`
+    #[serde(default, rename = "if")]
+    pub condition: Option<String>,
+
+    // 选择后执行的赋值
+    #[serde(default)]
+    pub set: Option<HashMap<String, VariableValue>>,
+}
+
+/// 选项的跳转目标：标签名，或（兼容旧脚本的）行号
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ChoiceTarget {
+    Line(usize),
+    Label(String),
+}
+
+impl ChoiceTarget {
+    /// 解析为行号；纯数字的字符串也按行号处理
+    pub fn resolve(&self, labels: &HashMap<String, usize>) -> Option<usize> {
+        match self {
+            ChoiceTarget::Line(line) => Some(*line),
+            ChoiceTarget::Label(label) => labels
+                .get(label)
+                .copied()
+                .or_else(|| label.parse().ok()),
+        }
+    }
+}
+
+impl std::fmt::Display for ChoiceTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChoiceTarget::Line(line) => write!(f, "{}", line),
+            ChoiceTarget::Label(label) => write!(f, "{}", label),
+        }
+    }
+}
+
+/// 跳转：可以直接写标签，也可以带条件 `jump: {label: good_end, if: "lamp_on"}`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Jump {
+    Label(String),
+    Conditional {
+        label: String,
+        #[serde(default, rename = "if")]
+        condition: Option<String>,
+    },
+}
+
+impl Jump {
+    pub fn label(&self) -> &str {
+        match self {
+            Jump::Label(label) => label,
+            Jump::Conditional { label, .. } => label,
+        }
+    }
+
+    pub fn label_mut(&mut self) -> &mut String {
+        match self {
+            Jump::Label(label) => label,
+            Jump::Conditional { label, .. } => label,
+        }
+    }
+
+    pub fn condition(&self) -> Option<&str> {
+        match self {
+            Jump::Label(_) => None,
+            Jump::Conditional { condition, .. } => condition.as_deref(),
+        }
+    }
+}
+
+/// 角色在画面上的位置：`left` / `center` 等名称，或画面宽度的比例（0.0 为左边缘，1.0 为右边缘）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StagePosition {
+    X(f32),
+    Named(String),
+}
+
+impl Default for StagePosition {
+    fn default() -> Self {
+        StagePosition::Named("center".to_string())
+    }
+}
+
+impl StagePosition {
+    /// 预设位置的名称
+    pub const NAMES: [&'static str; 5] = ["farleft", "left", "center", "right", "farright"];
+
+    /// 换算为画面宽度的比例；未知的位置名称返回 None
+    pub fn x_fraction(&self) -> Option<f32> {
+        match self {
+            StagePosition::X(x) => Some(*x),
+            StagePosition::Named(name) => match name.as_str() {
+                "farleft" => Some(0.1),
+                "left" => Some(0.25),
+                "center" => Some(0.5),
+                "right" => Some(0.75),
+                "farright" => Some(0.9),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for StagePosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StagePosition::X(x) => write!(f, "{}", x),
+            StagePosition::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// 显示角色：`show: [{char: heroine, at: left}, {char: villain:angry, at: right, z: 1}]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShowCharacter {
+    /// assets.characters 中的键名
+    #[serde(rename = "char")]
+    pub character: String,
+
+    // 表情（也可以写成 `char: heroine:smile`）；省略时沿用当前表情
+    #[serde(default)]
+    pub expression: Option<String>,
+
+    // 省略时沿用角色当前的位置，新登场的角色默认居中
+    #[serde(default)]
+    pub at: Option<StagePosition>,
+
+    // 越大越靠前；省略时后登场的角色在前
+    #[serde(default)]
+    pub z: Option<i32>,
+
+    // 登场 / 更新时播放的变换动画：main.yaml transforms 中的名称，或直接写出，例如 `slide in from left 0.6 ease_out_cubic`
+    #[serde(default)]
+    pub transform: Option<String>,
+}
+
+/// 一个音效：直接写 assets.audio.sfx 中的键名，或 `{sound: thunder, delay: 0.5, volume: 0.8}`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SfxCue {
+    Sound(String),
+    Detailed {
+        sound: String,
+        // 显示这一行后延迟多少秒播放
+        #[serde(default)]
+        delay: f32,
+        // 0.0 ~ 1.0
+        #[serde(default = "default_volume")]
+        volume: f32,
+    },
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+impl SfxCue {
+    pub fn sound(&self) -> &str {
+        match self {
+            SfxCue::Sound(sound) => sound,
+            SfxCue::Detailed { sound, .. } => sound,
+        }
+    }
+
+    pub fn sound_mut(&mut self) -> &mut String {
+        match self {
+            SfxCue::Sound(sound) => sound,
+            SfxCue::Detailed { sound, .. } => sound,
+        }
+    }
+
+    pub fn delay(&self) -> f32 {
+        match self {
+            SfxCue::Sound(_) => 0.0,
+            SfxCue::Detailed { delay, .. } => *delay,
+        }
+    }
+
+    pub fn volume(&self) -> f32 {
+        match self {
+            SfxCue::Sound(_) => 1.0,
+            SfxCue::Detailed { volume, .. } => *volume,
+        }
+    }
+}
+
+/// `sfx:` 可以写一个音效，也可以写一个列表
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SfxList {
+    One(SfxCue),
+    Many(Vec<SfxCue>),
+}
+
+impl SfxList {
+    pub fn cues(&self) -> &[SfxCue] {
+        match self {
+            SfxList::One(cue) => std::slice::from_ref(cue),
+            SfxList::Many(cues) => cues,
+        }
+    }
+
+    pub fn cues_mut(&mut self) -> &mut [SfxCue] {
+        match self {
+            SfxList::One(cue) => std::slice::from_mut(cue),
+            SfxList::Many(cues) => cues,
+        }
+    }
+}
+
+/// `effect:` 可以写一个镜头效果，也可以写一个列表
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum EffectList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl EffectList {
+    pub fn sources(&self) -> &[String] {
+        match self {
+            EffectList::One(source) => std::slice::from_ref(source),
+            EffectList::Many(sources) => sources,
+        }
+    }
+}
+
+/// `bgm: stop`：停止当前的 BGM
+pub const BGM_STOP: &str = "stop";
+
+/// 一行对 BGM 的操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BgmChange<'a> {
+    /// 没有写 bgm，继续播放当前的 BGM
+    Keep,
+    Stop,
+    Play(&'a str),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Dialogue {
+    pub character: String,
+    pub text: String,
+    pub portrait: String,
+
+    // 立绘的表情（也可以写成 `portrait: heroine:smile`），省略时为 default
+    #[serde(default)]
+    pub expression: Option<String>,
+
+    // 从这一行开始播放的 BGM，省略时继续播放当前的 BGM，`stop` 为停止
+    // （旧脚本中的 `audio` 与 bgm 相同）
+    #[serde(default, alias = "audio")]
+    pub bgm: Option<String>,
+
+    // 切换 / 停止 BGM 的淡出淡入秒数，省略时使用 settings.bgm_fade
+    #[serde(default)]
+    pub bgm_fade: Option<f32>,
+
+    #[serde(default)]
+    pub background: Option<String>,
+
+    // 这一行切换背景、角色登场 / 退场时的转场，例如 `dissolve 0.8`、`wipe left`、`none`
+    // 省略时背景使用 dissolve，角色直接显示
+    #[serde(default)]
+    pub transition: Option<String>,
+
+    // 进入这一行时的镜头效果，例如 `shake 0.4 strength 12`、`zoom 1.3 to [120, -40] over 2s`、`pan left 3s`、`flash white`
+    #[serde(default)]
+    pub effect: Option<EffectList>,
+
+    // 这一行说话角色头像的变换动画：main.yaml transforms 中的名称，或直接写出，例如 `move by [0, 20] 0.15; move by [0, -20] 0.15`
+    #[serde(default)]
+    pub transform: Option<String>,
+
+    // 显示这一行时播放的音效
+    #[serde(default)]
+    pub sfx: Option<SfxList>,
+
+    // 这一行的语音：相对 assets 的路径，角色设置了语音标签时也可以只写文件名
+    #[serde(default)]
+    pub voice: Option<String>,
+
+    #[serde(default)]
+    pub swf: Option<String>,
+
+    // 登场的角色，保持显示直到被 hide
+    #[serde(default)]
+    pub show: Option<Vec<ShowCharacter>>,
+
+    // 退场的角色，`all` 表示全部
+    #[serde(default)]
+    pub hide: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub label: Option<String>,
+
+    #[serde(default)]
+    pub jump: Option<Jump>,
+
+    #[serde(default)]
+    pub choices: Option<Vec<Choice>>,
+
+    #[serde(default)]
+    pub pause: Option<bool>,
+
+    // 条件不成立时跳过这一行
+    #[serde(default, rename = "if")]
+    pub condition: Option<String>,
+
+    // 进入这一行时执行的赋值，例如 `set: {affection_points: "+1"}`
+    #[serde(default)]
+    pub set: Option<HashMap<String, VariableValue>>,
+
+    // 无法识别的键（拼写错误或不支持的写法），运行时忽略，由检查工具提示
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, serde_yaml::Value>,
+}
+
+impl Dialogue {
+    pub fn bgm_change(&self) -> BgmChange<'_> {
+        match self.bgm.as_deref() {
+            None => BgmChange::Keep,
+            Some(BGM_STOP) => BgmChange::Stop,
+            Some(bgm) => BgmChange::Play(bgm),
+        }
+    }
+
+    /// 这一行切换背景的转场，省略时为 dissolve
+    pub fn background_transition(&self) -> Transition {
+        self.transition
+            .as_deref()
+            .map_or_else(Transition::default, Transition::parse_or_default)
+    }
+
+    /// 这一行角色登场 / 退场的转场，省略时直接显示
+    pub fn stage_transition(&self) -> Option<Transition> {
+        self.transition.as_deref().map(Transition::parse_or_default)
+    }
+
+    /// 这一行的镜头效果，写法无效的会被忽略
+    pub fn camera_effects(&self) -> Vec<CameraEffect> {
+        self.effect
+            .iter()
+            .flat_map(|effect| effect.sources())
+            .filter_map(|source| CameraEffect::parse_or_warn(source))
+            .collect()
+    }
+}
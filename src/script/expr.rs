@@ -0,0 +1,442 @@
+// src/script/expr.rs
+// 对话脚本中 `if:` 条件与 `set:` 表达式的解析和求值
+use std::collections::HashMap;
+
+use crate::config::VariableValue;
+
+use super::ScriptError;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+/// 解析后的表达式
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Literal(VariableValue),
+    Variable(String),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+}
+
+// 按长度排列，保证先匹配双字符运算符
+const OPERATORS: [&str; 15] = [
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "+", "-", "*", "/", "%", "=",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, ScriptError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::LParen);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::RParen);
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = text
+                .parse()
+                .map_err(|_| ScriptError::Syntax(format!("无效的数字 '{}'", text)))?;
+            tokens.push(Token::Number(number));
+        } else if c == '"' || c == '\'' {
+            let start = i + 1;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(ScriptError::Syntax("字符串缺少结束引号".to_string()));
+            }
+            tokens.push(Token::Str(chars[start..i].iter().collect()));
+            i += 1;
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| ScriptError::Syntax(format!("无法识别的字符 '{}'", c)))?;
+            // 单个 '=' 视为 '=='，方便书写
+            tokens.push(Token::Op(if *op == "=" { "==" } else { op }));
+            i += op.chars().count();
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // 匹配运算符（支持 and/or/not 关键字写法）
+    fn eat_op(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+        let found = match self.peek() {
+            Some(Token::Op(op)) => ops.iter().find(|o| *o == op).copied(),
+            Some(Token::Ident(word)) => {
+                let op = match word.as_str() {
+                    "and" => "&&",
+                    "or" => "||",
+                    "not" => "!",
+                    _ => return None,
+                };
+                ops.contains(&op).then_some(op)
+            }
+            _ => None,
+        };
+        if found.is_some() {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ScriptError> {
+        let mut left = self.parse_and()?;
+        while let Some(op) = self.eat_op(&["||"]) {
+            let right = self.parse_and()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ScriptError> {
+        let mut left = self.parse_not()?;
+        while let Some(op) = self.eat_op(&["&&"]) {
+            let right = self.parse_not()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, ScriptError> {
+        if self.eat_op(&["!"]).is_some() {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, ScriptError> {
+        let left = self.parse_additive()?;
+        if let Some(op) = self.eat_op(&["==", "!=", "<", "<=", ">", ">="]) {
+            let right = self.parse_additive()?;
+            return Ok(Expr::Binary(op, Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, ScriptError> {
+        let mut left = self.parse_multiplicative()?;
+        while let Some(op) = self.eat_op(&["+", "-"]) {
+            let right = self.parse_multiplicative()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr, ScriptError> {
+        let mut left = self.parse_unary()?;
+        while let Some(op) = self.eat_op(&["*", "/", "%"]) {
+            let right = self.parse_unary()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ScriptError> {
+        if self.eat_op(&["-"]).is_some() {
+            return Ok(Expr::Neg(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ScriptError> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(VariableValue::Number(n))),
+            Some(Token::Str(s)) => Ok(Expr::Literal(VariableValue::String(s))),
+            Some(Token::Ident(word)) => Ok(match word.as_str() {
+                "true" => Expr::Literal(VariableValue::Boolean(true)),
+                "false" => Expr::Literal(VariableValue::Boolean(false)),
+                "null" | "none" => Expr::Literal(VariableValue::Null),
+                _ => Expr::Variable(word),
+            }),
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => Err(ScriptError::Syntax("缺少右括号 ')'".to_string())),
+                }
+            }
+            Some(token) => Err(ScriptError::Syntax(format!("意外的符号 {:?}", token))),
+            None => Err(ScriptError::Syntax("表达式不完整".to_string())),
+        }
+    }
+}
+
+/// 解析表达式，例如 `affection_points >= 3 and lamp_on`
+pub fn parse_expression(source: &str) -> Result<Expr, ScriptError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+    };
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        return Err(ScriptError::Syntax(format!("多余的符号 {:?}", token)));
+    }
+    Ok(expr)
+}
+
+/// 值的真假判断：null、false、0、空字符串和空数组为假
+pub fn is_truthy(value: &VariableValue) -> bool {
+    match value {
+        VariableValue::Array(a) => !a.is_empty(),
+        VariableValue::Null => false,
+        other => other.as_bool().unwrap_or(false),
+    }
+}
+
+fn values_equal(left: &VariableValue, right: &VariableValue) -> bool {
+    match (left, right) {
+        (VariableValue::Null, VariableValue::Null) => true,
+        (VariableValue::Null, _) | (_, VariableValue::Null) => false,
+        (VariableValue::String(a), VariableValue::String(b)) => a == b,
+        (VariableValue::Boolean(a), VariableValue::Boolean(b)) => a == b,
+        _ => match (left.as_number(), right.as_number()) {
+            (Some(a), Some(b)) => a == b,
+            _ => left.as_string() == right.as_string(),
+        },
+    }
+}
+
+fn number_operand(value: &VariableValue, op: &str) -> Result<f64, ScriptError> {
+    value
+        .as_number()
+        .ok_or_else(|| ScriptError::Type(format!("运算符 '{}' 需要数字，得到 {:?}", op, value)))
+}
+
+impl Expr {
+    /// 对表达式求值，未定义的变量视为 null
+    pub fn evaluate(
+        &self,
+        variables: &HashMap<String, VariableValue>,
+    ) -> Result<VariableValue, ScriptError> {
+        match self {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Variable(name) => Ok(variables.get(name).cloned().unwrap_or_default()),
+            Expr::Not(inner) => Ok(VariableValue::Boolean(!is_truthy(&inner.evaluate(variables)?))),
+            Expr::Neg(inner) => Ok(VariableValue::Number(-number_operand(
+                &inner.evaluate(variables)?,
+                "-",
+            )?)),
+            Expr::Binary(op, left, right) => {
+                // 逻辑运算短路求值
+                if *op == "&&" || *op == "||" {
+                    let left = is_truthy(&left.evaluate(variables)?);
+                    if (*op == "&&" && !left) || (*op == "||" && left) {
+                        return Ok(VariableValue::Boolean(left));
+                    }
+                    return Ok(VariableValue::Boolean(is_truthy(&right.evaluate(variables)?)));
+                }
+
+                let left = left.evaluate(variables)?;
+                let right = right.evaluate(variables)?;
+                match *op {
+                    "==" => Ok(VariableValue::Boolean(values_equal(&left, &right))),
+                    "!=" => Ok(VariableValue::Boolean(!values_equal(&left, &right))),
+                    "<" | "<=" | ">" | ">=" => {
+                        let ordering = match (&left, &right) {
+                            (VariableValue::String(a), VariableValue::String(b)) => a.partial_cmp(b),
+                            _ => number_operand(&left, op)?.partial_cmp(&number_operand(&right, op)?),
+                        };
+                        let result = match ordering {
+                            Some(ordering) => match *op {
+                                "<" => ordering.is_lt(),
+                                "<=" => ordering.is_le(),
+                                ">" => ordering.is_gt(),
+                                _ => ordering.is_ge(),
+                            },
+                            None => false,
+                        };
+                        Ok(VariableValue::Boolean(result))
+                    }
+                    "+" => match (&left, &right) {
+                        // 任一侧是字符串时做拼接
+                        (VariableValue::String(_), _) | (_, VariableValue::String(_)) => Ok(
+                            VariableValue::String(format!(
+                                "{}{}",
+                                left.as_string().unwrap_or_default(),
+                                right.as_string().unwrap_or_default()
+                            )),
+                        ),
+                        _ => Ok(VariableValue::Number(
+                            number_operand(&left, op)? + number_operand(&right, op)?,
+                        )),
+                    },
+                    _ => {
+                        let a = number_operand(&left, op)?;
+                        let b = number_operand(&right, op)?;
+                        match *op {
+                            "-" => Ok(VariableValue::Number(a - b)),
+                            "*" => Ok(VariableValue::Number(a * b)),
+                            "/" | "%" if b == 0.0 => {
+                                Err(ScriptError::Type("除数不能为 0".to_string()))
+                            }
+                            "/" => Ok(VariableValue::Number(a / b)),
+                            _ => Ok(VariableValue::Number(a % b)),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::apply_set;
+
+    fn vars(pairs: &[(&str, VariableValue)]) -> HashMap<String, VariableValue> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    fn eval(source: &str, variables: &HashMap<String, VariableValue>) -> VariableValue {
+        parse_expression(source).unwrap().evaluate(variables).unwrap()
+    }
+
+    fn number(source: &str) -> f64 {
+        eval(source, &HashMap::new()).as_number().unwrap()
+    }
+
+    fn truthy(source: &str, variables: &HashMap<String, VariableValue>) -> bool {
+        is_truthy(&eval(source, variables))
+    }
+
+    #[test]
+    fn arithmetic_precedence() {
+        assert_eq!(number("1 + 2 * 3"), 7.0);
+        assert_eq!(number("(1 + 2) * 3"), 9.0);
+        assert_eq!(number("10 - 4 - 3"), 3.0);
+        assert_eq!(number("-2 * 3 + 10 % 4"), -4.0);
+    }
+
+    #[test]
+    fn logic_operators() {
+        let variables = vars(&[
+            ("lamp_on", VariableValue::Boolean(true)),
+            ("affection_points", VariableValue::Number(3.0)),
+        ]);
+        assert!(truthy("affection_points >= 3 and lamp_on", &variables));
+        assert!(truthy("affection_points > 5 || lamp_on", &variables));
+        assert!(!truthy("affection_points > 5 && lamp_on", &variables));
+        assert!(truthy("not (affection_points > 5)", &variables));
+        // && 比 || 优先
+        assert!(truthy("true || false && false", &variables));
+        assert!(!truthy("(true || false) && false", &variables));
+    }
+
+    #[test]
+    fn short_circuit_skips_errors() {
+        // 右侧会除以 0，短路时不求值
+        assert!(!truthy("false && 1 / 0 > 0", &HashMap::new()));
+        assert!(truthy("true || 1 / 0 > 0", &HashMap::new()));
+    }
+
+    #[test]
+    fn comparisons() {
+        let variables = vars(&[("name", VariableValue::String("艾拉".to_string()))]);
+        assert!(truthy("name == '艾拉'", &variables));
+        assert!(truthy("name = \"艾拉\"", &variables));
+        assert!(truthy("2 <= 2 and 3 != 4", &variables));
+        assert!(truthy("'a' < 'b'", &variables));
+    }
+
+    #[test]
+    fn missing_variables_are_null() {
+        let variables = HashMap::new();
+        assert!(!truthy("missing", &variables));
+        assert!(truthy("!missing", &variables));
+        assert!(!truthy("missing == 0", &variables));
+        assert!(truthy("missing != 0", &variables));
+        assert!(truthy("missing == other_missing", &variables));
+        // 大小比较需要数字
+        let error = parse_expression("missing > 1").unwrap().evaluate(&variables);
+        assert!(matches!(error, Err(ScriptError::Type(_))));
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        assert!(parse_expression("1 +").is_err());
+        assert!(parse_expression("(1 + 2").is_err());
+        assert!(parse_expression("1 2").is_err());
+        assert!(parse_expression("'unterminated").is_err());
+        assert!(parse_expression("a # b").is_err());
+        let error = parse_expression("1 / 0").unwrap().evaluate(&HashMap::new());
+        assert!(matches!(error, Err(ScriptError::Type(_))));
+    }
+
+    #[test]
+    fn apply_set_relative_and_expression() {
+        let mut variables = vars(&[("affection_points", VariableValue::Number(2.0))]);
+        let set = |variables: &mut HashMap<String, VariableValue>, name: &str, value: &str| {
+            apply_set(variables, name, &VariableValue::String(value.to_string())).unwrap();
+        };
+
+        set(&mut variables, "affection_points", "+1");
+        assert_eq!(variables["affection_points"].as_number(), Some(3.0));
+        set(&mut variables, "affection_points", "-0.5");
+        assert_eq!(variables["affection_points"].as_number(), Some(2.5));
+        // 没有定义的变量从 0 开始
+        set(&mut variables, "visits", "+1");
+        assert_eq!(variables["visits"].as_number(), Some(1.0));
+
+        set(&mut variables, "double", "= affection_points * 2");
+        assert_eq!(variables["double"].as_number(), Some(5.0));
+        set(&mut variables, "greeting", "hello");
+        assert_eq!(variables["greeting"].as_string().as_deref(), Some("hello"));
+
+        let error = apply_set(
+            &mut variables,
+            "broken",
+            &VariableValue::String("= 1 +".to_string()),
+        );
+        assert!(error.is_err());
+    }
+}
@@ -0,0 +1,129 @@
+// src/script/interpolate.rs
+// 对话文本插值：显示时把 `{player_name}` 这类占位符替换为变量的当前值
+use std::collections::HashMap;
+
+use crate::config::VariableValue;
+
+/// 替换文本中的变量占位符
+///
+/// - `{name}` 替换为变量的值，`{name:.2}` 保留两位小数
+/// - `{{` / `}}` 输出字面的花括号
+/// - 未定义的变量以及其他花括号内容原样保留
+pub fn interpolate(text: &str, variables: &HashMap<String, VariableValue>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(index) = rest.find(['{', '}']) {
+        result.push_str(&rest[..index]);
+        let tail = &rest[index..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            result.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if tail.starts_with('}') {
+            result.push('}');
+            rest = &tail[1..];
+            continue;
+        }
+
+        let Some(end) = tail.find('}') else {
+            result.push_str(tail);
+            return result;
+        };
+        let placeholder = &tail[1..end];
+        match format_placeholder(placeholder, variables) {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&tail[..=end]),
+        }
+        rest = &tail[end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// 解析 `name` 或 `name:.2`，返回格式化后的值；不是已定义的变量时返回 None
+pub fn format_placeholder(
+    placeholder: &str,
+    variables: &HashMap<String, VariableValue>,
+) -> Option<String> {
+    let (name, spec) = match placeholder.split_once(':') {
+        Some((name, spec)) => (name.trim(), Some(spec.trim())),
+        None => (placeholder.trim(), None),
+    };
+    let value = variables.get(name)?;
+    let precision = match spec {
+        Some(spec) => Some(spec.strip_prefix('.')?.parse().ok()?),
+        None => None,
+    };
+    Some(format_value(value, precision))
+}
+
+/// 变量值的显示文本：整数不带小数点，数组用顿号连接，null 为空
+pub fn format_value(value: &VariableValue, precision: Option<usize>) -> String {
+    match value {
+        VariableValue::Number(n) => match precision {
+            Some(precision) => format!("{:.*}", precision, n),
+            None if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", *n as i64),
+            None => n.to_string(),
+        },
+        VariableValue::Boolean(b) => b.to_string(),
+        VariableValue::String(s) => s.clone(),
+        VariableValue::Array(items) => items
+            .iter()
+            .map(|item| format_value(item, precision))
+            .collect::<Vec<_>>()
+            .join("、"),
+        VariableValue::Null => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> HashMap<String, VariableValue> {
+        HashMap::from([
+            ("name".to_string(), VariableValue::String("艾拉".to_string())),
+            ("points".to_string(), VariableValue::Number(3.0)),
+            ("ratio".to_string(), VariableValue::Number(0.125)),
+            ("met".to_string(), VariableValue::Boolean(true)),
+            (
+                "items".to_string(),
+                VariableValue::Array(vec![
+                    VariableValue::String("钥匙".to_string()),
+                    VariableValue::Number(2.0),
+                ]),
+            ),
+            ("nothing".to_string(), VariableValue::Null),
+        ])
+    }
+
+    #[test]
+    fn replaces_defined_variables() {
+        assert_eq!(
+            interpolate("{name} 有 {points} 分，{ met }", &variables()),
+            "艾拉 有 3 分，true"
+        );
+        assert_eq!(interpolate("物品：{items}", &variables()), "物品：钥匙、2");
+        assert_eq!(interpolate("[{nothing}]", &variables()), "[]");
+    }
+
+    #[test]
+    fn precision() {
+        assert_eq!(interpolate("{ratio}", &variables()), "0.125");
+        assert_eq!(interpolate("{ratio:.2}", &variables()), "0.12");
+        assert_eq!(interpolate("{points:.1}", &variables()), "3.0");
+        // 精度写错时原样保留
+        assert_eq!(interpolate("{points:2}", &variables()), "{points:2}");
+    }
+
+    #[test]
+    fn braces_and_unknown_placeholders() {
+        assert_eq!(interpolate("{{name}}", &variables()), "{name}");
+        assert_eq!(interpolate("{missing} }", &variables()), "{missing} }");
+        assert_eq!(interpolate("未闭合 {name", &variables()), "未闭合 {name");
+    }
+}
@@ -0,0 +1,473 @@
+// src/script/loader.rs
+// 加载对话脚本：按 main.yaml 的 script 清单依次读取，支持 include 和按文件划分的标签命名空间
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::config::MainConfig;
+
+use super::dialogue::{ChoiceTarget, Dialogue};
+
+/// 命名空间与标签之间的分隔符，例如 `chapter2::start`
+pub const NAMESPACE_SEPARATOR: &str = "::";
+
+/// 角色与表情之间的分隔符，例如 `heroine:smile`
+pub const EXPRESSION_SEPARATOR: char = ':';
+
+/// 未配置 script 清单时使用的脚本文件
+pub const DEFAULT_SCRIPT_FILE: &str = "dialogues.yaml";
+
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("无法读取脚本文件 {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("脚本文件 {path:?} 第 {line} 行格式错误: {message}")]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    #[error("脚本文件 {0:?} 被循环 include")]
+    IncludeCycle(PathBuf),
+}
+
+/// 每一行对话来自哪个文件
+#[derive(Debug, Clone)]
+pub struct LineOrigin {
+    pub file: PathBuf,
+    /// 在该文件中的行号（从 1 开始）
+    pub line: usize,
+}
+
+/// 重复定义的标签（保留第一次定义）
+#[derive(Debug, Clone)]
+pub struct DuplicateLabel {
+    pub label: String,
+    pub first: usize,
+    pub duplicate: usize,
+}
+
+/// 合并后的完整脚本
+#[derive(Debug, Default)]
+pub struct Script {
+    pub dialogues: Vec<Dialogue>,
+    /// 带命名空间的标签 -> 行号
+    pub labels: HashMap<String, usize>,
+    /// 与 dialogues 一一对应
+    pub origins: Vec<LineOrigin>,
+    pub duplicate_labels: Vec<DuplicateLabel>,
+}
+
+impl Script {
+    /// 描述某一行的位置，例如 `chapter2.yaml:31`
+    pub fn describe_line(&self, line: usize) -> String {
+        match self.origins.get(line) {
+            Some(origin) => format!(
+                "{}:{}",
+                origin
+                    .file
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                origin.line
+            ),
+            None => format!("第 {} 行", line),
+        }
+    }
+}
+
+/// main.yaml 中列出的脚本文件（未配置时为 dialogues.yaml）
+pub fn script_files(config: &MainConfig) -> Vec<String> {
+    if config.script.is_empty() {
+        vec![DEFAULT_SCRIPT_FILE.to_string()]
+    } else {
+        config.script.clone()
+    }
+}
+
+/// 给标签加上命名空间；已经带命名空间的标签保持不变
+pub fn qualify_label(namespace: &str, label: &str) -> String {
+    if label.contains(NAMESPACE_SEPARATOR) {
+        label.to_string()
+    } else {
+        format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, label)
+    }
+}
+
+/// 文件的命名空间：文件名去掉扩展名，例如 `chapters/chapter2.yaml` -> `chapter2`
+pub fn file_namespace(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// 加载 assets 目录下的全部脚本文件，合并为一份脚本
+pub fn load_script(config: &MainConfig, assets_dir: &Path) -> Result<Script, LoadError> {
+    let mut script = Script::default();
+    let mut include_stack = Vec::new();
+    for file in script_files(config) {
+        load_file(
+            &assets_dir.join(file),
+            config,
+            &mut script,
+            &mut include_stack,
+        )?;
+    }
+    Ok(script)
+}
+
+fn load_file(
+    path: &Path,
+    config: &MainConfig,
+    script: &mut Script,
+    include_stack: &mut Vec<PathBuf>,
+) -> Result<(), LoadError> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if include_stack.contains(&canonical) {
+        return Err(LoadError::IncludeCycle(path.to_path_buf()));
+    }
+
+    println!("加载脚本文件: {:?}", path);
+    let yaml_str = fs::read_to_string(path).map_err(|source| LoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let items: Vec<serde_yaml::Value> =
+        serde_yaml::from_str(&yaml_str).map_err(|e| LoadError::Parse {
+            path: path.to_path_buf(),
+            line: e.location().map(|location| location.line()).unwrap_or(1),
+            message: e.to_string(),
+        })?;
+    let item_lines = item_lines(&yaml_str);
+
+    include_stack.push(canonical);
+    let namespace = file_namespace(path);
+    // 本文件中第 N 行对话在合并后的行号，用于换算旧脚本里的数字 goto
+    let mut local_lines = Vec::new();
+
+    for (index, item) in items.into_iter().enumerate() {
+        let source_line = item_lines.get(index).copied().unwrap_or(1);
+        if let Some(include) = item.get("include").and_then(|value| value.as_str()) {
+            let include_path = path
+                .parent()
+                .map(|dir| dir.join(include))
+                .unwrap_or_else(|| PathBuf::from(include));
+            load_file(&include_path, config, script, include_stack)?;
+            continue;
+        }
+
+        let mut dialogue: Dialogue =
+            serde_yaml::from_value(item).map_err(|e| LoadError::Parse {
+                path: path.to_path_buf(),
+                line: source_line,
+                message: e.to_string(),
+            })?;
+        resolve_references(&mut dialogue, config);
+
+        let line = script.dialogues.len();
+        if let Some(label) = dialogue.label.as_mut() {
+            *label = qualify_label(&namespace, label);
+            match script.labels.get(label.as_str()) {
+                Some(&first) => script.duplicate_labels.push(DuplicateLabel {
+                    label: label.clone(),
+                    first,
+                    duplicate: line,
+                }),
+                None => {
+                    script.labels.insert(label.clone(), line);
+                }
+            }
+        }
+        if let Some(jump) = dialogue.jump.as_mut() {
+            let qualified = qualify_label(&namespace, jump.label());
+            *jump.label_mut() = qualified;
+        }
+        for choice in dialogue.choices.iter_mut().flatten() {
+            if let ChoiceTarget::Label(label) = &choice.goto {
+                if label.parse::<usize>().is_err() {
+                    choice.goto = ChoiceTarget::Label(qualify_label(&namespace, label));
+                }
+            }
+        }
+
+        local_lines.push(line);
+        script.dialogues.push(dialogue);
+        script.origins.push(LineOrigin {
+            file: path.to_path_buf(),
+            line: source_line,
+        });
+    }
+
+    // 数字 goto 按本文件内的行号计算；超出本文件时报错，否则合并后会跳到别的文件
+    for &line in &local_lines {
+        for choice in script.dialogues[line].choices.iter_mut().flatten() {
+            let local = match &choice.goto {
+                ChoiceTarget::Line(local) => Some(*local),
+                ChoiceTarget::Label(label) => label.parse().ok(),
+            };
+            let Some(local) = local else {
+                continue;
+            };
+            match local_lines.get(local) {
+                Some(&global) => choice.goto = ChoiceTarget::Line(global),
+                None => {
+                    return Err(LoadError::Parse {
+                        path: path.to_path_buf(),
+                        line: script.origins[line].line,
+                        message: format!(
+                            "选项的 goto {} 超出本文件的对话行数（共 {} 行）",
+                            local,
+                            local_lines.len()
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    include_stack.pop();
+    Ok(())
+}
+
+// 顶层列表中每一项在文件中的起始行号（从 1 开始）
+//
+// 以第一个 `- ` 的缩进作为顶层缩进，更深的缩进属于某一项的内部内容
+fn item_lines(yaml_str: &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let mut top_indent = None;
+    for (number, line) in yaml_str.lines().enumerate() {
+        let content = line.trim_start();
+        if !(content == "-" || content.starts_with("- ")) {
+            continue;
+        }
+        let indent = line.len() - content.len();
+        if *top_indent.get_or_insert(indent) == indent {
+            lines.push(number + 1);
+        }
+    }
+    lines
+}
+
+// `heroine:smile` 拆分为角色和表情；单独写的 expression 优先
+fn split_expression(character: &mut String, expression: &mut Option<String>) {
+    if let Some((name, inline)) = character.split_once(EXPRESSION_SEPARATOR) {
+        if expression.is_none() {
+            *expression = Some(inline.to_string());
+        }
+        *character = name.to_string();
+    }
+}
+
+// 资源引用：`$backgrounds.bg1` 这类写法解析为 main.yaml 中的键名；
+// 旧脚本中的 `$title` 替换为游戏标题
+fn resolve_references(dialogue: &mut Dialogue, config: &MainConfig) {
+    fn strip_reference(value: &mut String, prefix: &str) {
+        if let Some(key) = value.strip_prefix(prefix) {
+            *value = key.to_string();
+        }
+    }
+
+    strip_reference(&mut dialogue.portrait, "$characters.");
+    split_expression(&mut dialogue.portrait, &mut dialogue.expression);
+    for show in dialogue.show.iter_mut().flatten() {
+        strip_reference(&mut show.character, "$characters.");
+        split_expression(&mut show.character, &mut show.expression);
+    }
+    for hide in dialogue.hide.iter_mut().flatten() {
+        strip_reference(hide, "$characters.");
+    }
+    if let Some(background) = dialogue.background.as_mut() {
+        strip_reference(background, "$backgrounds.");
+    }
+    if let Some(bgm) = dialogue.bgm.as_mut() {
+        strip_reference(bgm, "$audio.bgm.");
+    }
+    if let Some(swf) = dialogue.swf.as_mut() {
+        strip_reference(swf, "$swf.");
+    }
+    for cue in dialogue.sfx.iter_mut().flat_map(|sfx| sfx.cues_mut()) {
+        strip_reference(cue.sound_mut(), "$audio.sfx.");
+    }
+
+    dialogue.text = dialogue.text.replace("$title", &config.title);
+    for choice in dialogue.choices.iter_mut().flatten() {
+        choice.text = choice.text.replace("$title", &config.title);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROLOGUE: &str = r#"
+- character: "艾拉"
+  text: "开始"
+  portrait: "none"
+  label: "start"
+  jump: "chapter2::start"
+- include: "chapters/chapter2.yaml"
+- character: "艾拉"
+  text: "回来"
+  portrait: "none"
+  choices:
+    - text: "再来"
+      goto: start
+    - text: "第一行"
+      goto: 0
+"#;
+
+    const CHAPTER2: &str = r#"
+- character: "舰长"
+  text: "第二章"
+  portrait: "none"
+  label: "start"
+- character: "舰长"
+  text: "选择"
+  portrait: "none"
+  jump: "prologue::start"
+  choices:
+    - text: "本文件第一行"
+      goto: 0
+    - text: "本章开头"
+      goto: start
+"#;
+
+    // 把脚本写到临时目录，返回 assets 目录
+    fn write_assets(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("raven_loader_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (file, content) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn load(name: &str, files: &[(&str, &str)]) -> Result<Script, LoadError> {
+        let assets_dir = write_assets(name, files);
+        let config = MainConfig {
+            script: vec![files[0].0.to_string()],
+            ..MainConfig::default()
+        };
+        let script = load_script(&config, &assets_dir);
+        let _ = fs::remove_dir_all(&assets_dir);
+        script
+    }
+
+    #[test]
+    fn includes_are_inlined_with_their_own_namespace() {
+        let script = load(
+            "namespaces",
+            &[("prologue.yaml", PROLOGUE), ("chapters/chapter2.yaml", CHAPTER2)],
+        )
+        .unwrap();
+        let texts: Vec<&str> = script.dialogues.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(texts, ["开始", "第二章", "选择", "回来"]);
+        assert_eq!(script.labels["prologue::start"], 0);
+        assert_eq!(script.labels["chapter2::start"], 1);
+        assert_eq!(script.dialogues[0].jump.as_ref().unwrap().label(), "chapter2::start");
+        assert_eq!(script.dialogues[2].jump.as_ref().unwrap().label(), "prologue::start");
+        assert_eq!(script.describe_line(1), "chapter2.yaml:2");
+        assert_eq!(script.describe_line(3), "prologue.yaml:8");
+    }
+
+    #[test]
+    fn choice_targets_are_remapped_per_file() {
+        let script = load(
+            "gotos",
+            &[("prologue.yaml", PROLOGUE), ("chapters/chapter2.yaml", CHAPTER2)],
+        )
+        .unwrap();
+        let choices = |line: usize| script.dialogues[line].choices.as_ref().unwrap();
+        // 数字 goto 按本文件的行号换算为合并后的行号
+        assert!(matches!(choices(2)[0].goto, ChoiceTarget::Line(1)));
+        assert!(matches!(&choices(2)[1].goto, ChoiceTarget::Label(label) if label == "chapter2::start"));
+        assert!(matches!(&choices(3)[0].goto, ChoiceTarget::Label(label) if label == "prologue::start"));
+        assert!(matches!(choices(3)[1].goto, ChoiceTarget::Line(0)));
+    }
+
+    #[test]
+    fn out_of_range_goto_is_reported_in_its_file() {
+        let chapter = r#"
+- character: "舰长"
+  text: "只有一行"
+  portrait: "none"
+  choices:
+    - text: "越界"
+      goto: 3
+"#;
+        let error = load(
+            "out_of_range",
+            &[("prologue.yaml", PROLOGUE), ("chapters/chapter2.yaml", chapter)],
+        )
+        .unwrap_err();
+        match error {
+            LoadError::Parse { path, line, .. } => {
+                assert!(path.ends_with("chapters/chapter2.yaml"));
+                assert_eq!(line, 2);
+            }
+            error => panic!("{}", error),
+        }
+    }
+
+    #[test]
+    fn include_cycles_are_rejected() {
+        let error = load(
+            "cycle",
+            &[
+                ("a.yaml", "- include: \"b.yaml\"\n"),
+                ("b.yaml", "- include: \"a.yaml\"\n"),
+            ],
+        )
+        .unwrap_err();
+        assert!(matches!(error, LoadError::IncludeCycle(path) if path.ends_with("a.yaml")));
+    }
+
+    #[test]
+    fn inline_expressions_and_references() {
+        let script = load(
+            "references",
+            &[(
+                "dialogues.yaml",
+                "- character: \"艾拉\"\n  text: \"$title\"\n  portrait: \"$characters.heroine:smile\"\n  background: \"$backgrounds.bg1\"\n",
+            )],
+        )
+        .unwrap();
+        let dialogue = &script.dialogues[0];
+        assert_eq!(dialogue.portrait, "heroine");
+        assert_eq!(dialogue.expression.as_deref(), Some("smile"));
+        assert_eq!(dialogue.background.as_deref(), Some("bg1"));
+        assert_eq!(dialogue.text, MainConfig::default().title);
+    }
+
+    #[test]
+    fn qualified_labels_are_kept() {
+        assert_eq!(qualify_label("prologue", "start"), "prologue::start");
+        assert_eq!(qualify_label("prologue", "chapter2::start"), "chapter2::start");
+        assert_eq!(file_namespace(Path::new("chapters/chapter2.yaml")), "chapter2");
+    }
+
+    #[test]
+    fn audio_is_bgm_and_unknown_keys_are_kept() {
+        let script = load(
+            "unknown_keys",
+            &[(
+                "main.yaml",
+                "- character: \"艾拉\"\n  text: \"开始\"\n  portrait: \"none\"\n  audio: \"main_theme\"\n  bgm_fade: 2\n  interactive: \"console\"\n",
+            )],
+        )
+        .unwrap();
+        let dialogue = &script.dialogues[0];
+        assert_eq!(dialogue.bgm.as_deref(), Some("main_theme"));
+        assert_eq!(dialogue.bgm_fade, Some(2.0));
+        assert_eq!(dialogue.unknown.keys().collect::<Vec<_>>(), ["interactive"]);
+    }
+}
@@ -0,0 +1,363 @@
+// src/script/markup.rs
+// 对话文本的内联标签：{color=#f00}、{b}、{i}、{size=+4}、{w=0.5}、{cps=10}、{nw}
+use std::collections::HashMap;
+
+use crate::config::VariableValue;
+
+use super::interpolate::format_placeholder;
+
+/// 字号变化：`{size=24}` 为绝对值，`{size=+4}` / `{size=-2}` 为相对值
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeChange {
+    Absolute(f32),
+    Relative(f32),
+}
+
+impl SizeChange {
+    pub fn apply(self, base: f32) -> f32 {
+        match self {
+            SizeChange::Absolute(size) => size,
+            SizeChange::Relative(delta) => (base + delta).max(1.0),
+        }
+    }
+}
+
+/// 打字速度：`{cps=10}` 为每秒字数，`{cps=*2}` 为默认速度的倍数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Speed {
+    Absolute(f32),
+    Multiplier(f32),
+}
+
+impl Speed {
+    pub fn apply(self, base: f32) -> f32 {
+        match self {
+            Speed::Absolute(cps) => cps,
+            Speed::Multiplier(factor) => base * factor,
+        }
+    }
+}
+
+/// 一段文字的样式
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpanStyle {
+    /// sRGBA，取值 0.0 ~ 1.0
+    pub color: Option<[f32; 4]>,
+    pub bold: bool,
+    pub italic: bool,
+    pub size: Option<SizeChange>,
+    pub speed: Option<Speed>,
+}
+
+/// 样式相同的一段连续文字
+#[derive(Debug, Clone)]
+pub struct MarkupRun {
+    pub text: String,
+    pub style: SpanStyle,
+}
+
+/// `{w}` 暂停：在第 `at` 个字符之前停下，seconds 为 None 时等待点击
+#[derive(Debug, Clone, Copy)]
+pub struct Pause {
+    pub at: usize,
+    pub seconds: Option<f32>,
+}
+
+/// 解析后的对话文本
+#[derive(Debug, Clone, Default)]
+pub struct Markup {
+    pub runs: Vec<MarkupRun>,
+    pub pauses: Vec<Pause>,
+    /// `{nw}`：显示完毕后不等待点击，直接进入下一行
+    pub no_wait: bool,
+}
+
+impl Markup {
+    /// 去掉标签后的纯文本（用于历史记录、存档摘要等）
+    pub fn plain_text(&self) -> String {
+        self.runs.iter().map(|run| run.text.as_str()).collect()
+    }
+
+    /// 字符总数
+    pub fn char_count(&self) -> usize {
+        self.runs.iter().map(|run| run.text.chars().count()).sum()
+    }
+
+    /// 第 index 个字符的打字速度
+    pub fn speed_at(&self, index: usize) -> Option<Speed> {
+        let mut start = 0;
+        for run in &self.runs {
+            let len = run.text.chars().count();
+            if index < start + len {
+                return run.style.speed;
+            }
+            start += len;
+        }
+        None
+    }
+
+    fn push_text(&mut self, text: &str, style: &SpanStyle) {
+        if text.is_empty() {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some(run) if run.style == *style => run.text.push_str(text),
+            _ => self.runs.push(MarkupRun {
+                text: text.to_string(),
+                style: style.clone(),
+            }),
+        }
+    }
+}
+
+/// 解析对话文本中的标签和变量占位符
+///
+/// 标签优先于同名变量；`{{` / `}}` 输出字面的花括号；无法识别的内容、参数无效的标签（如 `{w=abc}`）原样保留
+pub fn parse_markup(text: &str, variables: &HashMap<String, VariableValue>) -> Markup {
+    let mut markup = Markup::default();
+    let mut style = SpanStyle::default();
+    // 已打开的标签及打开前的样式
+    let mut open_tags: Vec<(String, SpanStyle)> = Vec::new();
+    let mut rest = text;
+
+    while let Some(index) = rest.find(['{', '}']) {
+        markup.push_text(&rest[..index], &style);
+        let tail = &rest[index..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            markup.push_text(&tail[..1], &style);
+            rest = &tail[2..];
+            continue;
+        }
+        if tail.starts_with('}') {
+            markup.push_text("}", &style);
+            rest = &tail[1..];
+            continue;
+        }
+
+        let Some(end) = tail.find('}') else {
+            markup.push_text(tail, &style);
+            return markup;
+        };
+        let inner = tail[1..end].trim();
+        rest = &tail[end + 1..];
+
+        // 结束标签：恢复到对应标签打开前的样式
+        if let Some(name) = inner.strip_prefix('/') {
+            match open_tags.iter().rposition(|(open, _)| open == name) {
+                Some(position) => {
+                    style = open_tags[position].1.clone();
+                    open_tags.truncate(position);
+                }
+                None => eprintln!("文本标签 {{/{}}} 没有对应的开始标签", name),
+            }
+            continue;
+        }
+
+        let (name, value) = match inner.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (inner, None),
+        };
+        let previous = style.clone();
+        let handled = match (name, value) {
+            ("b", None) => {
+                style.bold = true;
+                true
+            }
+            ("i", None) => {
+                style.italic = true;
+                true
+            }
+            ("color", Some(value)) => {
+                style.color = parse_color(value);
+                style.color.is_some()
+            }
+            ("size", Some(value)) => {
+                style.size = parse_size(value);
+                style.size.is_some()
+            }
+            ("cps", Some(value)) => {
+                style.speed = parse_speed(value);
+                style.speed.is_some()
+            }
+            ("w", None) => {
+                markup.pauses.push(Pause {
+                    at: markup.char_count(),
+                    seconds: None,
+                });
+                continue;
+            }
+            ("w", Some(value)) => match parse_non_negative(value) {
+                Some(seconds) => {
+                    markup.pauses.push(Pause {
+                        at: markup.char_count(),
+                        seconds: Some(seconds),
+                    });
+                    continue;
+                }
+                None => false,
+            },
+            ("nw", None) => {
+                markup.no_wait = true;
+                continue;
+            }
+            _ => false,
+        };
+
+        if handled {
+            open_tags.push((name.to_string(), previous));
+            continue;
+        }
+        style = previous;
+        if let Some(value) = format_placeholder(inner, variables) {
+            markup.push_text(&value, &style);
+        } else {
+            markup.push_text(&tail[..=end], &style);
+        }
+    }
+
+    markup.push_text(rest, &style);
+    markup
+}
+
+/// 解析 #rgb / #rgba / #rrggbb / #rrggbbaa，返回 sRGBA（0.0 ~ 1.0）
+pub fn parse_color(value: &str) -> Option<[f32; 4]> {
+    let hex = value.strip_prefix('#')?;
+    let digits: Vec<u8> = hex
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits.iter().map(|d| d * 17).collect(),
+        6 | 8 => digits.chunks(2).map(|pair| pair[0] * 16 + pair[1]).collect(),
+        _ => return None,
+    };
+    let channel = |index: usize| channels.get(index).map_or(1.0, |c| *c as f32 / 255.0);
+    Some([channel(0), channel(1), channel(2), channel(3)])
+}
+
+// nan / inf 视为无效
+fn parse_number(value: &str) -> Option<f32> {
+    value.parse::<f32>().ok().filter(|number| number.is_finite())
+}
+
+// 暂停的秒数、打字速度不能为负
+fn parse_non_negative(value: &str) -> Option<f32> {
+    parse_number(value).filter(|number| *number >= 0.0)
+}
+
+fn parse_size(value: &str) -> Option<SizeChange> {
+    if value.starts_with('+') || value.starts_with('-') {
+        parse_number(value).map(SizeChange::Relative)
+    } else {
+        parse_number(value).map(SizeChange::Absolute)
+    }
+}
+
+fn parse_speed(value: &str) -> Option<Speed> {
+    match value.strip_prefix('*') {
+        Some(factor) => parse_non_negative(factor.trim()).map(Speed::Multiplier),
+        None => parse_non_negative(value).map(Speed::Absolute),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Markup {
+        let variables = HashMap::from([(
+            "player_name".to_string(),
+            VariableValue::String("主角".to_string()),
+        )]);
+        parse_markup(text, &variables)
+    }
+
+    #[test]
+    fn nested_tags_restore_the_outer_style() {
+        let markup = parse("普通{b}粗{color=#f00}红{/color}粗{/b}普通");
+        let styles: Vec<(&str, bool, Option<[f32; 4]>)> = markup
+            .runs
+            .iter()
+            .map(|run| (run.text.as_str(), run.style.bold, run.style.color))
+            .collect();
+        let red = Some([1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(
+            styles,
+            vec![
+                ("普通", false, None),
+                ("粗", true, None),
+                ("红", true, red),
+                ("粗", true, None),
+                ("普通", false, None),
+            ]
+        );
+        assert_eq!(markup.plain_text(), "普通粗红粗普通");
+    }
+
+    #[test]
+    fn closing_an_outer_tag_closes_inner_ones() {
+        let markup = parse("{i}{size=+4}大{/i}小");
+        assert_eq!(markup.runs.len(), 2);
+        assert_eq!(markup.runs[0].style.size, Some(SizeChange::Relative(4.0)));
+        assert!(markup.runs[0].style.italic);
+        assert_eq!(markup.runs[1].style, SpanStyle::default());
+    }
+
+    #[test]
+    fn pauses_and_no_wait() {
+        let markup = parse("等一下{w=0.5}再{w}继续{nw}");
+        let pauses: Vec<(usize, Option<f32>)> = markup
+            .pauses
+            .iter()
+            .map(|pause| (pause.at, pause.seconds))
+            .collect();
+        assert_eq!(pauses, vec![(3, Some(0.5)), (4, None)]);
+        assert!(markup.no_wait);
+        assert_eq!(markup.plain_text(), "等一下再继续");
+    }
+
+    #[test]
+    fn speed_applies_to_its_run() {
+        let markup = parse("快{cps=*2}慢慢{/cps}快");
+        assert_eq!(markup.speed_at(0), None);
+        assert_eq!(markup.speed_at(2), Some(Speed::Multiplier(2.0)));
+        assert_eq!(markup.speed_at(3), None);
+        assert_eq!(Speed::Multiplier(2.0).apply(30.0), 60.0);
+        assert_eq!(SizeChange::Relative(-40.0).apply(24.0), 1.0);
+    }
+
+    #[test]
+    fn variables_braces_and_unknown_tags() {
+        assert_eq!(parse("你好，{player_name}").plain_text(), "你好，主角");
+        assert_eq!(parse("{{b}} 与 {missing}").plain_text(), "{b} 与 {missing}");
+        assert_eq!(parse("没有闭合 {b").plain_text(), "没有闭合 {b");
+        assert_eq!(parse("多余的{/b}结束").plain_text(), "多余的结束");
+    }
+
+    #[test]
+    fn invalid_values_are_kept_as_text() {
+        for tag in [
+            "{w=abc}",
+            "{w=-1}",
+            "{w=inf}",
+            "{size=nan}",
+            "{cps=-5}",
+            "{cps=*inf}",
+            "{color=red}",
+        ] {
+            let markup = parse(tag);
+            assert_eq!(markup.plain_text(), tag);
+            assert!(markup.pauses.is_empty(), "{}", tag);
+            assert_eq!(markup.runs[0].style, SpanStyle::default(), "{}", tag);
+        }
+    }
+
+    #[test]
+    fn color_formats() {
+        assert_eq!(parse_color("#fff"), Some([1.0, 1.0, 1.0, 1.0]));
+        assert_eq!(parse_color("#00000080").map(|c| c[3]), Some(128.0 / 255.0));
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("fff"), None);
+    }
+}
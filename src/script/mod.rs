@@ -0,0 +1,124 @@
+// src/script/mod.rs
+// 对话脚本：数据结构、加载，以及变量条件与赋值
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::config::{MainConfig, VariableValue};
+
+pub mod dialogue;
+pub mod expr;
+pub mod interpolate;
+pub mod loader;
+pub mod markup;
+
+pub use dialogue::{Choice, Dialogue};
+pub use expr::{is_truthy, parse_expression};
+pub use interpolate::interpolate;
+pub use loader::{Script, load_script};
+pub use markup::{Markup, parse_markup};
+
+#[derive(Debug, Error)]
+pub enum ScriptError {
+    #[error("表达式语法错误: {0}")]
+    Syntax(String),
+    #[error("表达式类型错误: {0}")]
+    Type(String),
+}
+
+/// 角色的显示名称：角色表中的 name（替换变量后）；不在角色表中时显示 character 本身
+pub fn character_name(
+    config: &MainConfig,
+    character: &str,
+    variables: &HashMap<String, VariableValue>,
+) -> String {
+    let name = config
+        .get_character(character)
+        .and_then(|entry| entry.name.as_deref())
+        .unwrap_or(character);
+    interpolate(name, variables)
+}
+
+/// 角色的语音标签：角色表中的 voice，未设置时为角色 id
+pub fn voice_tag<'a>(config: &'a MainConfig, character: &'a str) -> &'a str {
+    config
+        .get_character(character)
+        .and_then(|entry| entry.voice.as_deref())
+        .unwrap_or(character)
+}
+
+/// 语音文件路径（相对 assets 目录）：只写文件名且角色设置了语音标签时位于 `voice/<标签>/`
+pub fn voice_path(config: &MainConfig, character: &str, voice: &str) -> String {
+    let voice = voice.trim_start_matches("assets/");
+    let tag = config
+        .get_character(character)
+        .and_then(|entry| entry.voice.as_deref());
+    match tag {
+        Some(tag) if !voice.contains('/') => format!("voice/{}/{}", tag, voice),
+        _ => voice.to_string(),
+    }
+}
+
+/// 判断 `if:` 条件是否成立
+pub fn evaluate_condition(
+    condition: &str,
+    variables: &HashMap<String, VariableValue>,
+) -> Result<bool, ScriptError> {
+    let value = parse_expression(condition)?.evaluate(variables)?;
+    Ok(is_truthy(&value))
+}
+
+/// 执行一条 `set:` 赋值
+///
+/// - `"+1"` / `"-2"`：在原有数值上增减
+/// - `"= affection_points * 2"`：以 `=` 开头时按表达式求值
+/// - 其他值直接赋值
+pub fn apply_set(
+    variables: &mut HashMap<String, VariableValue>,
+    name: &str,
+    value: &VariableValue,
+) -> Result<(), ScriptError> {
+    let new_value = match value {
+        VariableValue::String(s) => {
+            let s = s.trim();
+            if let Some(source) = s.strip_prefix('=') {
+                parse_expression(source)?.evaluate(variables)?
+            } else if let Some(delta) = relative_delta(s) {
+                let current = variables
+                    .get(name)
+                    .and_then(VariableValue::as_number)
+                    .unwrap_or(0.0);
+                VariableValue::Number(current + delta)
+            } else {
+                value.clone()
+            }
+        }
+        other => other.clone(),
+    };
+    variables.insert(name.to_string(), new_value);
+    Ok(())
+}
+
+// 解析 "+1"、"-0.5" 这类相对增量
+fn relative_delta(s: &str) -> Option<f64> {
+    let (sign, rest) = if let Some(rest) = s.strip_prefix('+') {
+        (1.0, rest)
+    } else if let Some(rest) = s.strip_prefix('-') {
+        (-1.0, rest)
+    } else {
+        return None;
+    };
+    rest.trim().parse::<f64>().ok().map(|n| sign * n)
+}
+
+/// 对话行的稳定标识，用于记录已读文本
+///
+/// 由说话人和文本内容计算（FNV-1a），在脚本中插入或删除其他行时保持不变
+pub fn line_key(character: &str, text: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in character.bytes().chain([0u8]).chain(text.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
@@ -433,7 +433,7 @@ pub fn update_window_state(
 ) {
     for window in q_windows.iter() {
         let new_resolution = Vec2::new(window.resolution.width(), window.resolution.height());
-        let new_fullscreen = matches!(window.mode, bevy::window::WindowMode::Fullscreen(..));
+        let new_fullscreen = !matches!(window.mode, bevy::window::WindowMode::Windowed);
         
         if window_state.resolution != new_resolution || window_state.is_fullscreen != new_fullscreen {
             window_state.resolution = new_resolution;
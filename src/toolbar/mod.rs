@@ -1,4 +1,5 @@
 use crate::GameScene;
+use crate::locale::Locale;
 use bevy::prelude::*; // 导入 GameScene
 
 // 添加事件定义
@@ -11,6 +12,18 @@ pub struct RollbackEventMessage; // 新增回退事件
 #[derive(Message)]
 pub struct ToggleAutoPlayEventMessage; // 新增自动播放事件
 
+#[derive(Message)]
+pub struct ToggleSkipEventMessage; // 切换快进模式
+
+#[derive(Message)]
+pub struct OpenHistoryEventMessage; // 打开历史记录
+
+#[derive(Message)]
+pub struct OpenSaveMenuEventMessage; // 打开存档槽选择
+
+#[derive(Message)]
+pub struct OpenLoadMenuEventMessage; // 打开读档槽选择
+
 #[derive(Component)]
 pub struct ToolbarContainer;
 
@@ -32,6 +45,10 @@ impl Plugin for ToolbarPlugin {
         app.add_message::<ToggleMenuEventMessage>() // 添加事件
             .add_message::<RollbackEventMessage>() // 注册回退事件
             .add_message::<ToggleAutoPlayEventMessage>() // 注册自动播放事件
+            .add_message::<ToggleSkipEventMessage>()
+            .add_message::<OpenHistoryEventMessage>()
+            .add_message::<OpenSaveMenuEventMessage>()
+            .add_message::<OpenLoadMenuEventMessage>()
             .add_systems(OnEnter(GameScene::Game), setup_toolbar) // 只在进入游戏状态时创建
             .add_systems(OnExit(GameScene::Game), cleanup_toolbar) // 离开游戏状态时清理
             .add_systems(
@@ -45,7 +62,7 @@ impl Plugin for ToolbarPlugin {
     }
 }
 
-fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>, locale: Res<Locale>) {
     let font_handle = asset_server.load("fonts/SarasaFixedHC-Light.ttf");
 
     commands
@@ -89,7 +106,7 @@ fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ))
                 .with_children(|parent| {
                     parent.spawn((
-                        Text::new("回退"),
+                        locale.ui_text("toolbar.rollback"),
                         TextFont {
                             font: font_handle.clone(),
                             font_size: 14.0, // 稍小的字体
@@ -110,7 +127,7 @@ fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ))
                 .with_children(|parent| {
                     parent.spawn((
-                        Text::new("历史"),
+                        locale.ui_text("toolbar.history"),
                         TextFont {
                             font: font_handle.clone(),
                             font_size: 14.0,
@@ -131,7 +148,7 @@ fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ))
                 .with_children(|parent| {
                     parent.spawn((
-                        Text::new("快进"),
+                        locale.ui_text("toolbar.skip"),
                         TextFont {
                             font: font_handle.clone(),
                             font_size: 14.0,
@@ -152,7 +169,7 @@ fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ))
                 .with_children(|parent| {
                     parent.spawn((
-                        Text::new("自动"),
+                        locale.ui_text("toolbar.auto"),
                         TextFont {
                             font: font_handle.clone(),
                             font_size: 14.0,
@@ -173,7 +190,7 @@ fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ))
                 .with_children(|parent| {
                     parent.spawn((
-                        Text::new("存档"),
+                        locale.ui_text("toolbar.save"),
                         TextFont {
                             font: font_handle.clone(),
                             font_size: 14.0,
@@ -194,7 +211,7 @@ fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ))
                 .with_children(|parent| {
                     parent.spawn((
-                        Text::new("读档"),
+                        locale.ui_text("toolbar.load"),
                         TextFont {
                             font: font_handle.clone(),
                             font_size: 14.0,
@@ -215,7 +232,7 @@ fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ))
                 .with_children(|parent| {
                     parent.spawn((
-                        Text::new("设置"),
+                        locale.ui_text("toolbar.settings"),
                         TextFont {
                             font: font_handle.clone(),
                             font_size: 14.0,
@@ -241,6 +258,10 @@ fn handle_toolbar_buttons(
     mut toggle_menu_event: MessageWriter<ToggleMenuEventMessage>,
     mut rollback_event: MessageWriter<RollbackEventMessage>, // 添加回退事件发送器
     mut toggle_auto_play_event: MessageWriter<ToggleAutoPlayEventMessage>, // 添加自动播放事件发送器
+    mut toggle_skip_event: MessageWriter<ToggleSkipEventMessage>,
+    mut open_history_event: MessageWriter<OpenHistoryEventMessage>,
+    mut open_save_menu_event: MessageWriter<OpenSaveMenuEventMessage>,
+    mut open_load_menu_event: MessageWriter<OpenLoadMenuEventMessage>,
 ) {
     for (interaction, button_type, mut color) in &mut interaction_query {
         match *interaction {
@@ -257,10 +278,12 @@ fn handle_toolbar_buttons(
                         rollback_event.write(RollbackEventMessage); // 发送回退事件
                     }
                     ToolbarButton::History => {
-                        println!("历史按钮正在开发中");
+                        println!("历史按钮被点击");
+                        open_history_event.write(OpenHistoryEventMessage);
                     }
                     ToolbarButton::Skip => {
                         println!("快进按钮被点击");
+                        toggle_skip_event.write(ToggleSkipEventMessage);
                     }
                     ToolbarButton::Auto => {
                         println!("自动按钮被点击");
@@ -268,9 +291,11 @@ fn handle_toolbar_buttons(
                     }
                     ToolbarButton::Save => {
                         println!("存档按钮被点击");
+                        open_save_menu_event.write(OpenSaveMenuEventMessage);
                     }
                     ToolbarButton::Load => {
                         println!("读档按钮被点击");
+                        open_load_menu_event.write(OpenLoadMenuEventMessage);
                     }
                 }
             }
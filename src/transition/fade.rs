@@ -1,17 +1,30 @@
 // src/transition/fade.rs
 use bevy::prelude::*;
 
+use crate::easing::Easing;
+
 #[derive(Component)]
 pub struct FadeOverlay {
     pub duration: f32,
     pub elapsed: f32,
     pub fade_type: FadeType,
+    pub easing: Easing,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum FadeType {
     FadeIn,   // 从黑到透明
     FadeOut,  // 从透明到黑
+    Flash(Color), // 从透明到指定颜色，再回到透明
+}
+
+impl FadeType {
+    fn color(self, alpha: f32) -> Color {
+        match self {
+            FadeType::Flash(color) => color.with_alpha(alpha),
+            FadeType::FadeIn | FadeType::FadeOut => Color::srgba(0.0, 0.0, 0.0, alpha),
+        }
+    }
 }
 
 // 创建渐变覆盖层
@@ -19,10 +32,11 @@ pub fn create_fade_overlay(
     commands: &mut Commands,
     fade_type: FadeType,
     duration: f32,
+    easing: Easing,
 ) -> Entity {
     let initial_alpha = match fade_type {
         FadeType::FadeIn => 1.0,  // 开始时不透明
-        FadeType::FadeOut => 0.0, // 开始时透明
+        FadeType::FadeOut | FadeType::Flash(_) => 0.0, // 开始时透明
     };
 
     commands
@@ -35,12 +49,13 @@ pub fn create_fade_overlay(
                 left: Val::Px(0.0),
                 ..default()
             },
-            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, initial_alpha)),
+            BackgroundColor(fade_type.color(initial_alpha)),
             ZIndex(1000), // 确保在最顶层
             FadeOverlay {
                 duration,
                 elapsed: 0.0,
                 fade_type,
+                easing,
             },
         ))
         .id()
@@ -48,11 +63,11 @@ pub fn create_fade_overlay(
 
 // 便捷函数
 pub fn fade_in(commands: &mut Commands, duration: f32) -> Entity {
-    create_fade_overlay(commands, FadeType::FadeIn, duration)
+    create_fade_overlay(commands, FadeType::FadeIn, duration, Easing::Linear)
 }
 
 pub fn fade_out(commands: &mut Commands, duration: f32) -> Entity {
-    create_fade_overlay(commands, FadeType::FadeOut, duration)
+    create_fade_overlay(commands, FadeType::FadeOut, duration, Easing::Linear)
 }
 
 // 渐变系统
@@ -63,14 +78,15 @@ pub fn fade_system(
     for (mut fade, mut background_color) in query.iter_mut() {
         fade.elapsed += time.delta_secs(); // 修改这里：delta_seconds() -> delta_secs()
         
-        let progress = (fade.elapsed / fade.duration).clamp(0.0, 1.0);
+        let progress = fade.easing.apply(fade.elapsed / fade.duration);
         
         let alpha = match fade.fade_type {
             FadeType::FadeIn => 1.0 - progress,  // 从1到0
             FadeType::FadeOut => progress,       // 从0到1
+            FadeType::Flash(_) => 1.0 - (progress * 2.0 - 1.0).abs(), // 从0到1再到0
         };
         
-        *background_color = BackgroundColor(Color::srgba(0.0, 0.0, 0.0, alpha));
+        *background_color = BackgroundColor(fade.fade_type.color(alpha));
     }
 }
 
@@ -0,0 +1,245 @@
+// src/transition/kind.rs
+// 转场的写法：`名称 [方向 | 遮罩图片] [秒数] [缓动]`，例如 `dissolve 0.8`、`wipe left 1.0 ease_out_cubic`、`imagedissolve gui/mask.png 1.5`
+use crate::easing::{Easing, split_words};
+
+/// 擦除、推移转场的方向（新画面移动的方向）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "left" => Some(Direction::Left),
+            "right" => Some(Direction::Right),
+            "up" => Some(Direction::Up),
+            "down" => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    /// 画面坐标中的单位向量（y 轴向上）
+    pub fn vector(self) -> [f32; 2] {
+        match self {
+            Direction::Left => [-1.0, 0.0],
+            Direction::Right => [1.0, 0.0],
+            Direction::Up => [0.0, 1.0],
+            Direction::Down => [0.0, -1.0],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransitionKind {
+    /// 直接切换
+    None,
+    /// 新旧画面交叉溶解
+    Dissolve,
+    /// 经过黑屏
+    Fade,
+    /// 新画面沿方向逐渐擦出
+    Wipe(Direction),
+    /// 新画面沿方向把旧画面推出
+    Push(Direction),
+    /// 新画面从中心放大出现，旧画面缩小消失
+    Zoom,
+    /// 旧画面马赛克化后，新画面由马赛克还原
+    Pixellate,
+    /// 闪白后切换
+    Flash,
+    /// 按灰度遮罩图片溶解，亮的部分先出现；内容为相对 assets 的路径
+    ImageDissolve(String),
+}
+
+/// 背景、角色的转场
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    pub duration: f32,
+    /// 省略时溶解使用 renpy 曲线，其余为线性
+    pub easing: Option<Easing>,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Transition {
+            kind: TransitionKind::Dissolve,
+            duration: Transition::DEFAULT_DURATION,
+            easing: None,
+        }
+    }
+}
+
+impl Transition {
+    /// 省略秒数时的转场时长
+    pub const DEFAULT_DURATION: f32 = 0.8;
+
+    /// 转场的名称（`slide` 与 `push` 相同）
+    pub const NAMES: [&'static str; 9] = [
+        "dissolve",
+        "fade",
+        "wipe",
+        "push",
+        "zoom",
+        "pixellate",
+        "flash",
+        "imagedissolve",
+        "none",
+    ];
+
+    /// 直接切换
+    pub const NONE: Transition = Transition {
+        kind: TransitionKind::None,
+        duration: 0.0,
+        easing: None,
+    };
+
+    /// 解析 `名称 [方向 | 遮罩图片] [秒数] [缓动]`；写法无效时返回 None
+    ///
+    /// wipe / push 省略方向时为 left，imagedissolve 必须写遮罩图片
+    pub fn parse(source: &str) -> Option<Self> {
+        let words = split_words(source);
+        let mut parts = words.iter().map(String::as_str);
+        let name = parts.next()?;
+        let mut direction = None;
+        let mut mask = None;
+        let mut duration = None;
+        let mut easing = None;
+        for part in parts {
+            if duration.is_none() {
+                if let Ok(seconds) = part.parse::<f32>() {
+                    // nan / inf 也能解析为 f32，这样的转场永远不会结束
+                    if seconds < 0.0 || !seconds.is_finite() {
+                        return None;
+                    }
+                    duration = Some(seconds);
+                    continue;
+                }
+            }
+            if easing.is_none() {
+                if let Some(parsed) = Easing::parse(part) {
+                    easing = Some(parsed);
+                    continue;
+                }
+            }
+            match name {
+                "wipe" | "push" | "slide" if direction.is_none() => {
+                    direction = Some(Direction::parse(part)?);
+                }
+                "imagedissolve" if mask.is_none() => mask = Some(part.to_string()),
+                _ => return None,
+            }
+        }
+        let direction = direction.unwrap_or(Direction::Left);
+        let kind = match name {
+            "none" => return Some(Transition::NONE),
+            "dissolve" => TransitionKind::Dissolve,
+            "fade" => TransitionKind::Fade,
+            "wipe" => TransitionKind::Wipe(direction),
+            "push" | "slide" => TransitionKind::Push(direction),
+            "zoom" => TransitionKind::Zoom,
+            "pixellate" => TransitionKind::Pixellate,
+            "flash" => TransitionKind::Flash,
+            "imagedissolve" => TransitionKind::ImageDissolve(mask?),
+            _ => return None,
+        };
+        Some(Transition {
+            kind,
+            duration: duration.unwrap_or(Self::DEFAULT_DURATION),
+            easing,
+        })
+    }
+
+    /// 解析脚本中写的转场，写法无效时提示并使用默认转场
+    pub fn parse_or_default(source: &str) -> Self {
+        Self::parse(source).unwrap_or_else(|| {
+            eprintln!(
+                "无效的转场 '{}'（可用: {}；缓动: {}），使用默认转场",
+                source,
+                Self::NAMES.join(" / "),
+                Easing::NAMES.join(" / ")
+            );
+            Self::default()
+        })
+    }
+
+    /// 不需要播放动画，直接切换
+    pub fn is_instant(&self) -> bool {
+        self.kind == TransitionKind::None || self.duration <= 0.0
+    }
+
+    /// 实际使用的缓动
+    pub fn easing(&self) -> Easing {
+        self.easing.unwrap_or(match self.kind {
+            TransitionKind::Dissolve => Easing::RenPy,
+            _ => Easing::Linear,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::easing::{Curve, EaseMode};
+
+    #[test]
+    fn direction_mask_and_easing_in_any_order() {
+        let wipe = Transition::parse("wipe left 1.0 ease_out_cubic").unwrap();
+        assert_eq!(wipe.kind, TransitionKind::Wipe(Direction::Left));
+        assert_eq!(wipe.duration, 1.0);
+        assert_eq!(wipe.easing, Some(Easing::Curve(Curve::Cubic, EaseMode::Out)));
+        assert_eq!(Transition::parse("wipe ease_out_cubic 1.0 left"), Some(wipe));
+
+        assert_eq!(
+            Transition::parse("imagedissolve gui/mask.png 1.5").map(|t| t.kind),
+            Some(TransitionKind::ImageDissolve("gui/mask.png".to_string()))
+        );
+        // slide 与 push 相同，省略方向时为 left
+        assert_eq!(
+            Transition::parse("slide up").map(|t| t.kind),
+            Some(TransitionKind::Push(Direction::Up))
+        );
+        assert_eq!(
+            Transition::parse("push").map(|t| t.kind),
+            Some(TransitionKind::Push(Direction::Left))
+        );
+    }
+
+    #[test]
+    fn dissolve_defaults_to_renpy_curve() {
+        let dissolve = Transition::parse("dissolve").unwrap();
+        assert_eq!(dissolve.duration, Transition::DEFAULT_DURATION);
+        assert_eq!(dissolve.easing(), Easing::RenPy);
+        assert_eq!(Transition::parse("fade 0.5").unwrap().easing(), Easing::Linear);
+    }
+
+    #[test]
+    fn none_and_zero_duration_are_instant() {
+        assert_eq!(Transition::parse("none 2.0"), Some(Transition::NONE));
+        assert!(Transition::parse("dissolve 0").unwrap().is_instant());
+        assert!(!Transition::default().is_instant());
+    }
+
+    #[test]
+    fn rejects_negative_and_non_finite_durations() {
+        assert_eq!(Transition::parse("dissolve -1"), None);
+        assert_eq!(Transition::parse("dissolve nan"), None);
+        assert_eq!(Transition::parse("fade inf"), None);
+    }
+
+    #[test]
+    fn rejects_unknown_names_and_extra_words() {
+        assert_eq!(Transition::parse(""), None);
+        assert_eq!(Transition::parse("spin 1.0"), None);
+        assert_eq!(Transition::parse("wipe sideways"), None);
+        assert_eq!(Transition::parse("wipe left right"), None);
+        assert_eq!(Transition::parse("fade 1.0 2.0"), None);
+        // 遮罩图片只有 imagedissolve 能写，且必须写
+        assert_eq!(Transition::parse("dissolve gui/mask.png"), None);
+        assert_eq!(Transition::parse("imagedissolve 1.0"), None);
+    }
+}
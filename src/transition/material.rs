@@ -0,0 +1,55 @@
+// src/transition/material.rs
+// 擦除、马赛克、遮罩溶解转场的材质：转场期间代替精灵绘制图片（assets/shaders/transition.wgsl）
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderType},
+    shader::ShaderRef,
+    sprite_render::{AlphaMode2d, Material2d},
+};
+
+pub const MODE_WIPE: u32 = 0;
+pub const MODE_PIXELLATE: u32 = 1;
+pub const MODE_IMAGE_DISSOLVE: u32 = 2;
+
+/// 着色器参数，字段顺序与 transition.wgsl 中的 TransitionParams 一致
+#[derive(ShaderType, Debug, Clone, Copy, Default)]
+pub struct TransitionParams {
+    /// 0.0 ~ 1.0
+    pub progress: f32,
+    pub mode: u32,
+    /// 1 为旧画面：新画面出现的部分随之消失
+    pub outgoing: u32,
+    /// 马赛克块的边长（纹素）
+    pub block: f32,
+    /// 擦除方向（uv 坐标，y 轴向下）
+    pub direction: Vec2,
+    /// 图片尺寸（纹素）
+    pub size: Vec2,
+    /// 擦除边缘、遮罩溶解的柔和程度
+    pub softness: f32,
+    /// 马赛克模式下的不透明度
+    pub opacity: f32,
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct TransitionMaterial {
+    #[uniform(0)]
+    pub params: TransitionParams,
+    #[texture(1)]
+    #[sampler(2)]
+    pub image: Handle<Image>,
+    /// imagedissolve 的灰度遮罩图片
+    #[texture(3)]
+    #[sampler(4)]
+    pub mask: Option<Handle<Image>>,
+}
+
+impl Material2d for TransitionMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/transition.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
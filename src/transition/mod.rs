@@ -1,6 +1,11 @@
 // src/transition/mod.rs
 mod fade;
+mod kind;
+mod material;
 mod plugin;
+mod sprite;
 
 pub use fade::*;
-pub use plugin::*;
\ No newline at end of file
+pub use kind::*;
+pub use plugin::*;
+pub use sprite::*;
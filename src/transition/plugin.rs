@@ -1,11 +1,54 @@
 // src/transition/plugin.rs
-use bevy::prelude::*;
-use super::fade::{fade_system, cleanup_completed_fades};
+use bevy::{prelude::*, sprite_render::Material2dPlugin};
+use super::fade::{FadeOverlay, fade_system, cleanup_completed_fades};
+use super::material::TransitionMaterial;
+use super::sprite::{TransitionSettings, update_sprite_transitions};
+use crate::dissolve::{
+    RenpyDissolve, RenpyDissolvePlugin, RenpyDissolveTransition, renpy_dissolve_system,
+    renpy_dissolve_transition_system,
+};
 
 pub struct TransitionPlugin;
 
 impl Plugin for TransitionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (fade_system, cleanup_completed_fades));
+        if !app.is_plugin_added::<RenpyDissolvePlugin>() {
+            app.add_plugins(RenpyDissolvePlugin);
+        }
+        app.add_plugins(Material2dPlugin::<TransitionMaterial>::default())
+            .init_resource::<TransitionSettings>()
+            .add_systems(
+                Update,
+                (
+                    complete_instant_transitions
+                        .before(fade_system)
+                        .before(renpy_dissolve_system)
+                        .before(renpy_dissolve_transition_system),
+                    fade_system,
+                    cleanup_completed_fades,
+                    update_sprite_transitions,
+                ),
+            );
     }
-}
\ No newline at end of file
+}
+
+// 快进或忽略转场时，让进行中的渐变与溶解在这一帧完成
+fn complete_instant_transitions(
+    settings: Res<TransitionSettings>,
+    mut fades: Query<&mut FadeOverlay>,
+    mut dissolves: Query<&mut RenpyDissolve>,
+    mut dissolve_transitions: Query<&mut RenpyDissolveTransition>,
+) {
+    if !settings.instant {
+        return;
+    }
+    for mut fade in fades.iter_mut() {
+        fade.elapsed = fade.duration;
+    }
+    for mut dissolve in dissolves.iter_mut() {
+        dissolve.elapsed = dissolve.duration;
+    }
+    for mut transition in dissolve_transitions.iter_mut() {
+        transition.elapsed = transition.duration;
+    }
+}
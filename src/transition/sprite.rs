@@ -0,0 +1,380 @@
+// src/transition/sprite.rs
+// 精灵的转场：切换背景、角色登场 / 退场时，旧精灵按转场消失（结束后移除），新精灵按转场出现
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use super::{
+    fade::{FadeOverlay, FadeType, create_fade_overlay},
+    kind::{Transition, TransitionKind},
+    material::{
+        MODE_IMAGE_DISSOLVE, MODE_PIXELLATE, MODE_WIPE, TransitionMaterial, TransitionParams,
+    },
+};
+use crate::{
+    dissolve::{RenpyDissolve, RenpyDissolveTransition},
+    easing::Easing,
+};
+
+/// 马赛克最粗时块的边长（纹素）
+const PIXELLATE_MAX_BLOCK: f32 = 32.0;
+
+/// 擦除边缘的柔和程度（占画面的比例）
+const WIPE_SOFTNESS: f32 = 0.05;
+
+/// 遮罩溶解的过渡范围（灰度）
+const IMAGE_DISSOLVE_RAMP: f32 = 0.1;
+
+/// 取不到窗口时按这个大小计算推移距离
+const FALLBACK_SCREEN_SIZE: Vec2 = Vec2::new(1400.0, 770.0);
+
+/// 转场设置
+#[derive(Resource, Default)]
+pub struct TransitionSettings {
+    /// 为 true 时所有转场立即完成（快进、忽略转场时）
+    pub instant: bool,
+}
+
+/// 正在转场的精灵（溶解由 dissolve 模块处理，不使用这个组件）
+#[derive(Component)]
+pub struct SpriteTransition {
+    kind: TransitionKind,
+    duration: f32,
+    easing: Easing,
+    elapsed: f32,
+    /// 旧精灵：转场结束后移除
+    outgoing: bool,
+    /// 转场开始时的位置与缩放，结束后恢复；转场中调整精灵位置时应修改这里
+    pub origin: Transform,
+    /// 转场开始时的颜色，结束后恢复
+    pub color: Color,
+    /// push 的位移
+    offset: Vec3,
+    /// 转场期间代替精灵绘制的网格（擦除、马赛克、遮罩溶解）
+    overlay: Option<(Entity, Handle<TransitionMaterial>)>,
+}
+
+impl SpriteTransition {
+    // 按进度更新位置、缩放与不透明度
+    fn apply(&self, progress: f32, sprite: &mut Sprite, transform: &mut Transform) {
+        match self.kind {
+            TransitionKind::Push(_) => {
+                // 新精灵从 -offset 移到原位，旧精灵从原位移到 offset
+                let shift = if self.outgoing { progress } else { progress - 1.0 };
+                transform.translation = self.origin.translation + self.offset * shift;
+            }
+            TransitionKind::Zoom => {
+                let shown = if self.outgoing { 1.0 - progress } else { progress };
+                transform.scale = self.origin.scale * shown.max(0.001);
+            }
+            TransitionKind::Flash => {
+                // 闪白最亮时切换
+                let visible = (progress < 0.5) == self.outgoing;
+                let alpha = if visible { self.color.alpha() } else { 0.0 };
+                sprite.color = self.color.with_alpha(alpha);
+            }
+            _ => {}
+        }
+    }
+
+    // 按进度更新着色器参数
+    fn apply_params(&self, progress: f32, params: &mut TransitionParams) {
+        params.progress = progress;
+        if self.kind == TransitionKind::Pixellate {
+            // 前半段旧画面的马赛克逐渐变粗，后半段新画面由粗变细
+            let (coarseness, visible) = if self.outgoing {
+                (progress * 2.0, progress < 0.5)
+            } else {
+                ((1.0 - progress) * 2.0, progress >= 0.5)
+            };
+            params.block = 1.0 + (PIXELLATE_MAX_BLOCK - 1.0) * coarseness.min(1.0);
+            params.opacity = if visible { 1.0 } else { 0.0 };
+        }
+    }
+}
+
+/// 开始转场：old 为要消失的精灵（转场结束后移除），new 为要出现的精灵
+///
+/// 角色登场时没有 old，退场时没有 new；快进或设置了忽略转场时直接切换
+pub fn start_transition(
+    commands: &mut Commands,
+    transition: Transition,
+    old: Option<Entity>,
+    new: Option<Entity>,
+) {
+    commands.queue(move |world: &mut World| begin_transition(world, transition, old, new));
+}
+
+/// 立即结束精灵上正在进行的转场：出现中的精灵恢复原样，消失中的精灵移除
+pub fn finish_transition(commands: &mut Commands, entity: Entity) {
+    commands.queue(move |world: &mut World| complete_transition(world, entity));
+}
+
+fn begin_transition(
+    world: &mut World,
+    transition: Transition,
+    old: Option<Entity>,
+    new: Option<Entity>,
+) {
+    let instant = world
+        .get_resource::<TransitionSettings>()
+        .is_some_and(|settings| settings.instant);
+    if instant || transition.is_instant() {
+        if let Some(old) = old {
+            despawn(world, old);
+        }
+        return;
+    }
+
+    let duration = transition.duration;
+    let easing = transition.easing();
+    match transition.kind {
+        TransitionKind::None => {}
+        TransitionKind::Dissolve => dissolve(world, duration, easing, old, new),
+        TransitionKind::Fade => {
+            // 直接切换，黑屏逐渐褪去
+            if let Some(old) = old {
+                despawn(world, old);
+            }
+            spawn_overlay(world, FadeType::FadeIn, duration, easing);
+        }
+        kind => {
+            if kind == TransitionKind::Flash {
+                spawn_overlay(world, FadeType::Flash(Color::WHITE), duration, easing);
+            }
+            for (entity, outgoing) in [(old, true), (new, false)] {
+                if let Some(entity) = entity {
+                    attach(world, entity, &kind, duration, easing, outgoing);
+                }
+            }
+        }
+    }
+}
+
+// 溶解使用 dissolve 模块：旧精灵带上 RenpyDissolveTransition，结束时随之移除
+fn dissolve(
+    world: &mut World,
+    duration: f32,
+    easing: Easing,
+    old: Option<Entity>,
+    new: Option<Entity>,
+) {
+    if let Some(old) = old {
+        if let Ok(mut entity) = world.get_entity_mut(old) {
+            entity.insert(RenpyDissolveTransition {
+                duration,
+                elapsed: 0.0,
+                old_entity: old,
+                new_entity: new.unwrap_or(Entity::PLACEHOLDER),
+                easing,
+                completed: false,
+            });
+        }
+    } else if let Some(new) = new {
+        if let Ok(mut entity) = world.get_entity_mut(new) {
+            entity.insert(RenpyDissolve::fade_in(duration).with_easing(easing));
+        }
+    }
+    if let Some(new) = new {
+        set_alpha(world, new, 0.0);
+    }
+}
+
+fn attach(
+    world: &mut World,
+    entity: Entity,
+    kind: &TransitionKind,
+    duration: f32,
+    easing: Easing,
+    outgoing: bool,
+) {
+    let Ok(entity_ref) = world.get_entity(entity) else {
+        return;
+    };
+    let (Some(sprite), Some(&origin)) = (entity_ref.get::<Sprite>(), entity_ref.get::<Transform>())
+    else {
+        return;
+    };
+    let image = sprite.image.clone();
+    let color = sprite.color;
+    let image_size = world
+        .resource::<Assets<Image>>()
+        .get(&image)
+        .map(|image| image.size_f32());
+    let size = sprite.custom_size.or(image_size).unwrap_or_default();
+
+    let mut transition = SpriteTransition {
+        kind: kind.clone(),
+        duration,
+        easing,
+        elapsed: 0.0,
+        outgoing,
+        origin,
+        color,
+        offset: Vec3::ZERO,
+        overlay: None,
+    };
+
+    match kind {
+        TransitionKind::Push(direction) => {
+            // 推出画面：位移取窗口与精灵中较大的一边
+            let screen = world
+                .query_filtered::<&Window, With<PrimaryWindow>>()
+                .single(world)
+                .map_or(FALLBACK_SCREEN_SIZE, |window| window.size());
+            let extent = screen.max(size * origin.scale.truncate());
+            let [x, y] = direction.vector();
+            transition.offset = Vec3::new(x * extent.x, y * extent.y, 0.0);
+        }
+        TransitionKind::Wipe(_) | TransitionKind::Pixellate | TransitionKind::ImageDissolve(_) => {
+            let Some(image_size) = image_size else {
+                // 图片尚未加载，无法按像素处理，改为溶解
+                let (old, new) = if outgoing { (Some(entity), None) } else { (None, Some(entity)) };
+                dissolve(world, duration, easing, old, new);
+                return;
+            };
+            let mut params = TransitionParams {
+                size: image_size,
+                block: 1.0,
+                opacity: 1.0,
+                outgoing: outgoing as u32,
+                ..default()
+            };
+            let mut mask = None;
+            match kind {
+                TransitionKind::Wipe(direction) => {
+                    let [x, y] = direction.vector();
+                    params.mode = MODE_WIPE;
+                    params.direction = Vec2::new(x, -y);
+                    params.softness = WIPE_SOFTNESS;
+                }
+                TransitionKind::ImageDissolve(path) => {
+                    params.mode = MODE_IMAGE_DISSOLVE;
+                    params.softness = IMAGE_DISSOLVE_RAMP;
+                    mask = Some(world.resource::<AssetServer>().load(path.clone()));
+                }
+                _ => params.mode = MODE_PIXELLATE,
+            }
+            transition.apply_params(0.0, &mut params);
+
+            let material = world
+                .resource_mut::<Assets<TransitionMaterial>>()
+                .add(TransitionMaterial { params, image, mask });
+            let mesh = world
+                .resource_mut::<Assets<Mesh>>()
+                .add(Rectangle::from_size(size));
+            let overlay = world
+                .spawn((
+                    Mesh2d(mesh),
+                    MeshMaterial2d(material.clone()),
+                    Transform::default(),
+                    ChildOf(entity),
+                ))
+                .id();
+            transition.overlay = Some((overlay, material));
+            // 转场期间由网格绘制，精灵本身隐藏
+            set_alpha(world, entity, 0.0);
+        }
+        _ => {}
+    }
+
+    let mut entity = world.entity_mut(entity);
+    if let Some(mut sprite) = entity.get_mut::<Sprite>() {
+        let mut transform = origin;
+        transition.apply(0.0, &mut sprite, &mut transform);
+        entity.insert(transform);
+    }
+    entity.insert(transition);
+}
+
+fn complete_transition(world: &mut World, entity: Entity) {
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if let Some(dissolve) = entity_mut.take::<RenpyDissolve>() {
+        if let Some(mut sprite) = entity_mut.get_mut::<Sprite>() {
+            sprite.color = sprite.color.with_alpha(dissolve.to_alpha);
+        }
+    }
+    if let Some(dissolve) = entity_mut.take::<RenpyDissolveTransition>() {
+        entity_mut.despawn();
+        set_alpha(world, dissolve.new_entity, 1.0);
+        return;
+    }
+    let Some(transition) = entity_mut.take::<SpriteTransition>() else {
+        return;
+    };
+    if transition.outgoing {
+        entity_mut.despawn();
+        return;
+    }
+    entity_mut.insert(transition.origin);
+    if let Some(mut sprite) = entity_mut.get_mut::<Sprite>() {
+        sprite.color = transition.color;
+    }
+    if let Some((overlay, _)) = transition.overlay {
+        despawn(world, overlay);
+    }
+}
+
+// 同一帧里多个精灵使用同一种转场时，只生成一个全屏遮罩
+fn spawn_overlay(world: &mut World, fade_type: FadeType, duration: f32, easing: Easing) {
+    let exists = world
+        .query::<&FadeOverlay>()
+        .iter(world)
+        .any(|overlay| overlay.fade_type == fade_type && overlay.elapsed == 0.0);
+    if !exists {
+        create_fade_overlay(&mut world.commands(), fade_type, duration, easing);
+        world.flush();
+    }
+}
+
+fn set_alpha(world: &mut World, entity: Entity, alpha: f32) {
+    if let Some(mut sprite) = world
+        .get_entity_mut(entity)
+        .ok()
+        .and_then(|entity| entity.into_mut::<Sprite>())
+    {
+        sprite.color = sprite.color.with_alpha(alpha);
+    }
+}
+
+fn despawn(world: &mut World, entity: Entity) {
+    if let Ok(entity) = world.get_entity_mut(entity) {
+        entity.despawn();
+    }
+}
+
+pub fn update_sprite_transitions(
+    time: Res<Time>,
+    settings: Res<TransitionSettings>,
+    mut commands: Commands,
+    mut transitions: Query<(Entity, &mut SpriteTransition, &mut Sprite, &mut Transform)>,
+    mut materials: ResMut<Assets<TransitionMaterial>>,
+) {
+    for (entity, mut transition, mut sprite, mut transform) in transitions.iter_mut() {
+        transition.elapsed += time.delta_secs();
+        if settings.instant {
+            transition.elapsed = transition.duration;
+        }
+        if transition.elapsed >= transition.duration {
+            if transition.outgoing {
+                commands.entity(entity).despawn();
+                continue;
+            }
+            *transform = transition.origin;
+            sprite.color = transition.color;
+            if let Some((overlay, _)) = &transition.overlay {
+                commands.entity(*overlay).despawn();
+            }
+            commands.entity(entity).remove::<SpriteTransition>();
+            continue;
+        }
+
+        let progress = transition.easing.apply(transition.elapsed / transition.duration);
+        transition.apply(progress, &mut sprite, &mut transform);
+        if let Some((_, material)) = &transition.overlay {
+            if let Some(material) = materials.get_mut(material) {
+                transition.apply_params(progress, &mut material.params);
+            }
+        }
+    }
+}